@@ -9,10 +9,22 @@ mod modules {
     pub mod creddefs;
     pub mod credentials;
     pub mod dids;
+    pub mod encoding;
     pub mod envelope;
+    pub mod exchanges;
+    pub mod issuance_batch;
+    pub mod issue_v2;
+    pub mod issued_credentials;
+    pub mod issuer_manifest;
+    pub mod link_secrets;
     pub mod messaging;
+    pub mod offer_expiry;
+    pub mod overlays;
     pub mod presentations;
+    pub mod schema_templates;
     pub mod schemas;
+    pub mod time_window;
+    pub mod validity;
     pub mod wallets; 
 }
 use crate::modules::common::*;
@@ -163,6 +175,10 @@ pub struct IndyAgent {
     pool: Option<Arc<PoolRunner>>,
 
     connection_uri: String,
+
+    // Link secrets já carregados da wallet aberta (por id). Limpo em
+    // wallet_open/wallet_close para nunca vazar entre wallets do mesmo processo.
+    link_secrets: modules::credentials::LinkSecretCache,
 }
 
 fn classify_genesis_error_code(msg: &str) -> &'static str {
@@ -192,8 +208,156 @@ fn classify_genesis_error_code(msg: &str) -> &'static str {
     "PoolConnectFailed"
 }
 
+// =============================================================================
+// ATTRIB: núcleo de escrita/leitura reaproveitado por outros módulos (overlays)
+// =============================================================================
+pub(crate) async fn write_attrib_with_pool(
+    store: &Store,
+    pool: &PoolRunner,
+    did: &str,
+    raw_obj: &serde_json::Value,
+) -> Result<String> {
+    let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
+
+    // =================================================================
+    // A. TAA (Transaction Author Agreement)
+    // =================================================================
+    let taa_req = rb
+        .build_get_txn_author_agreement_request(None, None)
+        .map_err(|e| napi::Error::from_reason(format!("Erro TAA req: {}", e)))?;
+
+    let taa_resp = send_request_async(pool, taa_req).await?;
+    let taa_val: serde_json::Value = serde_json::from_str(&taa_resp)
+        .map_err(|e| napi::Error::from_reason(format!("Erro JSON TAA: {}", e)))?;
+
+    let taa_acceptance = if !taa_val["result"]["data"].is_null() {
+        let text = taa_val["result"]["data"]["text"].as_str();
+        let version = taa_val["result"]["data"]["version"].as_str();
+        let digest = taa_val["result"]["data"]["digest"].as_str();
+
+        // Timestamp seguro
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Some(
+            rb.prepare_txn_author_agreement_acceptance_data(
+                text,
+                version,
+                digest,
+                "wallet_agreement",
+                ts,
+            )
+            .map_err(|e| napi::Error::from_reason(format!("Erro TAA data: {}", e)))?,
+        )
+    } else {
+        None
+    };
+
+    // =================================================================
+    // B. CONSTRUÇÃO DO REQUEST
+    // =================================================================
+    let did_obj = DidValue(did.to_string());
+
+    let mut req = rb
+        .build_attrib_request(&did_obj, &did_obj, None, Some(raw_obj), None)
+        .map_err(|e| napi::Error::from_reason(format!("Erro build ATTRIB: {}", e)))?;
+
+    if let Some(taa) = taa_acceptance {
+        req.set_txn_author_agreement_acceptance(&taa)
+            .map_err(|e| napi::Error::from_reason(format!("Erro set TAA: {}", e)))?;
+    }
+
+    // =================================================================
+    // C. ASSINATURA (Sessão Efêmera de Leitura)
+    // =================================================================
+    let mut session = store
+        .session(None)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+    // 1. Busca Metadados do DID
+    let did_entry = session
+        .fetch("did", did, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch DID: {}", e)))?
+        .ok_or_else(|| napi::Error::from_reason("DID não encontrado na wallet"))?;
+
+    let did_json: serde_json::Value = serde_json::from_slice(&did_entry.value)
+        .map_err(|e| napi::Error::from_reason(format!("DID JSON corrompido: {}", e)))?;
+
+    // .unwrap() removido -> substituição segura
+    let verkey_ref = did_json["verkey"]
+        .as_str()
+        .ok_or_else(|| napi::Error::from_reason("DID sem verkey"))?;
+
+    // 2. Carrega Chave
+    let key_entry = session
+        .fetch_key(verkey_ref, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch key: {}", e)))?
+        .ok_or_else(|| napi::Error::from_reason("Chave privada não encontrada"))?;
+
+    let local_key = key_entry
+        .load_local_key()
+        .map_err(|e| napi::Error::from_reason(format!("Erro load key: {}", e)))?;
+
+    // 3. Assina
+    let signature_input = req
+        .get_signature_input()
+        .map_err(|e| napi::Error::from_reason(format!("Erro sig input: {}", e)))?;
+
+    let signature = local_key
+        .sign_message(signature_input.as_bytes(), None)
+        .map_err(|e| napi::Error::from_reason(format!("Erro assinar: {}", e)))?;
+
+    req.set_signature(&signature)
+        .map_err(|e| napi::Error::from_reason(format!("Erro set sig: {}", e)))?;
+
+    // =================================================================
+    // D. ENVIO
+    // =================================================================
+    let response = send_request_async(pool, req).await?;
+    record_ledger_txn(store, did, Some(did), "ATTRIB", &response).await;
+
+    Ok(response)
+}
+
+// Lê o payload do ATTRIB `key` do DID alvo. Ok(None) quando o atributo não existe.
+pub(crate) async fn read_attrib_with_pool(
+    pool: &PoolRunner,
+    target_did: &str,
+    key: &str,
+) -> Result<Option<serde_json::Value>> {
+    let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
+    let target = DidValue(target_did.to_string());
+
+    let req = rb
+        .build_get_attrib_request(None, &target, Some(key.to_string()), None, None, None, None)
+        .map_err(|e| napi::Error::from_reason(format!("Erro build GET_ATTRIB: {}", e)))?;
+
+    let response_str = send_request_async(pool, req).await?;
+    let json: serde_json::Value = serde_json::from_str(&response_str)
+        .map_err(|e| napi::Error::from_reason(format!("Erro parse JSON resposta: {}", e)))?;
+
+    let data_field = &json["result"]["data"];
+    let inner_json: serde_json::Value = if let Some(s) = data_field.as_str() {
+        serde_json::from_str(s).map_err(|e| {
+            napi::Error::from_reason(format!("Erro parse dados internos string: {}", e))
+        })?
+    } else if data_field.is_object() {
+        data_field.clone()
+    } else {
+        return Ok(None);
+    };
+
+    Ok(inner_json.get(key).cloned())
+}
+
 // =============================================================================
 // 2. IMPLEMENTAÇÃO DO INDY AGENT
+// =============================================================================
 #[napi]
 impl IndyAgent {
     #[napi(constructor)]
@@ -206,6 +370,7 @@ impl IndyAgent {
             // session: None, // <--- CAMPO REMOVIDO
             pool: None,
             connection_uri: String::new(),
+            link_secrets: Default::default(),
         }
     }
 
@@ -299,116 +464,13 @@ impl IndyAgent {
 
         env.execute_tokio_future(
             async move {
-                let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
-
-                // =================================================================
-                // A. TAA (Transaction Author Agreement)
-                // =================================================================
-                let taa_req = rb
-                    .build_get_txn_author_agreement_request(None, None)
-                    .map_err(|e| napi::Error::from_reason(format!("Erro TAA req: {}", e)))?;
-
-                let taa_resp = send_request_async(&pool, taa_req).await?;
-                let taa_val: serde_json::Value = serde_json::from_str(&taa_resp)
-                    .map_err(|e| napi::Error::from_reason(format!("Erro JSON TAA: {}", e)))?;
-
-                let taa_acceptance = if !taa_val["result"]["data"].is_null() {
-                    let text = taa_val["result"]["data"]["text"].as_str();
-                    let version = taa_val["result"]["data"]["version"].as_str();
-                    let digest = taa_val["result"]["data"]["digest"].as_str();
-
-                    // Timestamp seguro
-                    let ts = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs();
-
-                    Some(
-                        rb.prepare_txn_author_agreement_acceptance_data(
-                            text,
-                            version,
-                            digest,
-                            "wallet_agreement",
-                            ts,
-                        )
-                        .map_err(|e| napi::Error::from_reason(format!("Erro TAA data: {}", e)))?,
-                    )
-                } else {
-                    None
-                };
-
-                // =================================================================
-                // B. CONSTRUÇÃO DO REQUEST
-                // =================================================================
-                let did_obj = DidValue(did.clone());
-
                 // Simplificação: Usando macro json! em vez de Map manual
                 // Isso cria { "chave": "valor" }
                 let raw_obj = serde_json::json!({
                     key: value
                 });
 
-                let mut req = rb
-                    .build_attrib_request(&did_obj, &did_obj, None, Some(&raw_obj), None)
-                    .map_err(|e| napi::Error::from_reason(format!("Erro build ATTRIB: {}", e)))?;
-
-                if let Some(taa) = taa_acceptance {
-                    req.set_txn_author_agreement_acceptance(&taa)
-                        .map_err(|e| napi::Error::from_reason(format!("Erro set TAA: {}", e)))?;
-                }
-
-                // =================================================================
-                // C. ASSINATURA (Sessão Efêmera de Leitura)
-                // =================================================================
-                let mut session = store
-                    .session(None)
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
-
-                // 1. Busca Metadados do DID
-                let did_entry = session
-                    .fetch("did", &did, false)
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch DID: {}", e)))?
-                    .ok_or_else(|| napi::Error::from_reason("DID não encontrado na wallet"))?;
-
-                let did_json: serde_json::Value = serde_json::from_slice(&did_entry.value)
-                    .map_err(|e| napi::Error::from_reason(format!("DID JSON corrompido: {}", e)))?;
-
-                // .unwrap() removido -> substituição segura
-                let verkey_ref = did_json["verkey"]
-                    .as_str()
-                    .ok_or_else(|| napi::Error::from_reason("DID sem verkey"))?;
-
-                // 2. Carrega Chave
-                let key_entry = session
-                    .fetch_key(verkey_ref, false)
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch key: {}", e)))?
-                    .ok_or_else(|| napi::Error::from_reason("Chave privada não encontrada"))?;
-
-                let local_key = key_entry
-                    .load_local_key()
-                    .map_err(|e| napi::Error::from_reason(format!("Erro load key: {}", e)))?;
-
-                // 3. Assina
-                let signature_input = req
-                    .get_signature_input()
-                    .map_err(|e| napi::Error::from_reason(format!("Erro sig input: {}", e)))?;
-
-                let signature = local_key
-                    .sign_message(signature_input.as_bytes(), None)
-                    .map_err(|e| napi::Error::from_reason(format!("Erro assinar: {}", e)))?;
-
-                req.set_signature(&signature)
-                    .map_err(|e| napi::Error::from_reason(format!("Erro set sig: {}", e)))?;
-
-                // =================================================================
-                // D. ENVIO
-                // =================================================================
-                let response = send_request_async(&pool, req).await?;
-
-                Ok(response)
+                write_attrib_with_pool(&store, &pool, &did, &raw_obj).await
             },
             |&mut env, data| env.create_string(&data),
         )
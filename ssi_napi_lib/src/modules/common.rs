@@ -32,10 +32,57 @@ pub struct SchemaRecord {
     pub env: String, // "prod" | "test" | "template"
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>, // id_local da versão anterior (schemaCreateNextVersion)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<String>, // templateId do catálogo (schemaInstantiateTemplate)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attr_types: Option<std::collections::BTreeMap<String, AttrType>>, // overlay local de tipos
+}
+
+// Tipagem local de atributos (não vai ao ledger). Define validação e
+// codificação canônica em create_credential, para predicados funcionarem.
+// Regras de codificação: ver modules/encoding.rs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AttrType {
+    Int {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<i64>, // min < 0 habilita negativos (encoded = n - min)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<i64>,
+    },
+    // Escala fixa: "12.5" com scale 2 -> raw "12.50", encoded 1250
+    Decimal {
+        scale: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<String>,
+    },
+    Date,
+    // RFC 3339 UTC ou epoch em segundos -> minutos desde 1970-01-01T00:00:00Z
+    Timestamp,
+    Enum {
+        values: Vec<String>,
+    },
+    String {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none", rename = "maxLen")]
+        max_len: Option<usize>,
+    },
+    Boolean,
 }
 
 pub const CONFIG_CATEGORY: &str = "config";
 pub const KEY_DEFAULT_SCHEMA_ISSUER_DID: &str = "default_schema_issuer_did";
+pub const KEY_DEFAULT_LINK_SECRET_ID: &str = "default_link_secret_id";
+pub const KEY_ISSUED_VALUES_POLICY: &str = "issued_values_policy";
+pub const KEY_ISSUED_VALUES_SALT: &str = "issued_values_salt";
+pub const KEY_VALIDITY_ATTRS: &str = "credential_validity_attrs";
+pub const KEY_OFFER_EXPIRY: &str = "offer_expiry";
+pub const KEY_CONTACT_MISMATCH_POLICY: &str = "contact_mismatch_policy";
 
 pub fn napi_err(code: &str, message: impl Into<String>) -> napi::Error {
     napi::Error::from_reason(
@@ -48,6 +95,30 @@ pub fn napi_err(code: &str, message: impl Into<String>) -> napi::Error {
     )
 }
 
+// Valor de uma tag de entrada do Askar (None se ausente).
+pub(crate) fn tag_value(tags: &[aries_askar::entry::EntryTag], name: &str) -> Option<String> {
+    tags.iter()
+        .find(|t| t.name() == name)
+        .map(|t| t.value().to_string())
+}
+
+// remove do Askar para upserts (remove + insert): entrada inexistente não é
+// erro (devolve false); demais erros do banco sobem.
+pub(crate) async fn remove_if_exists(
+    session: &mut aries_askar::Session,
+    category: &str,
+    name: &str,
+) -> napi::Result<bool> {
+    match session.remove(category, name).await {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == aries_askar::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(napi::Error::from_reason(format!(
+            "Erro remover {}/{}: {}",
+            category, name, e
+        ))),
+    }
+}
+
 pub fn now_ts() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -102,6 +173,407 @@ pub fn build_final_attr_names(mut user_attrs: Vec<String>, revocable: bool) -> R
     Ok(user_attrs)
 }
 
+// "1.2" + minor -> "1.3"; "1.2" + major -> "2.0". Aceita "X", "X.Y" ou "X.Y.Z"
+// numéricos (o componente patch é zerado).
+pub fn bump_schema_version(version: &str, bump: &str) -> napi::Result<String> {
+    let parts: Vec<u64> = version
+        .split('.')
+        .map(|p| p.trim().parse::<u64>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| {
+            napi_err(
+                "SchemaVersionInvalid",
+                format!("versão não numérica: {}", version),
+            )
+        })?;
+    if parts.is_empty() || parts.len() > 3 {
+        return Err(napi_err(
+            "SchemaVersionInvalid",
+            format!("formato de versão não suportado: {}", version),
+        ));
+    }
+
+    let major = parts[0];
+    let minor = parts.get(1).copied().unwrap_or(0);
+    let (major, minor) = match bump {
+        "major" => (major + 1, 0),
+        "minor" => (major, minor + 1),
+        other => {
+            return Err(napi_err(
+                "SchemaBumpInvalid",
+                format!("bump deve ser \"minor\" ou \"major\" (recebido: {})", other),
+            ))
+        }
+    };
+
+    if parts.len() == 3 {
+        Ok(format!("{}.{}.0", major, minor))
+    } else {
+        Ok(format!("{}.{}", major, minor))
+    }
+}
+
+// Ordenação de versões numéricas ("1.10" > "1.9"); não numéricas vão por texto.
+pub fn compare_schema_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |v: &str| -> Option<Vec<u64>> {
+        v.split('.').map(|p| p.trim().parse::<u64>().ok()).collect()
+    };
+    match (parse(a), parse(b)) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        _ => a.cmp(b),
+    }
+}
+
+// Helpers Tipagem de atributos >>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>
+
+// Inteiros codificados precisam caber em i32 positivo para predicados AnonCreds.
+pub const PREDICATE_INT_MAX: i64 = i32::MAX as i64;
+
+// "YYYY-MM-DD" -> dias desde 1970-01-01 (calendário gregoriano proléptico).
+pub fn iso_date_to_epoch_days(s: &str) -> Option<i64> {
+    let b = s.as_bytes();
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return None;
+    }
+    let y: i64 = s[0..4].parse().ok()?;
+    let m: i64 = s[5..7].parse().ok()?;
+    let d: i64 = s[8..10].parse().ok()?;
+    if !(1..=12).contains(&m) {
+        return None;
+    }
+    let leap = (y % 4 == 0 && y % 100 != 0) || y % 400 == 0;
+    let mdays = [31, if leap { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    if d < 1 || d > mdays[(m - 1) as usize] {
+        return None;
+    }
+
+    // days_from_civil (H. Hinnant)
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146097 + doe - 719468)
+}
+
+// Dias desde 1970-01-01 -> "YYYY-MM-DD" (inverso de iso_date_to_epoch_days).
+pub fn epoch_days_to_iso_date(days: i64) -> String {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// Timestamps viram minutos (não segundos): segundos estouram i32 em 2038.
+pub const TIMESTAMP_ENCODING_UNIT_SECS: i64 = 60;
+pub const DECIMAL_MAX_SCALE: u32 = 6;
+
+// Deslocamento aplicado a inteiros/decimais com mínimo negativo (encoded = n - offset).
+pub fn int_encoding_offset(min: Option<i64>) -> i64 {
+    min.filter(|m| *m < 0).unwrap_or(0)
+}
+
+// "YYYY-MM-DDTHH:MM:SSZ" (frações de segundo opcionais, descartadas) ou
+// epoch em segundos -> segundos desde 1970-01-01T00:00:00Z.
+pub fn parse_timestamp_utc(s: &str) -> Option<i64> {
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) {
+        return s.parse().ok();
+    }
+    let s = s.strip_suffix('Z').or_else(|| s.strip_suffix("+00:00"))?;
+    let (date, time) = s.split_once('T')?;
+    let days = iso_date_to_epoch_days(date)?;
+    let time = time.split('.').next()?;
+    let b = time.as_bytes();
+    if b.len() != 8 || b[2] != b':' || b[5] != b':' {
+        return None;
+    }
+    let h: i64 = time[0..2].parse().ok()?;
+    let m: i64 = time[3..5].parse().ok()?;
+    let sec: i64 = time[6..8].parse().ok()?;
+    if h > 23 || m > 59 || sec > 59 {
+        return None;
+    }
+    Some(days * 86400 + h * 3600 + m * 60 + sec)
+}
+
+// Segundos desde epoch -> "YYYY-MM-DDTHH:MM:SSZ"
+pub fn format_timestamp_utc(secs: i64) -> String {
+    let days = secs.div_euclid(86400);
+    let rem = secs.rem_euclid(86400);
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        epoch_days_to_iso_date(days),
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+// "-12.5" com scale 2 -> -1250. Recusa mais casas decimais que a escala.
+pub fn parse_scaled_decimal(s: &str, scale: u32) -> Option<i64> {
+    let (neg, body) = match s.strip_prefix('-') {
+        Some(b) => (true, b),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (int_part, frac_part) = match body.split_once('.') {
+        Some((i, f)) => (i, f),
+        None => (body, ""),
+    };
+    if int_part.is_empty()
+        || !int_part.chars().all(|c| c.is_ascii_digit())
+        || !frac_part.chars().all(|c| c.is_ascii_digit())
+        || frac_part.len() > scale as usize
+    {
+        return None;
+    }
+    let factor = 10i64.checked_pow(scale)?;
+    let frac: i64 = if frac_part.is_empty() {
+        0
+    } else {
+        let padded = format!("{:0<width$}", frac_part, width = scale as usize);
+        padded.parse().ok()?
+    };
+    let v = int_part.parse::<i64>().ok()?.checked_mul(factor)?.checked_add(frac)?;
+    Some(if neg { -v } else { v })
+}
+
+// -1250 com scale 2 -> "-12.50"
+pub fn format_scaled_decimal(v: i64, scale: u32) -> String {
+    if scale == 0 {
+        return v.to_string();
+    }
+    let factor = 10i64.pow(scale);
+    let sign = if v < 0 { "-" } else { "" };
+    let a = v.unsigned_abs();
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        a / factor as u64,
+        a % factor as u64,
+        width = scale as usize
+    )
+}
+
+// Confere se a definição de tipo é utilizável (regex compila, enum não vazio...).
+pub fn check_attr_type_def(attr: &str, t: &AttrType) -> napi::Result<()> {
+    let bad = |m: String| napi_err("AttrTypeInvalid", format!("{}: {}", attr, m));
+    match t {
+        AttrType::Int { min, max } => {
+            if let (Some(a), Some(b)) = (min, max) {
+                if a > b {
+                    return Err(bad("min > max".to_string()));
+                }
+            }
+            let offset = int_encoding_offset(*min);
+            if offset < -PREDICATE_INT_MAX
+                || max
+                    .map(|v| v.checked_sub(offset).is_none_or(|d| d > PREDICATE_INT_MAX))
+                    .unwrap_or(false)
+            {
+                return Err(bad(format!(
+                    "faixa codificada (max - min) deve caber em 0..={}",
+                    PREDICATE_INT_MAX
+                )));
+            }
+        }
+        AttrType::Decimal { scale, min, max } => {
+            if *scale > DECIMAL_MAX_SCALE {
+                return Err(bad(format!("scale deve estar em 0..={}", DECIMAL_MAX_SCALE)));
+            }
+            let parse = |v: &Option<String>| -> napi::Result<Option<i64>> {
+                match v {
+                    None => Ok(None),
+                    Some(x) => parse_scaled_decimal(x, *scale)
+                        .map(Some)
+                        .ok_or_else(|| bad(format!("limite decimal inválido: {}", x))),
+                }
+            };
+            let (lo, hi) = (parse(min)?, parse(max)?);
+            if let (Some(a), Some(b)) = (lo, hi) {
+                if a > b {
+                    return Err(bad("min > max".to_string()));
+                }
+            }
+            let offset = int_encoding_offset(lo);
+            if offset < -PREDICATE_INT_MAX
+                || hi
+                    .map(|v| v.checked_sub(offset).is_none_or(|d| d > PREDICATE_INT_MAX))
+                    .unwrap_or(false)
+            {
+                return Err(bad(format!(
+                    "faixa escalada (max - min) deve caber em 0..={}",
+                    PREDICATE_INT_MAX
+                )));
+            }
+        }
+        AttrType::Enum { values } => {
+            if values.is_empty() {
+                return Err(bad("enum sem valores".to_string()));
+            }
+        }
+        AttrType::String { pattern: Some(p), .. } => {
+            regex::Regex::new(p).map_err(|e| bad(format!("regex inválida: {}", e)))?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Valida o valor bruto contra o tipo e devolve (raw canônico, encoded).
+pub fn encode_typed_attr_value(
+    attr: &str,
+    t: &AttrType,
+    val: &serde_json::Value,
+) -> napi::Result<(String, String)> {
+    let raw = match val {
+        serde_json::Value::String(s) => s.trim().to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        other => {
+            return Err(napi_err(
+                "AttrValueInvalid",
+                format!("{}: valor não suportado ({})", attr, other),
+            ))
+        }
+    };
+    let bad = |m: String| napi_err("AttrValueInvalid", format!("{}: {}", attr, m));
+
+    match t {
+        AttrType::Int { min, max } => {
+            let n: i64 = raw
+                .parse()
+                .map_err(|_| bad(format!("esperado inteiro, recebido \"{}\"", raw)))?;
+            if min.map(|m| n < m).unwrap_or(false) || max.map(|m| n > m).unwrap_or(false) {
+                return Err(bad(format!("{} fora da faixa {:?}..={:?}", n, min, max)));
+            }
+            let enc = n
+                .checked_sub(int_encoding_offset(*min))
+                .filter(|e| (0..=PREDICATE_INT_MAX).contains(e))
+                .ok_or_else(|| {
+                    bad(format!(
+                        "{} não codificável em 0..={} (negativos exigem min < 0)",
+                        n, PREDICATE_INT_MAX
+                    ))
+                })?;
+            Ok((n.to_string(), enc.to_string()))
+        }
+        AttrType::Decimal { scale, min, max } => {
+            let v = parse_scaled_decimal(&raw, *scale).ok_or_else(|| {
+                bad(format!(
+                    "esperado decimal com até {} casas, recebido \"{}\"",
+                    scale, raw
+                ))
+            })?;
+            let lo = min.as_deref().and_then(|m| parse_scaled_decimal(m, *scale));
+            let hi = max.as_deref().and_then(|m| parse_scaled_decimal(m, *scale));
+            if lo.map(|m| v < m).unwrap_or(false) || hi.map(|m| v > m).unwrap_or(false) {
+                return Err(bad(format!("{} fora da faixa {:?}..={:?}", raw, min, max)));
+            }
+            let enc = v
+                .checked_sub(int_encoding_offset(lo))
+                .filter(|e| (0..=PREDICATE_INT_MAX).contains(e))
+                .ok_or_else(|| {
+                    bad(format!(
+                        "{} não codificável em 0..={} (negativos exigem min < 0)",
+                        raw, PREDICATE_INT_MAX
+                    ))
+                })?;
+            Ok((format_scaled_decimal(v, *scale), enc.to_string()))
+        }
+        AttrType::Date => {
+            let days = iso_date_to_epoch_days(&raw)
+                .ok_or_else(|| bad(format!("esperada data YYYY-MM-DD, recebido \"{}\"", raw)))?;
+            if days < 0 {
+                return Err(bad("datas anteriores a 1970-01-01 não são suportadas".to_string()));
+            }
+            Ok((raw, days.to_string()))
+        }
+        AttrType::Timestamp => {
+            let secs = parse_timestamp_utc(&raw).ok_or_else(|| {
+                bad(format!(
+                    "esperado timestamp RFC 3339 UTC ou epoch em segundos, recebido \"{}\"",
+                    raw
+                ))
+            })?;
+            let enc = secs.div_euclid(TIMESTAMP_ENCODING_UNIT_SECS);
+            if !(0..=PREDICATE_INT_MAX).contains(&enc) {
+                return Err(bad("timestamp fora da faixa codificável".to_string()));
+            }
+            Ok((format_timestamp_utc(secs), enc.to_string()))
+        }
+        AttrType::Boolean => {
+            let b = match raw.to_lowercase().as_str() {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => return Err(bad(format!("esperado booleano, recebido \"{}\"", raw))),
+            };
+            Ok((b.to_string(), if b { "1" } else { "0" }.to_string()))
+        }
+        AttrType::Enum { values } => {
+            if !values.iter().any(|v| v == &raw) {
+                return Err(bad(format!("\"{}\" não está em {:?}", raw, values)));
+            }
+            let enc = hash_attr_string(&raw);
+            Ok((raw, enc))
+        }
+        AttrType::String { pattern, max_len } => {
+            if let Some(ml) = max_len {
+                if raw.chars().count() > *ml {
+                    return Err(bad(format!("excede {} caracteres", ml)));
+                }
+            }
+            if let Some(p) = pattern {
+                let re = regex::Regex::new(p).map_err(|e| bad(format!("regex inválida: {}", e)))?;
+                if !re.is_match(&raw) {
+                    return Err(bad(format!("\"{}\" não casa com /{}/", raw, p)));
+                }
+            }
+            let enc = hash_attr_string(&raw);
+            Ok((raw, enc))
+        }
+    }
+}
+
+// Mesma regra de hash de create_credential para valores textuais
+// (SHA-256 interpretado como inteiro big-endian, em decimal).
+pub fn hash_attr_string(s: &str) -> String {
+    let digest = Sha256::digest(s.as_bytes());
+    num_bigint::BigUint::from_bytes_be(&digest).to_str_radix(10)
+}
+
+// Serialização canônica (chaves ordenadas, sem espaços) para conteúdo assinado.
+// Não depende da ordem interna do serde_json::Map (feature preserve_order).
+pub fn canonical_json(v: &serde_json::Value) -> String {
+    match v {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let parts: Vec<String> = keys
+                .into_iter()
+                .map(|k| {
+                    format!(
+                        "{}:{}",
+                        serde_json::Value::String(k.clone()),
+                        canonical_json(&map[k])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", parts.join(","))
+        }
+        serde_json::Value::Array(arr) => {
+            let parts: Vec<String> = arr.iter().map(canonical_json).collect();
+            format!("[{}]", parts.join(","))
+        }
+        other => other.to_string(),
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletKdfSidecar {
@@ -171,6 +643,103 @@ pub fn derive_raw_key_argon2id(
     Ok(bs58::encode(out).into_string())
 }
 
+// Envelope cifrado por senha (Argon2id + AES-256-GCM), mesmo formato do
+// backup de senha da wallet. Usado para exportar material privado.
+pub fn seal_with_pass(pass: &str, plaintext: &[u8]) -> napi::Result<serde_json::Value> {
+    use aes_gcm::{aead::Aead, aead::KeyInit, Aes256Gcm};
+
+    if pass.is_empty() {
+        return Err(napi_err("ExportPassInvalid", "senha de exportação vazia"));
+    }
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key_b58 = derive_raw_key_argon2id(pass, &salt, 65536, 3, 1)?;
+    let key_bytes = bs58::decode(key_b58)
+        .into_vec()
+        .map_err(|e| napi_err("ExportKeyDecodeFailed", e.to_string()))?;
+
+    let cipher = Aes256Gcm::new_from_slice(&key_bytes)
+        .map_err(|e| napi_err("ExportCipherInitFailed", e.to_string()))?;
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce);
+    let ct = cipher
+        .encrypt((&nonce).into(), plaintext)
+        .map_err(|e| napi_err("ExportEncryptFailed", e.to_string()))?;
+
+    Ok(serde_json::json!({
+        "version": 1,
+        "kdf": "argon2id",
+        "salt_b64": B64.encode(salt),
+        "m_cost_kib": 65536,
+        "t_cost": 3,
+        "p_cost": 1,
+        "nonce_b64": B64.encode(nonce),
+        "ct_b64": B64.encode(ct),
+    }))
+}
+
+// Limites aceitos em open_with_pass (m em KiB: 8 MiB .. 1 GiB).
+const ARGON2_M_COST_KIB_BOUNDS: (u64, u64) = (8 * 1024, 1024 * 1024);
+const ARGON2_T_COST_BOUNDS: (u64, u64) = (1, 10);
+const ARGON2_P_COST_BOUNDS: (u64, u64) = (1, 8);
+
+pub fn open_with_pass(pass: &str, sealed: &serde_json::Value) -> napi::Result<Vec<u8>> {
+    use aes_gcm::{aead::Aead, aead::KeyInit, Aes256Gcm};
+
+    let field = |k: &str| -> napi::Result<Vec<u8>> {
+        let s = sealed[k]
+            .as_str()
+            .ok_or_else(|| napi_err("ExportFormatInvalid", format!("{} ausente", k)))?;
+        B64.decode(s)
+            .map_err(|e| napi_err("ExportFormatInvalid", e.to_string()))
+    };
+    let salt = field("salt_b64")?;
+    let nonce = field("nonce_b64")?;
+    let ct = field("ct_b64")?;
+    if nonce.len() != 12 {
+        return Err(napi_err("ExportNonceInvalid", "nonce deve ter 12 bytes"));
+    }
+    if sealed["version"].as_u64() != Some(1) || sealed["kdf"].as_str() != Some("argon2id") {
+        return Err(napi_err(
+            "ExportFormatInvalid",
+            format!(
+                "version/kdf não suportados: {} / {}",
+                sealed["version"], sealed["kdf"]
+            ),
+        ));
+    }
+
+    // Parâmetros vêm do pacote (não confiável): fora dos limites o pacote é
+    // recusado, para não travar o processo (memória/tempo) nem truncar em u32.
+    let param = |k: &str, default: u64, (min, max): (u64, u64)| -> napi::Result<u32> {
+        let v = match &sealed[k] {
+            serde_json::Value::Null => default,
+            other => other.as_u64().unwrap_or(u64::MAX),
+        };
+        if v < min || v > max {
+            return Err(napi_err(
+                "ExportFormatInvalid",
+                format!("{} fora de {}..={}: {}", k, min, max, sealed[k]),
+            ));
+        }
+        Ok(v as u32)
+    };
+    let m = param("m_cost_kib", 65536, ARGON2_M_COST_KIB_BOUNDS)?;
+    let t = param("t_cost", 3, ARGON2_T_COST_BOUNDS)?;
+    let p = param("p_cost", 1, ARGON2_P_COST_BOUNDS)?;
+    let key_b58 = derive_raw_key_argon2id(pass, &salt, m, t, p)?;
+    let key_bytes = bs58::decode(key_b58)
+        .into_vec()
+        .map_err(|e| napi_err("ExportKeyDecodeFailed", e.to_string()))?;
+
+    let cipher = Aes256Gcm::new_from_slice(&key_bytes)
+        .map_err(|e| napi_err("ExportCipherInitFailed", e.to_string()))?;
+    cipher
+        .decrypt((&nonce[..]).into(), ct.as_ref())
+        .map_err(|_| napi_err("ExportDecryptFailed", "senha incorreta ou pacote adulterado"))
+}
+
 pub fn derive_raw_key_from_sidecar(password: &str, sc: &WalletKdfSidecar) -> napi::Result<String> {
     match sc.kdf.as_str() {
         "argon2id" => {
@@ -235,6 +804,68 @@ pub fn cleanup_wallet_files(wallet_path: &str, sidecar_path: &str) {
     let _ = std::fs::remove_file(format!("{}.tmp", sidecar_path));
 }
 
+// Registro local das nossas escritas no ledger (seqNo) — usado pelo histórico de DID.
+// Best-effort: falha aqui nunca invalida a escrita que já foi aceita pelo ledger.
+pub const LEDGER_TXN_CATEGORY: &str = "ledger_txn";
+
+pub async fn record_ledger_txn(
+    store: &aries_askar::Store,
+    from_did: &str,
+    dest_did: Option<&str>,
+    txn_type: &str,
+    response: &str,
+) {
+    let v: serde_json::Value = match serde_json::from_str(response) {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+    if v.get("op").and_then(|x| x.as_str()) != Some("REPLY") {
+        return;
+    }
+    // Node 1.4: result.txnMetadata.seqNo (legado: result.seqNo)
+    let seq_no = v["result"]["txnMetadata"]["seqNo"]
+        .as_u64()
+        .or_else(|| v["result"]["seqNo"].as_u64());
+    let seq_no = match seq_no {
+        Some(n) => n,
+        None => return,
+    };
+    let txn_time = v["result"]["txnMetadata"]["txnTime"]
+        .as_u64()
+        .or_else(|| v["result"]["txnTime"].as_u64());
+
+    let rec = serde_json::json!({
+        "seqNo": seq_no,
+        "type": txn_type,
+        "from": from_did,
+        "dest": dest_did,
+        "txnTime": txn_time,
+        "recordedAt": now_ts()
+    });
+    let tags = vec![
+        aries_askar::entry::EntryTag::Encrypted("from".to_string(), from_did.to_string()),
+        aries_askar::entry::EntryTag::Encrypted(
+            "dest".to_string(),
+            dest_did.unwrap_or(from_did).to_string(),
+        ),
+        aries_askar::entry::EntryTag::Encrypted("type".to_string(), txn_type.to_string()),
+    ];
+
+    if let Ok(mut session) = store.session(None).await {
+        let name = seq_no.to_string();
+        if remove_if_exists(&mut session, LEDGER_TXN_CATEGORY, &name)
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let _ = session
+            .insert(LEDGER_TXN_CATEGORY, &name, rec.to_string().as_bytes(), Some(&tags), None)
+            .await;
+        let _ = session.commit().await;
+    }
+}
+
 // =============================================================================
 // 1. HELPER (CORRIGIDO) - Coloque fora do "impl IndyAgent"
 // =============================================================================
@@ -1,7 +1,13 @@
 // src/modules/creddefs.rs
-use crate::modules::common::{send_request_async};
+use crate::modules::common::{
+    napi_err, now_ts, open_with_pass, record_ledger_txn, remove_if_exists, seal_with_pass,
+    send_request_async, tag_value,
+};
+use crate::modules::exchanges::{
+    exchange_advance, exchange_transition_to, load_exchange, ExchangePatch, ROLE_ISSUER,
+};
 use crate::IndyAgent;
-use aries_askar::entry::EntryTag;
+use aries_askar::entry::{EntryTag, TagFilter};
 use napi::{Env, Error, JsObject, Result};
 use napi_derive::napi;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -25,6 +31,790 @@ use indy_vdr::utils::did::DidValue;
 // Import de ID tipado para busca
 use indy_data_types::CredentialDefinitionId;
 
+// =========================================================================
+//  HELPERS DO REGISTRO DE CRED DEFS
+// =========================================================================
+
+// Reescreve um registro trocando/adicionando tags (upsert via remove+insert),
+// preservando as tags existentes que não foram informadas.
+pub(crate) async fn rewrite_entry_tags(
+    session: &mut aries_askar::Session,
+    category: &str,
+    name: &str,
+    set_tags: &[(&str, String)],
+) -> Result<()> {
+    let entry = match session
+        .fetch(category, name, true)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch {}: {}", category, e)))?
+    {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+
+    let mut tags: Vec<EntryTag> = entry
+        .tags
+        .iter()
+        .filter(|t| !set_tags.iter().any(|(n, _)| *n == t.name()))
+        .map(|t| EntryTag::Encrypted(t.name().to_string(), t.value().to_string()))
+        .collect();
+    for (n, v) in set_tags {
+        tags.push(EntryTag::Encrypted(n.to_string(), v.clone()));
+    }
+
+    session
+        .remove(category, name)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro remove {}: {}", category, e)))?;
+    session
+        .insert(category, name, &entry.value, Some(&tags), None)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro insert {}: {}", category, e)))?;
+    Ok(())
+}
+
+// Registra uma emissão: marca a oferta (pelo nonce) como emitida e
+// incrementa o contador "issued_count" da cred def.
+pub(crate) async fn note_credential_issued(
+    session: &mut aries_askar::Session,
+    cred_def_id: &str,
+    offer_nonce: &str,
+) -> Result<()> {
+    note_credentials_issued(session, cred_def_id, &[offer_nonce]).await
+}
+
+// Versão em lote: uma leitura das ofertas da cred def e um único incremento
+// de "issued_count" para todos os nonces.
+pub(crate) async fn note_credentials_issued(
+    session: &mut aries_askar::Session,
+    cred_def_id: &str,
+    offer_nonces: &[&str],
+) -> Result<()> {
+    if offer_nonces.is_empty() {
+        return Ok(());
+    }
+    let now = now_ts().to_string();
+
+    let offers = session
+        .fetch_all(
+            Some("cred_offer"),
+            Some(TagFilter::is_eq("cred_def_id", cred_def_id)),
+            None,
+            None,
+            false,
+            false,
+        )
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch ofertas: {}", e)))?;
+
+    for entry in offers.iter() {
+        let v: serde_json::Value = serde_json::from_slice(&entry.value).unwrap_or_default();
+        if v["nonce"].as_str().is_some_and(|n| offer_nonces.contains(&n)) {
+            rewrite_entry_tags(
+                session,
+                "cred_offer",
+                &entry.name,
+                &[("issued_at", now.clone())],
+            )
+            .await?;
+        }
+    }
+
+    let current = session
+        .fetch("cred_def", cred_def_id, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+        .and_then(|e| tag_value(&e.tags, "issued_count"))
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0);
+
+    rewrite_entry_tags(
+        session,
+        "cred_def",
+        cred_def_id,
+        &[
+            (
+                "issued_count",
+                (current + offer_nonces.len() as u64).to_string(),
+            ),
+            ("last_issued_at", now),
+        ],
+    )
+    .await
+}
+
+// "{issuer}:3:CL:{schemaSeqNo}:{tag}" -> (issuer, schemaSeqNo, tag)
+fn split_cred_def_id(cred_def_id: &str) -> (String, Option<u64>, String) {
+    let parts: Vec<&str> = cred_def_id.split(':').collect();
+    let issuer = parts.first().unwrap_or(&"").to_string();
+    let seq = parts.get(3).and_then(|s| s.parse::<u64>().ok());
+    let tag = if parts.len() >= 5 {
+        parts[4..].join(":")
+    } else {
+        String::new()
+    };
+    (issuer, seq, tag)
+}
+
+// "{issuer}:2:{name}:{version}" -> (name, version)
+fn split_schema_id(schema_id: &str) -> (Option<String>, Option<String>) {
+    let parts: Vec<&str> = schema_id.split(':').collect();
+    if parts.len() == 4 && parts[1] == "2" {
+        (Some(parts[2].to_string()), Some(parts[3].to_string()))
+    } else {
+        (None, None)
+    }
+}
+
+// Conta as ofertas da cred def: (total, pendentes = ainda não emitidas)
+async fn count_offers(
+    session: &mut aries_askar::Session,
+    cred_def_id: &str,
+) -> Result<(u64, u64)> {
+    let offers = session
+        .fetch_all(
+            Some("cred_offer"),
+            Some(TagFilter::is_eq("cred_def_id", cred_def_id)),
+            None,
+            None,
+            false,
+            false,
+        )
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch ofertas: {}", e)))?;
+
+    let total = offers.len() as u64;
+    let pending = offers
+        .iter()
+        .filter(|e| tag_value(&e.tags, "issued_at").is_none())
+        .count() as u64;
+    Ok((total, pending))
+}
+
+// Resumo de uma cred def para o registro local (sem material privado).
+async fn cred_def_summary(
+    session: &mut aries_askar::Session,
+    entry: &aries_askar::entry::Entry,
+) -> Result<serde_json::Value> {
+    let pub_val: serde_json::Value = serde_json::from_slice(&entry.value).unwrap_or_default();
+    let (issuer, schema_seq_no, tag) = split_cred_def_id(&entry.name);
+
+    let schema_id = tag_value(&entry.tags, "schema_id")
+        .or_else(|| pub_val["schemaId"].as_str().map(|s| s.to_string()))
+        .unwrap_or_default();
+    let (schema_name, schema_version) = split_schema_id(&schema_id);
+
+    let has_private = session
+        .fetch("cred_def_private", &entry.name, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def_private: {}", e)))?
+        .is_some();
+
+    let (offers_total, offers_pending) = count_offers(session, &entry.name).await?;
+
+    let num = |n: &str| tag_value(&entry.tags, n).and_then(|s| s.parse::<u64>().ok());
+
+    Ok(serde_json::json!({
+        "credDefId": entry.name,
+        "issuerDid": issuer,
+        "schemaId": schema_id,
+        "schemaSeqNo": schema_seq_no,
+        "schemaName": schema_name,
+        "schemaVersion": schema_version,
+        "tag": tag,
+        "supportRevocation": !pub_val["value"]["revocation"].is_null(),
+        "ledgerSeqNo": num("ledger_seq_no"),
+        "createdAt": num("created_at"),
+        "hasPrivate": has_private,
+        "offersTotal": offers_total,
+        "offersPending": offers_pending,
+        "issuedCount": num("issued_count").unwrap_or(0),
+        "lastIssuedAt": num("last_issued_at"),
+        "status": tag_value(&entry.tags, "status").unwrap_or_else(|| "active".to_string()),
+        "supersedes": tag_value(&entry.tags, "supersedes"),
+        "supersededBy": tag_value(&entry.tags, "superseded_by"),
+        "deprecatedAt": num("deprecated_at")
+    }))
+}
+
+// Tag versionada para rotação: "default" -> "default_v2", "default_v2" -> "default_v3".
+fn next_versioned_tag(tag: &str) -> String {
+    if let Some((base, n)) = tag.rsplit_once("_v") {
+        if let Ok(n) = n.parse::<u32>() {
+            if !base.is_empty() {
+                return format!("{}_v{}", base, n + 1);
+            }
+        }
+    }
+    format!("{}_v2", tag)
+}
+
+// Cred def ativa para novas ofertas. Aceita:
+//  - cred_def_id: segue superseded_by enquanto a cred def estiver deprecated;
+//  - schema_id: a cred def própria ativa mais recente daquele schema.
+pub(crate) async fn resolve_active_cred_def(
+    session: &mut aries_askar::Session,
+    id: &str,
+) -> Result<String> {
+    if split_schema_id(id).0.is_some() {
+        let entries = session
+            .fetch_all(
+                Some("cred_def"),
+                Some(TagFilter::is_eq("schema_id", id)),
+                None,
+                None,
+                false,
+                false,
+            )
+            .await
+            .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?;
+
+        let mut best: Option<(u64, String)> = None;
+        for e in entries.iter() {
+            if tag_value(&e.tags, "status").as_deref() == Some("deprecated") {
+                continue;
+            }
+            let own = session
+                .fetch("cred_def_private", &e.name, false)
+                .await
+                .map_err(|e| napi::Error::from_reason(format!("Erro fetch privada: {}", e)))?
+                .is_some();
+            if !own {
+                continue;
+            }
+            let created = tag_value(&e.tags, "created_at")
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(0);
+            if best.as_ref().map(|(c, _)| created >= *c).unwrap_or(true) {
+                best = Some((created, e.name.clone()));
+            }
+        }
+        return best.map(|(_, id)| id).ok_or_else(|| {
+            napi_err(
+                "CredDefNotFound",
+                format!("Nenhuma cred def ativa própria para o schema {}", id),
+            )
+        });
+    }
+
+    // Limite de saltos protege contra ciclo em tags corrompidas.
+    let mut current = id.to_string();
+    for _ in 0..32 {
+        let entry = match session
+            .fetch("cred_def", &current, false)
+            .await
+            .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+        {
+            Some(e) => e,
+            None => return Ok(current),
+        };
+        if tag_value(&entry.tags, "status").as_deref() != Some("deprecated") {
+            return Ok(current);
+        }
+        match tag_value(&entry.tags, "superseded_by") {
+            Some(next) => current = next,
+            None => return Ok(current),
+        }
+    }
+    Ok(current)
+}
+
+// Nova oferta AnonCreds a partir do key proof guardado na tag da parte privada.
+async fn build_offer_for_cred_def(
+    session: &mut aries_askar::Session,
+    schema_id: &str,
+    cred_def_id: &str,
+) -> Result<String> {
+    use anoncreds::data_types::cred_def::CredentialDefinitionId as AnonCredDefId;
+    use anoncreds::data_types::schema::SchemaId as AnonSchemaId;
+    use anoncreds::types::CredentialKeyCorrectnessProof;
+
+    let priv_entry = session
+        .fetch("cred_def_private", cred_def_id, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch privada: {}", e)))?
+        .ok_or_else(|| napi::Error::from_reason("CredDef Private não encontrada"))?;
+    let key_proof: CredentialKeyCorrectnessProof = tag_value(&priv_entry.tags, "key_proof")
+        .and_then(|k| serde_json::from_str(&k).ok())
+        .ok_or_else(|| napi::Error::from_reason("Key Proof não encontrado nas tags"))?;
+
+    let offer = anoncreds::issuer::create_credential_offer(
+        AnonSchemaId::new(schema_id.to_string())
+            .map_err(|_| napi::Error::from_reason("SchemaId inválido"))?,
+        AnonCredDefId::new(cred_def_id.to_string())
+            .map_err(|_| napi::Error::from_reason("CredDefId inválido"))?,
+        &key_proof,
+    )
+    .map_err(|e| napi::Error::from_reason(format!("Erro criando oferta: {}", e)))?;
+
+    serde_json::to_string(&offer)
+        .map_err(|_| napi::Error::from_reason("Erro serializando oferta"))
+}
+
+// Valida material de cred def importado: a key correctness proof precisa
+// bater com a chave pública (verificada em create_credential_request) e a
+// chave privada precisa assinar uma credencial que o prover aceite
+// (process_credential). Tudo em memória, com link secret descartável.
+fn validate_cred_def_material(
+    cred_def_id: &str,
+    schema_id: &str,
+    pub_json: &str,
+    priv_json: &str,
+    key_proof_json: &str,
+) -> Result<()> {
+    use anoncreds::data_types::cred_def::{
+        CredentialDefinition, CredentialDefinitionId, CredentialDefinitionPrivate,
+    };
+    use anoncreds::data_types::schema::SchemaId;
+    use anoncreds::types::{
+        AttributeValues, CredentialKeyCorrectnessProof, CredentialValues, LinkSecret,
+    };
+    use rand::Rng;
+    use std::convert::TryFrom;
+
+    let invalid = |m: String| napi_err("CredDefMaterialInvalid", m);
+
+    let cred_def: CredentialDefinition =
+        serde_json::from_str(pub_json).map_err(|e| invalid(format!("CredDef pública: {}", e)))?;
+    let cred_def_priv: CredentialDefinitionPrivate =
+        serde_json::from_str(priv_json).map_err(|e| invalid(format!("CredDef privada: {}", e)))?;
+    let key_proof: CredentialKeyCorrectnessProof = serde_json::from_str(key_proof_json)
+        .map_err(|e| invalid(format!("Key proof: {}", e)))?;
+
+    let offer = anoncreds::issuer::create_credential_offer(
+        SchemaId::new(schema_id.to_string()).map_err(|e| invalid(format!("SchemaId: {}", e)))?,
+        CredentialDefinitionId::new(cred_def_id.to_string())
+            .map_err(|e| invalid(format!("CredDefId: {}", e)))?,
+        &key_proof,
+    )
+    .map_err(|e| invalid(format!("Oferta de teste: {}", e)))?;
+
+    let seed_str = rand::thread_rng().gen::<u128>().to_string();
+    let link_secret = LinkSecret::try_from(seed_str.as_str())
+        .map_err(|e| invalid(format!("Link secret temporário: {:?}", e)))?;
+
+    let prover_did = cred_def_id.split(':').next().unwrap_or("");
+    let (request, metadata) = anoncreds::prover::create_credential_request(
+        None,
+        Some(prover_did),
+        &cred_def,
+        &link_secret,
+        &offer.nonce,
+        &offer,
+    )
+    .map_err(|e| {
+        napi_err(
+            "CredDefKeyProofInvalid",
+            format!("Key proof não confere com a cred def pública: {}", e),
+        )
+    })?;
+
+    let pub_val: serde_json::Value = serde_json::from_str(pub_json).unwrap_or_default();
+    let mut values = CredentialValues::default();
+    if let Some(r) = pub_val["value"]["primary"]["r"].as_object() {
+        for name in r.keys().filter(|k| k.as_str() != "master_secret") {
+            values.0.insert(
+                name.clone(),
+                AttributeValues {
+                    raw: "1".to_string(),
+                    encoded: "1".to_string(),
+                },
+            );
+        }
+    }
+
+    let mut credential = anoncreds::issuer::create_credential(
+        &cred_def,
+        &cred_def_priv,
+        &offer,
+        &request,
+        values,
+        None,
+    )
+    .map_err(|e| invalid(format!("Emissão de teste: {}", e)))?;
+
+    anoncreds::prover::process_credential(&mut credential, &metadata, &link_secret, &cred_def, None)
+        .map_err(|e| {
+            napi_err(
+                "CredDefPrivateMismatch",
+                format!("Chave privada não corresponde à cred def pública: {}", e),
+            )
+        })?;
+
+    Ok(())
+}
+
+// Escreve no ledger a CLAIM_DEF de uma cred def já gravada na wallet (sem
+// commit) e grava o seqNo como tag ledger_seq_no. schema_seq_no vai em schemaId.
+async fn write_cred_def_to_ledger(
+    store: &aries_askar::Store,
+    pool: &indy_vdr::pool::PoolRunner,
+    session: &mut aries_askar::Session,
+    issuer_did: &str,
+    cred_def_id: &str,
+    schema_seq_no: u64,
+    mut cred_def_val: serde_json::Value,
+) -> Result<()> {
+    use indy_vdr::ledger::requests::cred_def::{
+        CredentialDefinition as VdrCredDefEnum, CredentialDefinitionV1 as VdrCredDefStruct,
+    };
+
+    let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
+
+    if let Some(obj) = cred_def_val.as_object_mut() {
+        obj.insert("ver".to_string(), serde_json::json!("1.0"));
+        obj.insert("id".to_string(), serde_json::json!(cred_def_id));
+        obj.insert(
+            "schemaId".to_string(),
+            serde_json::json!(schema_seq_no.to_string()),
+        );
+        obj.insert("type".to_string(), serde_json::json!("CL"));
+    }
+
+    let vdr_struct: VdrCredDefStruct =
+        serde_json::from_value(cred_def_val).map_err(|e| {
+            napi::Error::from_reason(format!("Erro convert VDR Struct: {}", e))
+        })?;
+
+    let vdr_enum = VdrCredDefEnum::CredentialDefinitionV1(vdr_struct);
+
+    let did_obj = DidValue(issuer_did.to_string());
+    let mut req = rb
+        .build_cred_def_request(&did_obj, vdr_enum)
+        .map_err(|e| napi::Error::from_reason(format!("Erro build req: {}", e)))?;
+
+    // TAA
+    let taa_req = rb
+        .build_get_txn_author_agreement_request(None, None)
+        .map_err(|e| napi::Error::from_reason(format!("TAA req build: {}", e)))?;
+    let taa_resp = send_request_async(pool, taa_req).await?;
+    let taa_val: serde_json::Value = serde_json::from_str(&taa_resp)
+        .map_err(|_| napi::Error::from_reason("TAA parse error"))?;
+
+    if let Some(res) = taa_val.get("result") {
+        if let Some(data) = res.get("data") {
+            if !data.is_null() {
+                let text = data.get("text").and_then(|t| t.as_str());
+                let version = data.get("version").and_then(|v| v.as_str());
+                let digest = data.get("digest").and_then(|d| d.as_str());
+
+                if let (Some(t), Some(v)) = (text, version) {
+                    let ts = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs();
+                    let ts_midnight = (ts / 86400) * 86400;
+                    let taa = rb
+                        .prepare_txn_author_agreement_acceptance_data(
+                            Some(t),
+                            Some(v),
+                            digest,
+                            "wallet_agreement",
+                            ts_midnight,
+                        )
+                        .map_err(|e| {
+                            napi::Error::from_reason(format!("TAA prep: {}", e))
+                        })?;
+                    req.set_txn_author_agreement_acceptance(&taa).map_err(|e| {
+                        napi::Error::from_reason(format!("TAA set: {}", e))
+                    })?;
+                }
+            }
+        }
+    }
+
+    // Assinar e Enviar
+    let did_entry = session
+        .fetch("did", issuer_did, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Fetch DID: {}", e)))?
+        .ok_or_else(|| napi::Error::from_reason("DID issuer não achado"))?;
+
+    let did_json_val: serde_json::Value =
+        serde_json::from_slice(&did_entry.value).unwrap();
+    let verkey = did_json_val["verkey"].as_str().unwrap();
+
+    let key_entry = session
+        .fetch_key(verkey, false)
+        .await
+        .map_err(|_| napi::Error::from_reason("Fetch key error"))?
+        .ok_or_else(|| napi::Error::from_reason("Chave privada não achada"))?;
+
+    let signer_key = key_entry
+        .load_local_key()
+        .map_err(|_| napi::Error::from_reason("Load key error"))?;
+
+    let signature = signer_key
+        .sign_message(req.get_signature_input().unwrap().as_bytes(), None)
+        .map_err(|e| napi::Error::from_reason(format!("Sign error: {}", e)))?;
+
+    req.set_signature(&signature)
+        .map_err(|e| napi::Error::from_reason(format!("Set sig error: {}", e)))?;
+
+    let resp = send_request_async(pool, req).await?;
+    record_ledger_txn(store, issuer_did, None, "CLAIM_DEF", &resp).await;
+
+    // seqNo da transação CLAIM_DEF fica como tag para o registro local
+    let resp_val: serde_json::Value =
+        serde_json::from_str(&resp).unwrap_or_default();
+    let txn_seq_no = resp_val["result"]["txnMetadata"]["seqNo"]
+        .as_u64()
+        .or_else(|| resp_val["result"]["seqNo"].as_u64());
+    if let Some(seq) = txn_seq_no {
+        rewrite_entry_tags(
+            session,
+            "cred_def",
+            cred_def_id,
+            &[("ledger_seq_no", seq.to_string())],
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Núcleo de create_and_register_cred_def (pool já conectado). Reutilizado
+// pela publicação em lote (publishIssuerManifest).
+pub(crate) async fn register_cred_def_with_pool(
+    store: &aries_askar::Store,
+    pool: &indy_vdr::pool::PoolRunner,
+    issuer_did: String,
+    schema_id: String,
+    tag: String,
+    support_revocation: bool,
+) -> Result<String> {
+    // 1. IMPORTS CORRIGIDOS (SEPARADOS POR MÓDULO CORRETO)
+
+    // A. Estruturas de Dados (Schema, ID, Atributos) -> data_types
+    use anoncreds::data_types::issuer_id::IssuerId;
+    use anoncreds::data_types::schema::{AttributeNames, Schema, SchemaId};
+
+    // B. Configuração e Tipos de Assinatura -> types (conforme tentativas anteriores)
+    // Se der erro aqui, mova para data_types::cred_def
+    use anoncreds::types::{CredentialDefinitionConfig, SignatureType};
+
+    use anoncreds::issuer::create_credential_definition;
+
+    // 2. Sessão
+    let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
+
+    let mut session = store
+        .session(None)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+    // 3. GET_SCHEMA
+    let schema_id_ledger = indy_vdr::ledger::identifiers::SchemaId(schema_id.clone());
+    let get_schema_req = rb
+        .build_get_schema_request(None, &schema_id_ledger)
+        .map_err(|e| {
+            napi::Error::from_reason(format!("Erro build GET_SCHEMA: {}", e))
+        })?;
+
+    let get_schema_resp = send_request_async(pool, get_schema_req).await?;
+    let get_schema_json: serde_json::Value = serde_json::from_str(&get_schema_resp)
+        .map_err(|e| {
+            napi::Error::from_reason(format!("Erro parse GET_SCHEMA: {}", e))
+        })?;
+
+    let seq_no = get_schema_json["result"]["seqNo"].as_u64().ok_or_else(|| {
+        napi::Error::from_reason("SeqNo ausente (Schema não confirmado)")
+    })?;
+
+    // 4. ID Determinístico
+    let cred_def_id = format!("{}:3:CL:{}:{}", issuer_did, seq_no, tag);
+
+    // 5. Idempotência
+    if session
+        .fetch("cred_def_private", &cred_def_id, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro verificar wallet: {}", e)))?
+        .is_some()
+    {
+        return Ok(cred_def_id);
+    }
+
+    // 6. Reconstruir Schema
+    let schema_data_val = &get_schema_json["result"]["data"];
+
+    let schema_json_obj: serde_json::Value = if schema_data_val.is_string() {
+        serde_json::from_str(schema_data_val.as_str().unwrap()).map_err(|e| {
+            napi::Error::from_reason(format!("Erro parse Schema Data: {}", e))
+        })?
+    } else {
+        schema_data_val.clone()
+    };
+
+    let name = schema_json_obj["name"].as_str().unwrap_or("").to_string();
+    let version = schema_json_obj["version"]
+        .as_str()
+        .unwrap_or("")
+        .to_string();
+
+    let attr_vec: Vec<String> =
+        if let Some(arr) = schema_json_obj["attrNames"].as_array() {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        } else if let Some(arr) = schema_json_obj["attr_names"].as_array() {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        } else {
+            return Err(napi::Error::from_reason(
+                "Atributos não encontrados no Schema",
+            ));
+        };
+
+    let schema_issuer_did = schema_id
+        .split(':')
+        .next()
+        .unwrap_or(&issuer_did)
+        .to_string();
+
+    // CORREÇÃO: Usamos o wrapper AttributeNames(vec) importado corretamente
+    let schema_obj = Schema {
+        name,
+        version,
+        attr_names: AttributeNames(attr_vec),
+        issuer_id: IssuerId::new(schema_issuer_did).map_err(|e| {
+            napi::Error::from_reason(format!("IssuerID schema inválido: {}", e))
+        })?,
+    };
+
+    // 7. Criar Cred Def (Anoncreds)
+    let anon_schema_id = SchemaId::new(schema_id.clone())
+        .map_err(|e| napi::Error::from_reason(format!("SchemaID inválido: {}", e)))?;
+
+    let anon_issuer_id = IssuerId::new(issuer_did.clone())
+        .map_err(|e| napi::Error::from_reason(format!("IssuerID inválido: {}", e)))?;
+
+    let config = CredentialDefinitionConfig { support_revocation };
+
+    let (cred_def_pub, cred_def_priv, key_proof) = create_credential_definition(
+        anon_schema_id,
+        &schema_obj,
+        anon_issuer_id,
+        &tag,
+        SignatureType::CL,
+        config,
+    )
+    .map_err(|e| {
+        napi::Error::from_reason(format!("Erro criando CredDef Maths: {}", e))
+    })?;
+
+    // 8. Salvar na Wallet
+    let priv_json = serde_json::to_string(&cred_def_priv)
+        .map_err(|_| napi::Error::from_reason("Serializar priv"))?;
+    let key_proof_json = serde_json::to_string(&key_proof)
+        .map_err(|_| napi::Error::from_reason("Serializar key_proof"))?;
+    let pub_json = serde_json::to_string(&cred_def_pub)
+        .map_err(|_| napi::Error::from_reason("Serializar pub"))?;
+
+    session
+        .insert(
+            "cred_def_private",
+            &cred_def_id,
+            priv_json.as_bytes(),
+            Some(&vec![EntryTag::Encrypted(
+                "key_proof".to_string(),
+                key_proof_json,
+            )]),
+            None,
+        )
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Save Private: {}", e)))?;
+
+    session
+        .insert(
+            "cred_def",
+            &cred_def_id,
+            pub_json.as_bytes(),
+            Some(&vec![
+                EntryTag::Encrypted("schema_id".to_string(), schema_id.clone()),
+                EntryTag::Encrypted("created_at".to_string(), now_ts().to_string()),
+            ]),
+            None,
+        )
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Save Public: {}", e)))?;
+
+    // 9. Publicar no VDR
+    let cred_def_val = serde_json::to_value(&cred_def_pub)
+        .map_err(|e| napi::Error::from_reason(format!("Erro json value: {}", e)))?;
+    write_cred_def_to_ledger(
+        store,
+        pool,
+        &mut session,
+        &issuer_did,
+        &cred_def_id,
+        seq_no,
+        cred_def_val,
+    )
+    .await?;
+
+    session
+        .commit()
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Commit: {}", e)))?;
+
+    Ok(cred_def_id)
+}
+
+// Republica uma cred def que está na wallet mas não no ledger (escrita que
+// falhou depois de gravar as chaves locais). Usado por publishIssuerManifest.
+pub(crate) async fn republish_cred_def_with_pool(
+    store: &aries_askar::Store,
+    pool: &indy_vdr::pool::PoolRunner,
+    issuer_did: &str,
+    cred_def_id: &str,
+) -> Result<()> {
+    let schema_seq_no: u64 = cred_def_id
+        .split(':')
+        .nth(3)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| {
+            napi_err(
+                "CredDefIdInvalid",
+                format!("seqNo do schema ausente em {}", cred_def_id),
+            )
+        })?;
+
+    let mut session = store
+        .session(None)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+    let entry = session
+        .fetch("cred_def", cred_def_id, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+        .ok_or_else(|| {
+            napi_err(
+                "CredDefNotFound",
+                format!("Cred def pública não está na wallet: {}", cred_def_id),
+            )
+        })?;
+    let cred_def_val: serde_json::Value = serde_json::from_slice(&entry.value)
+        .map_err(|e| napi::Error::from_reason(format!("Erro parse cred_def: {}", e)))?;
+
+    write_cred_def_to_ledger(
+        store,
+        pool,
+        &mut session,
+        issuer_did,
+        cred_def_id,
+        schema_seq_no,
+        cred_def_val,
+    )
+    .await?;
+
+    session
+        .commit()
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Commit: {}", e)))?;
+    Ok(())
+}
+
 #[napi]
 impl IndyAgent {
     // =========================================================================
@@ -104,23 +894,6 @@ impl IndyAgent {
         schema_id: String,
         tag: String,
     ) -> Result<JsObject> {
-        // 1. IMPORTS CORRIGIDOS (SEPARADOS POR MÓDULO CORRETO)
-
-        // A. Estruturas de Dados (Schema, ID, Atributos) -> data_types
-        use anoncreds::data_types::issuer_id::IssuerId;
-        use anoncreds::data_types::schema::{AttributeNames, Schema, SchemaId};
-
-        // B. Configuração e Tipos de Assinatura -> types (conforme tentativas anteriores)
-        // Se der erro aqui, mova para data_types::cred_def
-        use anoncreds::types::{CredentialDefinitionConfig, SignatureType};
-
-        use anoncreds::issuer::create_credential_definition;
-
-        // C. VDR Imports
-        use indy_vdr::ledger::requests::cred_def::{
-            CredentialDefinition as VdrCredDefEnum, CredentialDefinitionV1 as VdrCredDefStruct,
-        };
-
         let store = match &self.store {
             Some(s) => s.clone(),
             None => return Err(Error::from_reason("Wallet fechada!")),
@@ -134,122 +907,429 @@ impl IndyAgent {
                 let pool = PoolBuilder::new(PoolConfig::default(), transactions)
                     .into_runner(None)
                     .map_err(|e| napi::Error::from_reason(format!("Erro pool: {}", e)))?;
-                let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
 
+                register_cred_def_with_pool(&store, &pool, issuer_did, schema_id, tag, false).await
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
+
+    // =========================================================================
+    //  REGISTRO LOCAL DE CRED DEFS (LIST / GET / EXPORT / DELETE)
+    // =========================================================================
+    // Lista as cred defs da wallet com resumo (schema, tag, revogação,
+    // seqNo no ledger e contadores de ofertas/emissões).
+    // Por padrão só as próprias (com chave privada); onlyOwn=false inclui todas.
+    #[napi]
+    pub fn list_cred_defs(&self, env: Env, only_own: Option<bool>) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let only_own = only_own.unwrap_or(true);
+
+        env.execute_tokio_future(
+            async move {
                 let mut session = store
                     .session(None)
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-                // 3. GET_SCHEMA
-                let schema_id_ledger = indy_vdr::ledger::identifiers::SchemaId(schema_id.clone());
-                let get_schema_req = rb
-                    .build_get_schema_request(None, &schema_id_ledger)
-                    .map_err(|e| {
-                        napi::Error::from_reason(format!("Erro build GET_SCHEMA: {}", e))
+                let entries = session
+                    .fetch_all(Some("cred_def"), None, None, None, false, false)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?;
+
+                let mut out: Vec<serde_json::Value> = Vec::new();
+                for entry in entries.iter() {
+                    let summary = cred_def_summary(&mut session, entry).await?;
+                    if only_own && !summary["hasPrivate"].as_bool().unwrap_or(false) {
+                        continue;
+                    }
+                    out.push(summary);
+                }
+
+                out.sort_by_key(|v| v["createdAt"].as_u64().unwrap_or(0));
+
+                serde_json::to_string(&out)
+                    .map_err(|e| napi::Error::from_reason(format!("Erro serializar lista: {}", e)))
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
+
+    // Resumo + cred def pública de um credDefId.
+    #[napi]
+    pub fn get_cred_def(&self, env: Env, cred_def_id: String) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+
+        env.execute_tokio_future(
+            async move {
+                let mut session = store
+                    .session(None)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+                let entry = session
+                    .fetch("cred_def", &cred_def_id, false)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+                    .ok_or_else(|| {
+                        napi_err(
+                            "CredDefNotFound",
+                            format!("CredDef não encontrada: {}", cred_def_id),
+                        )
                     })?;
 
-                let get_schema_resp = send_request_async(&pool, get_schema_req).await?;
-                let get_schema_json: serde_json::Value = serde_json::from_str(&get_schema_resp)
-                    .map_err(|e| {
-                        napi::Error::from_reason(format!("Erro parse GET_SCHEMA: {}", e))
+                let mut summary = cred_def_summary(&mut session, &entry).await?;
+                let pub_val: serde_json::Value =
+                    serde_json::from_slice(&entry.value).unwrap_or_default();
+                summary["credDef"] = pub_val;
+
+                Ok(summary.to_string())
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
+
+    // Exporta somente a parte pública da cred def (formato anoncreds + id),
+    // pronta para ser entregue a holders/verificadores.
+    #[napi]
+    pub fn export_cred_def_public(&self, env: Env, cred_def_id: String) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+
+        env.execute_tokio_future(
+            async move {
+                let mut session = store
+                    .session(None)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+                let entry = session
+                    .fetch("cred_def", &cred_def_id, false)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+                    .ok_or_else(|| {
+                        napi_err(
+                            "CredDefNotFound",
+                            format!("CredDef não encontrada: {}", cred_def_id),
+                        )
                     })?;
 
-                let seq_no = get_schema_json["result"]["seqNo"].as_u64().ok_or_else(|| {
-                    napi::Error::from_reason("SeqNo ausente (Schema não confirmado)")
-                })?;
+                let mut pub_val: serde_json::Value = serde_json::from_slice(&entry.value)
+                    .map_err(|e| napi_err("CredDefCorrupted", format!("JSON inválido: {}", e)))?;
+                if let Some(obj) = pub_val.as_object_mut() {
+                    obj.insert("id".to_string(), serde_json::json!(cred_def_id));
+                }
 
-                // 4. ID Determinístico
-                let cred_def_id = format!("{}:3:CL:{}:{}", issuer_did, seq_no, tag);
+                Ok(pub_val.to_string())
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
 
-                // 5. Idempotência
-                if session
+    // Remove cred_def + cred_def_private. Recusa (CredDefInUse) enquanto houver
+    // ofertas pendentes (sem emissão) apontando para a cred def; ofertas já
+    // emitidas são removidas junto.
+    #[napi]
+    pub fn delete_cred_def(&self, env: Env, cred_def_id: String) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+
+        env.execute_tokio_future(
+            async move {
+                let mut session = store
+                    .session(None)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+                let exists_pub = session
+                    .fetch("cred_def", &cred_def_id, false)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+                    .is_some();
+                let exists_priv = session
                     .fetch("cred_def_private", &cred_def_id, false)
                     .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro verificar wallet: {}", e)))?
-                    .is_some()
-                {
-                    return Ok(cred_def_id);
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("Erro fetch cred_def_private: {}", e))
+                    })?
+                    .is_some();
+                if !exists_pub && !exists_priv {
+                    return Err(napi_err(
+                        "CredDefNotFound",
+                        format!("CredDef não encontrada: {}", cred_def_id),
+                    ));
                 }
 
-                // 6. Reconstruir Schema
-                let schema_data_val = &get_schema_json["result"]["data"];
+                let offers = session
+                    .fetch_all(
+                        Some("cred_offer"),
+                        Some(TagFilter::is_eq("cred_def_id", &cred_def_id)),
+                        None,
+                        None,
+                        false,
+                        false,
+                    )
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch ofertas: {}", e)))?;
 
-                let schema_json_obj: serde_json::Value = if schema_data_val.is_string() {
-                    serde_json::from_str(schema_data_val.as_str().unwrap()).map_err(|e| {
-                        napi::Error::from_reason(format!("Erro parse Schema Data: {}", e))
+                let pending: Vec<String> = offers
+                    .iter()
+                    .filter(|e| tag_value(&e.tags, "issued_at").is_none())
+                    .map(|e| e.name.clone())
+                    .collect();
+                if !pending.is_empty() {
+                    return Err(napi_err(
+                        "CredDefInUse",
+                        format!(
+                            "CredDef {} possui {} oferta(s) pendente(s): {}",
+                            cred_def_id,
+                            pending.len(),
+                            pending.join(", ")
+                        ),
+                    ));
+                }
+
+                for o in offers.iter() {
+                    session.remove("cred_offer", &o.name).await.map_err(|e| {
+                        napi::Error::from_reason(format!("Erro remove oferta: {}", e))
+                    })?;
+                }
+                if exists_pub {
+                    session.remove("cred_def", &cred_def_id).await.map_err(|e| {
+                        napi::Error::from_reason(format!("Erro remove cred_def: {}", e))
+                    })?;
+                }
+                if exists_priv {
+                    session
+                        .remove("cred_def_private", &cred_def_id)
+                        .await
+                        .map_err(|e| {
+                            napi::Error::from_reason(format!("Erro remove cred_def_private: {}", e))
+                        })?;
+                }
+
+                session
+                    .commit()
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
+
+                Ok(serde_json::json!({
+                    "ok": true,
+                    "credDefId": cred_def_id,
+                    "removedOffers": offers.len(),
+                    "removedPrivate": exists_priv
+                })
+                .to_string())
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
+
+    // =========================================================================
+    //  BACKUP / TRANSFERÊNCIA DA CRED DEF PRIVADA
+    // =========================================================================
+    // Pacote cifrado (Argon2id + AES-256-GCM) com chave privada, key proof,
+    // cred def pública e referência ao schema. Só credDefId/schemaId ficam
+    // em claro, para identificação do arquivo.
+    #[napi]
+    pub fn export_cred_def_private(
+        &self,
+        env: Env,
+        cred_def_id: String,
+        export_pass: String,
+    ) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+
+        env.execute_tokio_future(
+            async move {
+                let mut session = store
+                    .session(None)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+                let priv_entry = session
+                    .fetch("cred_def_private", &cred_def_id, false)
+                    .await
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("Erro fetch cred_def_private: {}", e))
                     })?
-                } else {
-                    schema_data_val.clone()
-                };
+                    .ok_or_else(|| {
+                        napi_err(
+                            "CredDefPrivateNotFound",
+                            format!("CredDef privada não encontrada: {}", cred_def_id),
+                        )
+                    })?;
+                let pub_entry = session
+                    .fetch("cred_def", &cred_def_id, false)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+                    .ok_or_else(|| {
+                        napi_err(
+                            "CredDefNotFound",
+                            format!("CredDef não encontrada: {}", cred_def_id),
+                        )
+                    })?;
+
+                let key_proof_json = tag_value(&priv_entry.tags, "key_proof")
+                    .ok_or_else(|| napi_err("KeyProofMissing", "key_proof ausente nas tags"))?;
+                let pub_val: serde_json::Value =
+                    serde_json::from_slice(&pub_entry.value).unwrap_or_default();
+                let priv_val: serde_json::Value =
+                    serde_json::from_slice(&priv_entry.value).unwrap_or_default();
+                let key_proof_val: serde_json::Value =
+                    serde_json::from_str(&key_proof_json).unwrap_or_default();
 
-                let name = schema_json_obj["name"].as_str().unwrap_or("").to_string();
-                let version = schema_json_obj["version"]
+                let schema_id = tag_value(&pub_entry.tags, "schema_id")
+                    .or_else(|| pub_val["schemaId"].as_str().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                let (schema_name, schema_version) = split_schema_id(&schema_id);
+
+                let inner = serde_json::json!({
+                    "credDefId": cred_def_id,
+                    "schema": {
+                        "schemaId": schema_id,
+                        "name": schema_name,
+                        "version": schema_version
+                    },
+                    "credDef": pub_val,
+                    "credDefPrivate": priv_val,
+                    "keyCorrectnessProof": key_proof_val
+                });
+
+                let sealed = seal_with_pass(&export_pass, inner.to_string().as_bytes())?;
+
+                let package = serde_json::json!({
+                    "type": "ssi-creddef-private-export",
+                    "version": 1,
+                    "credDefId": cred_def_id,
+                    "schemaId": schema_id,
+                    "exportedAt": now_ts(),
+                    "sealed": sealed
+                });
+
+                Ok(package.to_string())
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
+
+    // Importa um pacote de exportCredDefPrivate. Antes de gravar, valida a
+    // key proof e a chave privada contra a cred def pública.
+    // Se a cred def já existir, recusa (CredDefAlreadyExists) salvo overwrite=true.
+    #[napi]
+    pub fn import_cred_def_private(
+        &self,
+        env: Env,
+        package_json: String,
+        export_pass: String,
+        overwrite: Option<bool>,
+    ) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let overwrite = overwrite.unwrap_or(false);
+
+        env.execute_tokio_future(
+            async move {
+                let package: serde_json::Value = serde_json::from_str(&package_json)
+                    .map_err(|e| napi_err("CredDefPackageInvalid", e.to_string()))?;
+                if package["type"].as_str() != Some("ssi-creddef-private-export") {
+                    return Err(napi_err(
+                        "CredDefPackageInvalid",
+                        "pacote não é ssi-creddef-private-export",
+                    ));
+                }
+
+                let plain = open_with_pass(&export_pass, &package["sealed"])?;
+                let inner: serde_json::Value = serde_json::from_slice(&plain)
+                    .map_err(|e| napi_err("CredDefPackageInvalid", e.to_string()))?;
+
+                let cred_def_id = inner["credDefId"]
+                    .as_str()
+                    .ok_or_else(|| napi_err("CredDefPackageInvalid", "credDefId ausente"))?
+                    .to_string();
+                if package["credDefId"].as_str() != Some(cred_def_id.as_str()) {
+                    return Err(napi_err(
+                        "CredDefPackageInvalid",
+                        "credDefId do envelope difere do conteúdo cifrado",
+                    ));
+                }
+                let schema_id = inner["schema"]["schemaId"]
                     .as_str()
-                    .unwrap_or("")
+                    .unwrap_or_default()
                     .to_string();
 
-                let attr_vec: Vec<String> =
-                    if let Some(arr) = schema_json_obj["attrNames"].as_array() {
-                        arr.iter()
-                            .map(|v| v.as_str().unwrap_or("").to_string())
-                            .collect()
-                    } else if let Some(arr) = schema_json_obj["attr_names"].as_array() {
-                        arr.iter()
-                            .map(|v| v.as_str().unwrap_or("").to_string())
-                            .collect()
-                    } else {
-                        return Err(napi::Error::from_reason(
-                            "Atributos não encontrados no Schema",
-                        ));
-                    };
+                let pub_json = inner["credDef"].to_string();
+                let priv_json = inner["credDefPrivate"].to_string();
+                let key_proof_json = inner["keyCorrectnessProof"].to_string();
 
-                let schema_issuer_did = schema_id
-                    .split(':')
-                    .next()
-                    .unwrap_or(&issuer_did)
-                    .to_string();
+                validate_cred_def_material(
+                    &cred_def_id,
+                    &schema_id,
+                    &pub_json,
+                    &priv_json,
+                    &key_proof_json,
+                )?;
 
-                // CORREÇÃO: Usamos o wrapper AttributeNames(vec) importado corretamente
-                let schema_obj = Schema {
-                    name,
-                    version,
-                    attr_names: AttributeNames(attr_vec),
-                    issuer_id: IssuerId::new(schema_issuer_did).map_err(|e| {
-                        napi::Error::from_reason(format!("IssuerID schema inválido: {}", e))
-                    })?,
-                };
+                let mut session = store
+                    .session(None)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-                // 7. Criar Cred Def (Anoncreds)
-                let anon_schema_id = SchemaId::new(schema_id.clone())
-                    .map_err(|e| napi::Error::from_reason(format!("SchemaID inválido: {}", e)))?;
+                let existing = session
+                    .fetch("cred_def_private", &cred_def_id, false)
+                    .await
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("Erro fetch cred_def_private: {}", e))
+                    })?;
+                if existing.is_some() && !overwrite {
+                    return Err(napi_err(
+                        "CredDefAlreadyExists",
+                        format!("CredDef já existe na wallet: {}", cred_def_id),
+                    ));
+                }
 
-                let anon_issuer_id = IssuerId::new(issuer_did.clone())
-                    .map_err(|e| napi::Error::from_reason(format!("IssuerID inválido: {}", e)))?;
+                // Preserva contadores/seqNo locais quando a pública já existe
+                let pub_tags_old = session
+                    .fetch("cred_def", &cred_def_id, false)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+                    .map(|e| e.tags)
+                    .unwrap_or_default();
 
-                let config = CredentialDefinitionConfig {
-                    support_revocation: false,
-                };
+                let mut pub_tags: Vec<EntryTag> = pub_tags_old
+                    .iter()
+                    .filter(|t| !matches!(t.name(), "schema_id" | "imported_at"))
+                    .map(|t| EntryTag::Encrypted(t.name().to_string(), t.value().to_string()))
+                    .collect();
+                pub_tags.push(EntryTag::Encrypted("schema_id".to_string(), schema_id.clone()));
+                pub_tags.push(EntryTag::Encrypted(
+                    "imported_at".to_string(),
+                    now_ts().to_string(),
+                ));
+                if tag_value(&pub_tags, "created_at").is_none() {
+                    pub_tags.push(EntryTag::Encrypted(
+                        "created_at".to_string(),
+                        now_ts().to_string(),
+                    ));
+                }
 
-                let (cred_def_pub, cred_def_priv, key_proof) = create_credential_definition(
-                    anon_schema_id,
-                    &schema_obj,
-                    anon_issuer_id,
-                    &tag,
-                    SignatureType::CL,
-                    config,
-                )
-                .map_err(|e| {
-                    napi::Error::from_reason(format!("Erro criando CredDef Maths: {}", e))
-                })?;
-
-                // 8. Salvar na Wallet
-                let priv_json = serde_json::to_string(&cred_def_priv)
-                    .map_err(|_| napi::Error::from_reason("Serializar priv"))?;
-                let key_proof_json = serde_json::to_string(&key_proof)
-                    .map_err(|_| napi::Error::from_reason("Serializar key_proof"))?;
-                let pub_json = serde_json::to_string(&cred_def_pub)
-                    .map_err(|_| napi::Error::from_reason("Serializar pub"))?;
+                remove_if_exists(&mut session, "cred_def_private", &cred_def_id).await?;
+                remove_if_exists(&mut session, "cred_def", &cred_def_id).await?;
 
                 session
                     .insert(
@@ -264,126 +1344,315 @@ impl IndyAgent {
                     )
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Save Private: {}", e)))?;
-
                 session
-                    .insert(
-                        "cred_def",
-                        &cred_def_id,
-                        pub_json.as_bytes(),
-                        Some(&vec![EntryTag::Encrypted(
-                            "schema_id".to_string(),
-                            schema_id.clone(),
-                        )]),
-                        None,
-                    )
+                    .insert("cred_def", &cred_def_id, pub_json.as_bytes(), Some(&pub_tags), None)
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Save Public: {}", e)))?;
 
-                // 9. Publicar no VDR
-                let mut cred_def_val = serde_json::to_value(&cred_def_pub)
-                    .map_err(|e| napi::Error::from_reason(format!("Erro json value: {}", e)))?;
-
-                if let Some(obj) = cred_def_val.as_object_mut() {
-                    obj.insert("ver".to_string(), serde_json::json!("1.0"));
-                    obj.insert("id".to_string(), serde_json::json!(cred_def_id.clone()));
-                    obj.insert(
-                        "schemaId".to_string(),
-                        serde_json::json!(seq_no.to_string()),
-                    );
-                    obj.insert("type".to_string(), serde_json::json!("CL"));
-                }
+                let issuer_did = cred_def_id.split(':').next().unwrap_or("").to_string();
+                let issuer_in_wallet = session
+                    .fetch("did", &issuer_did, false)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch did: {}", e)))?
+                    .is_some();
+
+                session
+                    .commit()
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
 
-                let vdr_struct: VdrCredDefStruct =
-                    serde_json::from_value(cred_def_val).map_err(|e| {
-                        napi::Error::from_reason(format!("Erro convert VDR Struct: {}", e))
+                Ok(serde_json::json!({
+                    "ok": true,
+                    "credDefId": cred_def_id,
+                    "schemaId": schema_id,
+                    "replaced": existing.is_some(),
+                    "issuerDid": issuer_did,
+                    "issuerDidInWallet": issuer_in_wallet
+                })
+                .to_string())
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
+
+    // =========================================================================
+    //  ROTAÇÃO DE CRED DEF (nova chave CL, mesma schema)
+    // =========================================================================
+    // Cria uma nova cred def com tag versionada (default -> default_v2), marca a
+    // antiga como "deprecated" (continua verificável e pode concluir emissões já
+    // solicitadas, mas não recebe novas ofertas) e refaz as ofertas ainda não
+    // solicitadas com a nova chave, mantendo o id local e as tags de cada oferta.
+    // movedOffers: [{ offerId, oldThreadId, threadId, offerJson }] a reenviar.
+    // Requer connect_network.
+    #[napi]
+    pub fn rotate_cred_def(&self, env: Env, old_cred_def_id: String) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => {
+                return Err(Error::from_reason(
+                    "Não conectado à rede. Execute connect_network antes.",
+                ))
+            }
+        };
+
+        env.execute_tokio_future(
+            async move {
+                let (issuer_did, schema_seq_no, old_tag) = split_cred_def_id(&old_cred_def_id);
+
+                // 1. Validar a cred def antiga e escolher a nova tag
+                let (schema_id, support_revocation, new_tag) = {
+                    let mut session = store
+                        .session(None)
+                        .await
+                        .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+                    let entry = session
+                        .fetch("cred_def", &old_cred_def_id, false)
+                        .await
+                        .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?
+                        .ok_or_else(|| {
+                            napi_err(
+                                "CredDefNotFound",
+                                format!("CredDef não encontrada: {}", old_cred_def_id),
+                            )
+                        })?;
+
+                    if tag_value(&entry.tags, "status").as_deref() == Some("deprecated") {
+                        return Err(napi_err(
+                            "CredDefDeprecated",
+                            format!(
+                                "CredDef {} já foi substituída por {}",
+                                old_cred_def_id,
+                                tag_value(&entry.tags, "superseded_by").unwrap_or_default()
+                            ),
+                        ));
+                    }
+
+                    let has_private = session
+                        .fetch("cred_def_private", &old_cred_def_id, false)
+                        .await
+                        .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?
+                        .is_some();
+                    if !has_private {
+                        return Err(napi_err(
+                            "CredDefPrivateMissing",
+                            format!("Sem chave privada para {}", old_cred_def_id),
+                        ));
+                    }
+
+                    let pub_val: serde_json::Value =
+                        serde_json::from_slice(&entry.value).unwrap_or_default();
+                    let schema_id = tag_value(&entry.tags, "schema_id").ok_or_else(|| {
+                        napi::Error::from_reason("Schema ID não encontrado nas tags")
                     })?;
 
-                let vdr_enum = VdrCredDefEnum::CredentialDefinitionV1(vdr_struct);
-
-                let did_obj = DidValue(issuer_did.clone());
-                let mut req = rb
-                    .build_cred_def_request(&did_obj, vdr_enum)
-                    .map_err(|e| napi::Error::from_reason(format!("Erro build req: {}", e)))?;
-
-                // 10. TAA
-                let taa_req = rb
-                    .build_get_txn_author_agreement_request(None, None)
-                    .map_err(|e| napi::Error::from_reason(format!("TAA req build: {}", e)))?;
-                let taa_resp = send_request_async(&pool, taa_req).await?;
-                let taa_val: serde_json::Value = serde_json::from_str(&taa_resp)
-                    .map_err(|_| napi::Error::from_reason("TAA parse error"))?;
-
-                if let Some(res) = taa_val.get("result") {
-                    if let Some(data) = res.get("data") {
-                        if !data.is_null() {
-                            let text = data.get("text").and_then(|t| t.as_str());
-                            let version = data.get("version").and_then(|v| v.as_str());
-                            let digest = data.get("digest").and_then(|d| d.as_str());
-
-                            if let (Some(t), Some(v)) = (text, version) {
-                                let ts = SystemTime::now()
-                                    .duration_since(UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs();
-                                let ts_midnight = (ts / 86400) * 86400;
-                                let taa = rb
-                                    .prepare_txn_author_agreement_acceptance_data(
-                                        Some(t),
-                                        Some(v),
-                                        digest,
-                                        "wallet_agreement",
-                                        ts_midnight,
-                                    )
-                                    .map_err(|e| {
-                                        napi::Error::from_reason(format!("TAA prep: {}", e))
-                                    })?;
-                                req.set_txn_author_agreement_acceptance(&taa).map_err(|e| {
-                                    napi::Error::from_reason(format!("TAA set: {}", e))
-                                })?;
-                            }
-                        }
+                    let seq = schema_seq_no
+                        .map(|n| n.to_string())
+                        .unwrap_or_default();
+                    let mut tag = next_versioned_tag(&old_tag);
+                    while session
+                        .fetch(
+                            "cred_def",
+                            &format!("{}:3:CL:{}:{}", issuer_did, seq, tag),
+                            false,
+                        )
+                        .await
+                        .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?
+                        .is_some()
+                    {
+                        tag = next_versioned_tag(&tag);
                     }
-                }
 
-                // 11. Assinar e Enviar
-                let did_entry = session
-                    .fetch("did", &issuer_did, false)
+                    (schema_id, !pub_val["value"]["revocation"].is_null(), tag)
+                };
+
+                // 2. Registrar a nova cred def (wallet + ledger)
+                let new_cred_def_id = register_cred_def_with_pool(
+                    &store,
+                    &pool,
+                    issuer_did.clone(),
+                    schema_id.clone(),
+                    new_tag.clone(),
+                    support_revocation,
+                )
+                .await?;
+
+                // 3. Encadear status e mover ofertas pendentes
+                let mut session = store
+                    .session(None)
                     .await
-                    .map_err(|e| napi::Error::from_reason(format!("Fetch DID: {}", e)))?
-                    .ok_or_else(|| napi::Error::from_reason("DID issuer não achado"))?;
+                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-                let did_json_val: serde_json::Value =
-                    serde_json::from_slice(&did_entry.value).unwrap();
-                let verkey = did_json_val["verkey"].as_str().unwrap();
+                let now = now_ts().to_string();
+                rewrite_entry_tags(
+                    &mut session,
+                    "cred_def",
+                    &old_cred_def_id,
+                    &[
+                        ("status", "deprecated".to_string()),
+                        ("superseded_by", new_cred_def_id.clone()),
+                        ("deprecated_at", now.clone()),
+                    ],
+                )
+                .await?;
+                rewrite_entry_tags(
+                    &mut session,
+                    "cred_def",
+                    &new_cred_def_id,
+                    &[
+                        ("status", "active".to_string()),
+                        ("supersedes", old_cred_def_id.clone()),
+                    ],
+                )
+                .await?;
 
-                let key_entry = session
-                    .fetch_key(verkey, false)
+                let offers = session
+                    .fetch_all(
+                        Some("cred_offer"),
+                        Some(TagFilter::is_eq("cred_def_id", &old_cred_def_id)),
+                        None,
+                        None,
+                        false,
+                        false,
+                    )
                     .await
-                    .map_err(|_| napi::Error::from_reason("Fetch key error"))?
-                    .ok_or_else(|| napi::Error::from_reason("Chave privada não achada"))?;
+                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch ofertas: {}", e)))?;
+
+                // Cada oferta movida ganha nonce novo: a troca antiga é abandonada e
+                // outra nasce em offer-sent; o chamador precisa reenviar movedOffers.
+                let mut moved: Vec<serde_json::Value> = Vec::new();
+                for offer in offers.iter() {
+                    if tag_value(&offer.tags, "issued_at").is_some() {
+                        continue;
+                    }
+                    let old_nonce = serde_json::from_slice::<serde_json::Value>(&offer.value)
+                        .ok()
+                        .and_then(|v| v["nonce"].as_str().map(|s| s.to_string()))
+                        .unwrap_or_default();
+                    // Já solicitada (ou encerrada): conclui na cred def antiga.
+                    let old_exchange = load_exchange(&mut session, ROLE_ISSUER, &old_nonce).await?;
+                    if old_exchange.as_ref().is_some_and(|r| r.state != "offer-sent") {
+                        continue;
+                    }
 
-                let signer_key = key_entry
-                    .load_local_key()
-                    .map_err(|_| napi::Error::from_reason("Load key error"))?;
+                    let offer_json =
+                        build_offer_for_cred_def(&mut session, &schema_id, &new_cred_def_id)
+                            .await?;
+                    let new_nonce = serde_json::from_str::<serde_json::Value>(&offer_json)
+                        .ok()
+                        .and_then(|v| v["nonce"].as_str().map(|s| s.to_string()))
+                        .unwrap_or_default();
 
-                let signature = signer_key
-                    .sign_message(req.get_signature_input().unwrap().as_bytes(), None)
-                    .map_err(|e| napi::Error::from_reason(format!("Sign error: {}", e)))?;
+                    // Mantém as tags da oferta (created_at, expires_at, ...) e troca
+                    // só o vínculo com a cred def.
+                    let replaced =
+                        ["cred_def_id", "schema_id", "nonce", "moved_from", "moved_at"];
+                    let mut tags: Vec<EntryTag> = offer
+                        .tags
+                        .iter()
+                        .filter(|t| !replaced.contains(&t.name()))
+                        .map(|t| EntryTag::Encrypted(t.name().to_string(), t.value().to_string()))
+                        .collect();
+                    tags.extend([
+                        EntryTag::Encrypted("cred_def_id".to_string(), new_cred_def_id.clone()),
+                        EntryTag::Encrypted("schema_id".to_string(), schema_id.clone()),
+                        EntryTag::Encrypted("nonce".to_string(), new_nonce.clone()),
+                        EntryTag::Encrypted("moved_from".to_string(), old_cred_def_id.clone()),
+                        EntryTag::Encrypted("moved_at".to_string(), now.clone()),
+                    ]);
+                    session
+                        .remove("cred_offer", &offer.name)
+                        .await
+                        .map_err(|e| napi::Error::from_reason(format!("Erro remover: {}", e)))?;
+                    session
+                        .insert("cred_offer", &offer.name, offer_json.as_bytes(), Some(&tags), None)
+                        .await
+                        .map_err(|e| napi::Error::from_reason(format!("Erro salvar: {}", e)))?;
 
-                req.set_signature(&signature)
-                    .map_err(|e| napi::Error::from_reason(format!("Set sig error: {}", e)))?;
+                    if old_exchange.is_some() {
+                        exchange_transition_to(
+                            &mut session,
+                            ROLE_ISSUER,
+                            &old_nonce,
+                            "abandoned",
+                            ExchangePatch {
+                                reason: Some("cred-def-rotated".to_string()),
+                                ..Default::default()
+                            },
+                        )
+                        .await?;
+                    }
+                    let expires_at = tag_value(&offer.tags, "expires_at")
+                        .and_then(|s| s.parse::<i64>().ok());
+                    exchange_advance(
+                        &mut session,
+                        ROLE_ISSUER,
+                        &new_nonce,
+                        "offer-sent",
+                        ExchangePatch {
+                            cred_def_id: Some(new_cred_def_id.clone()),
+                            schema_id: Some(schema_id.clone()),
+                            offer_id: Some(offer.name.clone()),
+                            counterparty_did: old_exchange
+                                .as_ref()
+                                .and_then(|r| r.counterparty_did.clone()),
+                            renews: old_exchange.as_ref().and_then(|r| r.renews.clone()),
+                            expires_at,
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
 
-                let _resp = send_request_async(&pool, req).await?;
+                    moved.push(serde_json::json!({
+                        "offerId": offer.name,
+                        "oldThreadId": old_nonce,
+                        "threadId": new_nonce,
+                        "offerJson": offer_json,
+                    }));
+                }
 
                 session
                     .commit()
                     .await
-                    .map_err(|e| napi::Error::from_reason(format!("Commit: {}", e)))?;
+                    .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
 
-                Ok(cred_def_id)
+                Ok(serde_json::json!({
+                    "ok": true,
+                    "oldCredDefId": old_cred_def_id,
+                    "newCredDefId": new_cred_def_id,
+                    "tag": new_tag,
+                    "schemaId": schema_id,
+                    "supportRevocation": support_revocation,
+                    "movedOffers": moved
+                })
+                .to_string())
             },
             |&mut env, data| env.create_string(&data),
         )
     }
 
+    // Cred def ativa (mesma regra de create_credential_offer) para um
+    // cred_def_id possivelmente deprecated ou para um schema_id.
+    #[napi]
+    pub fn get_active_cred_def(&self, env: Env, id: String) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+
+        env.execute_tokio_future(
+            async move {
+                let mut session = store
+                    .session(None)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+                resolve_active_cred_def(&mut session, &id).await
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
 }
@@ -1,12 +1,28 @@
 // src/modules/credentials.rs
-// use crate::modules::common::napi_err;
+use crate::modules::common::{
+    encode_typed_attr_value, napi_err, now_ts, remove_if_exists, tag_value, AttrType,
+    CONFIG_CATEGORY, KEY_DEFAULT_LINK_SECRET_ID,
+};
+use crate::modules::creddefs::{note_credential_issued, resolve_active_cred_def};
+use crate::modules::encoding::encode_untyped_attr_value;
+use crate::modules::exchanges::{
+    check_exchange_open, check_offer_usable, exchange_advance, load_exchange, ExchangePatch,
+    ROLE_HOLDER, ROLE_ISSUER,
+};
+use crate::modules::issued_credentials::record_issued_credential;
+use crate::modules::offer_expiry::offer_expires_at;
+use crate::modules::overlays::{credential_display, load_display_overlays};
+use crate::modules::schemas::load_attr_types_for_cred_def;
+use crate::modules::validity::{
+    credential_validity, load_validity_attrs, parse_time, values_raw_of,
+};
 use crate::IndyAgent;
 use napi::{Env, Error, JsObject, Result};
 use napi_derive::napi;
 // use num_bigint::BigUint;
-use once_cell::sync::Lazy;
 // use rand::Rng;
 // use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -29,12 +45,244 @@ use anoncreds::data_types::schema::SchemaId as AnonSchemaId;
 use anoncreds::types::{CredentialKeyCorrectnessProof, LinkSecret};
 
 // Imports do Askar
-use aries_askar::entry::{EntryTag, TagFilter};
+use aries_askar::entry::{Entry, EntryTag, TagFilter};
 
-// --- GLOBAL STATE ---
-// Tornamos pub(crate) para que o módulo presentations.rs possa ver, mas não o mundo externo (JS)
-pub(crate) static LINK_SECRET_CACHE: Lazy<Mutex<Option<Arc<LinkSecret>>>> =
-    Lazy::new(|| Mutex::new(None));
+// --- LINK SECRETS ---
+// Cache por agente (campo IndyAgent::link_secrets), indexado pelo id do link secret.
+// Cada agente tem uma única wallet aberta por vez e o cache é limpo em
+// wallet_open/wallet_close.
+pub(crate) type LinkSecretCache = Arc<Mutex<HashMap<String, Arc<LinkSecret>>>>;
+
+pub(crate) const LINK_SECRET_CATEGORY: &str = "link_secret";
+
+fn link_secret_not_found(id: &str) -> Error {
+    napi_err(
+        "LinkSecretNotFound",
+        format!("Link secret não encontrado: {}", id),
+    )
+}
+
+// Id do link secret default da wallet: config explícita; sem config vale o
+// legado "default" ou, na falta dele, o único link secret existente.
+pub(crate) async fn default_link_secret_id(
+    session: &mut aries_askar::Session,
+) -> Result<Option<String>> {
+    let cfg = session
+        .fetch(CONFIG_CATEGORY, KEY_DEFAULT_LINK_SECRET_ID, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch config: {}", e)))?
+        .map(|e| String::from_utf8(e.value.to_vec()).unwrap_or_default())
+        .filter(|s| !s.trim().is_empty());
+    if cfg.is_some() {
+        return Ok(cfg);
+    }
+
+    let entries = session
+        .fetch_all(Some(LINK_SECRET_CATEGORY), None, None, None, false, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch link secrets: {}", e)))?;
+    if entries.iter().any(|e| e.name == "default") {
+        return Ok(Some("default".to_string()));
+    }
+    if entries.len() == 1 {
+        return Ok(Some(entries[0].name.clone()));
+    }
+    Ok(None)
+}
+
+// Carrega o link secret pedido (ou o default da wallet) usando o cache do agente.
+// Devolve também o id efetivo, que deve ser registrado junto de requests/credenciais.
+pub(crate) async fn load_link_secret(
+    session: &mut aries_askar::Session,
+    cache: &LinkSecretCache,
+    requested: Option<&str>,
+) -> Result<(String, Arc<LinkSecret>)> {
+    let id = match requested.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(id) => id.to_string(),
+        None => default_link_secret_id(session).await?.ok_or_else(|| {
+            napi_err(
+                "LinkSecretNotFound",
+                "Nenhum link secret default configurado (use setDefaultLinkSecret)",
+            )
+        })?,
+    };
+
+    let cached = cache.lock().unwrap().get(&id).cloned();
+    if let Some(ls) = cached {
+        return Ok((id, ls));
+    }
+
+    let entry = session
+        .fetch(LINK_SECRET_CATEGORY, &id, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro DB LS: {}", e)))?
+        .ok_or_else(|| link_secret_not_found(&id))?;
+    let seed_str = String::from_utf8(entry.value.to_vec()).unwrap_or_default();
+    let ls = LinkSecret::try_from(seed_str.as_str())
+        .map_err(|e| Error::from_reason(format!("Erro LS math: {:?}", e)))?;
+
+    let arc_ls = Arc::new(ls);
+    cache.lock().unwrap().insert(id.clone(), arc_ls.clone());
+    Ok((id, arc_ls))
+}
+
+// Formato do valor armazenado: "anoncreds" (gerador nativo, forma canônica) ou
+// "u128_seed" (legado: u128 aleatório usado diretamente como link secret, entropia
+// insuficiente; ver linkSecretMigrationReport).
+pub(crate) const LINK_SECRET_FORMAT_NATIVE: &str = "anoncreds";
+pub(crate) const LINK_SECRET_FORMAT_U128: &str = "u128_seed";
+
+pub(crate) fn link_secret_format(tags: &[EntryTag], value: &[u8]) -> &'static str {
+    if let Some(t) = tags.iter().find(|t| t.name() == "format") {
+        if t.value() == LINK_SECRET_FORMAT_NATIVE {
+            return LINK_SECRET_FORMAT_NATIVE;
+        }
+    }
+    let s = std::str::from_utf8(value).unwrap_or_default().trim();
+    if s.parse::<u128>().is_ok() {
+        LINK_SECRET_FORMAT_U128
+    } else {
+        LINK_SECRET_FORMAT_NATIVE
+    }
+}
+
+// Renovação no holder: store_credential com { replaces } marca a credencial
+// antiga com status "superseded" (+ superseded_by / superseded_at) e a nova com
+// "replaces". Substituídas somem das listagens padrão e da seleção de
+// apresentação, mas continuam na wallet (histórico: getCredentialHistory).
+pub(crate) const CREDENTIAL_STATUS_SUPERSEDED: &str = "superseded";
+
+pub(crate) fn is_superseded(tags: &[EntryTag]) -> bool {
+    tags.iter()
+        .any(|t| t.name() == "status" && t.value() == CREDENTIAL_STATUS_SUPERSEDED)
+}
+
+// Listagens padrão: descarta as credenciais substituídas.
+fn without_superseded(entries: Vec<Entry>) -> Vec<Entry> {
+    entries
+        .into_iter()
+        .filter(|e| !is_superseded(&e.tags))
+        .collect()
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct StoreCredentialOptions {
+    #[serde(default)]
+    replaces: Option<String>,
+}
+
+// Troca/insere tags de uma credencial armazenada (upsert: remove + insert).
+// Sem commit; credencial inexistente é ignorada.
+async fn set_credential_tags(
+    session: &mut aries_askar::Session,
+    credential_id: &str,
+    set: &[(&str, String)],
+) -> Result<()> {
+    let entry = match session
+        .fetch("credential", credential_id, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch credencial: {}", e)))?
+    {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+    let mut tags: Vec<EntryTag> = entry
+        .tags
+        .iter()
+        .filter(|t| !set.iter().any(|(n, _)| *n == t.name()))
+        .map(|t| EntryTag::Encrypted(t.name().to_string(), t.value().to_string()))
+        .collect();
+    for (name, value) in set {
+        tags.push(EntryTag::Encrypted(name.to_string(), value.clone()));
+    }
+    session
+        .remove("credential", credential_id)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro remove (tags): {}", e)))?;
+    session
+        .insert(
+            "credential",
+            credential_id,
+            entry.value.as_ref(),
+            Some(&tags),
+            None,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro insert (tags): {}", e)))
+}
+
+// Link secret novo pelo gerador do AnonCreds; devolve a forma canônica + objeto.
+pub(crate) fn generate_link_secret() -> Result<(String, LinkSecret)> {
+    let ls = anoncreds::prover::create_link_secret()
+        .map_err(|e| Error::from_reason(format!("Erro criar link secret: {}", e)))?;
+    let value: String = ls
+        .try_into()
+        .map_err(|e| Error::from_reason(format!("Erro serializar link secret: {:?}", e)))?;
+    let ls = LinkSecret::try_from(value.as_str())
+        .map_err(|e| Error::from_reason(format!("Erro LS math: {:?}", e)))?;
+    Ok((value, ls))
+}
+
+// Grava um link secret (sem commit). O primeiro da wallet vira o default; se o
+// default ainda era implícito (legado "default"/único), fixa-o na config antes.
+pub(crate) async fn insert_link_secret(
+    session: &mut aries_askar::Session,
+    id: &str,
+    value: &str,
+    extra_tags: Vec<EntryTag>,
+) -> Result<()> {
+    let has_default_cfg = session
+        .fetch(CONFIG_CATEGORY, KEY_DEFAULT_LINK_SECRET_ID, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch config: {}", e)))?
+        .is_some();
+    if !has_default_cfg {
+        let default_id = default_link_secret_id(session)
+            .await?
+            .unwrap_or_else(|| id.to_string());
+        session
+            .insert(
+                CONFIG_CATEGORY,
+                KEY_DEFAULT_LINK_SECRET_ID,
+                default_id.as_bytes(),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar config: {}", e)))?;
+    }
+
+    let format = link_secret_format(&[], value.as_bytes());
+    let mut tags = vec![
+        EntryTag::Encrypted(
+            "created_at".to_string(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+                .to_string(),
+        ),
+        EntryTag::Encrypted("format".to_string(), format.to_string()),
+    ];
+    tags.extend(
+        extra_tags
+            .into_iter()
+            .filter(|t| !matches!(t.name(), "created_at" | "format")),
+    );
+
+    session
+        .insert(
+            LINK_SECRET_CATEGORY,
+            id,
+            value.as_bytes(),
+            Some(&tags),
+            None,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro salvar LS: {}", e)))?;
+    Ok(())
+}
 
 // // --- HELPER FUNCTIONS ---
 // fn hash_string_to_int_str(s: &str) -> String {
@@ -43,17 +291,188 @@ pub(crate) static LINK_SECRET_CACHE: Lazy<Mutex<Option<Arc<LinkSecret>>>> =
 //     n.to_str_radix(10) // decimal string
 // }
 
+// Valores de credencial a partir do JSON { atributo: valor }: atributos com tipo
+// no overlay do schema são validados/codificados canonicamente; os demais usam a
+// regra legada do registro (modules/encoding.rs).
+pub(crate) fn build_credential_values(
+    attr_types: &BTreeMap<String, AttrType>,
+    values_map: serde_json::Map<String, serde_json::Value>,
+) -> Result<anoncreds::types::CredentialValues> {
+    use anoncreds::types::{AttributeValues, CredentialValues};
+
+    let mut cred_values = CredentialValues::default();
+    for (key, val) in values_map {
+        let (raw, encoded) = match attr_types.get(&key) {
+            Some(t) => encode_typed_attr_value(&key, t, &val)?,
+            None => encode_untyped_attr_value(&val),
+        };
+        cred_values.0.insert(key, AttributeValues { raw, encoded });
+    }
+    Ok(cred_values)
+}
+
+// Material do emissor (parte pública + privada) de uma cred def própria.
+pub(crate) async fn load_issuer_cred_def(
+    session: &mut aries_askar::Session,
+    cred_def_id: &str,
+) -> Result<(
+    anoncreds::data_types::cred_def::CredentialDefinition,
+    anoncreds::data_types::cred_def::CredentialDefinitionPrivate,
+)> {
+    let priv_entry = session
+        .fetch("cred_def_private", cred_def_id, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro DB Priv: {}", e)))?
+        .ok_or_else(|| napi::Error::from_reason("CredDef Private não achada"))?;
+    let cred_def_priv = serde_json::from_slice(&priv_entry.value)
+        .map_err(|e| napi::Error::from_reason(format!("Erro Parse Priv: {}", e)))?;
+
+    let pub_entry = session
+        .fetch("cred_def", cred_def_id, false)
+        .await
+        .map_err(|_e| napi::Error::from_reason("Erro DB Pub"))?
+        .ok_or_else(|| napi::Error::from_reason("CredDef Public não achada"))?;
+    let cred_def_pub = serde_json::from_slice(&pub_entry.value)
+        .map_err(|e| napi::Error::from_reason(format!("Erro Parse Pub: {}", e)))?;
+
+    Ok((cred_def_pub, cred_def_priv))
+}
+
+// Cria a oferta, grava em "cred_offer" (offer_id_local) e abre a troca do
+// emissor (sem commit). `extra` acrescenta campos ao registro da troca;
+// ttl_secs None = prazo padrão da wallet (modules/offer_expiry.rs).
+// Retorna (offer_json, nonce).
+pub(crate) async fn create_offer_in_session(
+    session: &mut aries_askar::Session,
+    cred_def_id: &str,
+    offer_id_local: &str,
+    ttl_secs: Option<u32>,
+    extra: ExchangePatch,
+) -> Result<(String, String)> {
+    // 0. Cred def ativa: deprecated segue para a sucessora; schema_id
+    //    resolve a cred def própria ativa mais recente do schema.
+    let cred_def_id = resolve_active_cred_def(session, cred_def_id).await?;
+
+    // 1. Buscar a Chave Privada (Key Proof)
+    let priv_entry = session
+        .fetch("cred_def_private", &cred_def_id, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro DB: {}", e)))?
+        .ok_or_else(|| {
+            napi::Error::from_reason(format!("CredDef Private não encontrada: {}", cred_def_id))
+        })?;
+
+    // Nota: Assumimos que a key_proof foi salva na TAG durante a criação da CredDef.
+    let key_proof_json = priv_entry
+        .tags
+        .iter()
+        .find(|t| t.name() == "key_proof")
+        .map(|t| t.value().to_string())
+        .ok_or_else(|| napi::Error::from_reason("Key Proof não encontrado nas tags"))?;
+
+    let key_proof: CredentialKeyCorrectnessProof = serde_json::from_str(&key_proof_json)
+        .map_err(|_e| napi::Error::from_reason("Erro parse KeyProof"))?;
+
+    // 2. Buscar a CredDef Pública (para pegar o SchemaID)
+    let pub_entry = session
+        .fetch("cred_def", &cred_def_id, false)
+        .await
+        .map_err(|_e| napi::Error::from_reason("Erro DB Public"))?
+        .ok_or_else(|| napi::Error::from_reason("CredDef Public não encontrada"))?;
+
+    let schema_id_str = pub_entry
+        .tags
+        .iter()
+        .find(|t| t.name() == "schema_id")
+        .map(|t| t.value().to_string())
+        .ok_or_else(|| napi::Error::from_reason("Schema ID não encontrado tags"))?;
+
+    // 3. Gerar a Oferta (Anoncreds)
+    let anon_schema_id = AnonSchemaId::new(schema_id_str.clone())
+        .map_err(|_e| napi::Error::from_reason("SchemaId inválido"))?;
+
+    let anon_cred_def_id = AnonCredDefId::new(cred_def_id.clone())
+        .map_err(|_e| napi::Error::from_reason("CredDefId inválido"))?;
+
+    let offer = anoncreds::issuer::create_credential_offer(
+        anon_schema_id, // Passar referência se exigido
+        anon_cred_def_id,
+        &key_proof,
+    )
+    .map_err(|e| napi::Error::from_reason(format!("Erro criando oferta: {}", e)))?;
+
+    let offer_json = serde_json::to_string(&offer)
+        .map_err(|_e| napi::Error::from_reason("Erro serializando oferta"))?;
+
+    // =============================================================
+    // 4. PERSISTÊNCIA COM DATA
+    // =============================================================
+
+    let now_ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default() // unwrap_or_default é mais seguro que map_err aqui
+        .as_secs()
+        .to_string();
+
+    let mut tags = vec![
+        EntryTag::Encrypted("cred_def_id".to_string(), cred_def_id.clone()),
+        EntryTag::Encrypted("schema_id".to_string(), schema_id_str.clone()),
+        EntryTag::Encrypted("created_at".to_string(), now_ts),
+        EntryTag::Encrypted("nonce".to_string(), offer.nonce.to_string()),
+    ];
+    let expires_at = offer_expires_at(session, ttl_secs).await?;
+    if let Some(exp) = expires_at {
+        tags.push(EntryTag::Encrypted(
+            "expires_at".to_string(),
+            exp.to_string(),
+        ));
+    }
+
+    session
+        .insert(
+            "cred_offer",
+            offer_id_local,
+            offer_json.as_bytes(),
+            Some(&tags),
+            None,
+        )
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro ao salvar oferta: {}", e)))?;
+
+    // Troca do lado emissor (thread = nonce da oferta)
+    exchange_advance(
+        session,
+        ROLE_ISSUER,
+        &offer.nonce.to_string(),
+        "offer-sent",
+        ExchangePatch {
+            cred_def_id: Some(cred_def_id.clone()),
+            schema_id: Some(schema_id_str),
+            offer_id: Some(offer_id_local.to_string()),
+            expires_at,
+            ..extra
+        },
+    )
+    .await?;
+
+    Ok((offer_json, offer.nonce.to_string()))
+}
+
 #[napi]
 impl IndyAgent {
     // =========================================================================
     //  MÉTODOS DE EMISSÃO (ISSUER) - ATUALIZADO COM PERSISTÊNCIA
     // =========================================================================
+    // cred_def_id pode ser uma cred def deprecated (usa a sucessora ativa) ou um
+    // schema_id (usa a cred def própria ativa do schema). A oferta carrega o id efetivo.
+    // ttl_secs: prazo da oferta em segundos (omitido = padrão da wallet, 0 = sem expiração).
     #[napi]
     pub fn create_credential_offer(
         &self,
         env: Env,
         cred_def_id: String,
         offer_id_local: String,
+        ttl_secs: Option<u32>,
     ) -> Result<JsObject> {
         let store = match &self.store {
             Some(s) => s.clone(),
@@ -68,89 +487,14 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-                // 1. Buscar a Chave Privada (Key Proof)
-                let priv_entry = session
-                    .fetch("cred_def_private", &cred_def_id, false)
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro DB: {}", e)))?
-                    .ok_or_else(|| {
-                        napi::Error::from_reason(format!(
-                            "CredDef Private não encontrada: {}",
-                            cred_def_id
-                        ))
-                    })?;
-
-                // Nota: Assumimos que a key_proof foi salva na TAG durante a criação da CredDef.
-                let key_proof_json = priv_entry
-                    .tags
-                    .iter()
-                    .find(|t| t.name() == "key_proof")
-                    .map(|t| t.value().to_string())
-                    .ok_or_else(|| napi::Error::from_reason("Key Proof não encontrado nas tags"))?;
-
-                let key_proof: CredentialKeyCorrectnessProof =
-                    serde_json::from_str(&key_proof_json)
-                        .map_err(|_e| napi::Error::from_reason("Erro parse KeyProof"))?;
-
-                // 2. Buscar a CredDef Pública (para pegar o SchemaID)
-                let pub_entry = session
-                    .fetch("cred_def", &cred_def_id, false)
-                    .await
-                    .map_err(|_e| napi::Error::from_reason("Erro DB Public"))?
-                    .ok_or_else(|| napi::Error::from_reason("CredDef Public não encontrada"))?;
-
-                let schema_id_str = pub_entry
-                    .tags
-                    .iter()
-                    .find(|t| t.name() == "schema_id")
-                    .map(|t| t.value().to_string())
-                    .ok_or_else(|| napi::Error::from_reason("Schema ID não encontrado tags"))?;
-
-                // 3. Gerar a Oferta (Anoncreds)
-                let anon_schema_id = AnonSchemaId::new(schema_id_str.clone())
-                    .map_err(|_e| napi::Error::from_reason("SchemaId inválido"))?;
-
-                let anon_cred_def_id = AnonCredDefId::new(cred_def_id.clone())
-                    .map_err(|_e| napi::Error::from_reason("CredDefId inválido"))?;
-
-                let offer = anoncreds::issuer::create_credential_offer(
-                    anon_schema_id, // Passar referência se exigido
-                    anon_cred_def_id,
-                    &key_proof,
+                let (offer_json, _) = create_offer_in_session(
+                    &mut session,
+                    &cred_def_id,
+                    &offer_id_local,
+                    ttl_secs,
+                    ExchangePatch::default(),
                 )
-                .map_err(|e| napi::Error::from_reason(format!("Erro criando oferta: {}", e)))?;
-
-                let offer_json = serde_json::to_string(&offer)
-                    .map_err(|_e| napi::Error::from_reason("Erro serializando oferta"))?;
-
-                // =============================================================
-                // 4. PERSISTÊNCIA COM DATA
-                // =============================================================
-
-                let now_ts = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or_default() // unwrap_or_default é mais seguro que map_err aqui
-                    .as_secs()
-                    .to_string();
-
-                let tags = vec![
-                    EntryTag::Encrypted("cred_def_id".to_string(), cred_def_id.clone()),
-                    EntryTag::Encrypted("schema_id".to_string(), schema_id_str),
-                    EntryTag::Encrypted("created_at".to_string(), now_ts),
-                ];
-
-                session
-                    .insert(
-                        "cred_offer",
-                        &offer_id_local,
-                        offer_json.as_bytes(),
-                        Some(&tags),
-                        None,
-                    )
-                    .await
-                    .map_err(|e| {
-                        napi::Error::from_reason(format!("Erro ao salvar oferta: {}", e))
-                    })?;
+                .await?;
 
                 // 5. CORREÇÃO CRÍTICA: COMMIT OBRIGATÓRIO
                 // Sem isso, o registro não é salvo no SQLite.
@@ -208,6 +552,12 @@ impl IndyAgent {
                                 "created_at".to_string(),
                                 serde_json::Value::String(created_at),
                             );
+                            if let Some(exp) = tag_value(&entry.tags, "expires_at") {
+                                obj.insert(
+                                    "expires_at".to_string(),
+                                    serde_json::Value::String(exp),
+                                );
+                            }
                         }
                         results.push(val);
                     }
@@ -401,14 +751,11 @@ impl IndyAgent {
 
     #[napi]
     pub fn create_link_secret(&self, env: Env, link_secret_id: String) -> Result<JsObject> {
-        // IMPORTS
-        use anoncreds::types::LinkSecret;
-        use rand::Rng; // Importante: Trait necessário para usar .gen()
-
         let store = match &self.store {
             Some(s) => s.clone(),
             None => return Err(Error::from_reason("Wallet fechada!")),
         };
+        let cache = self.link_secrets.clone();
 
         env.execute_tokio_future(
             async move {
@@ -419,7 +766,7 @@ impl IndyAgent {
 
                 // 1. Verificar se já existe
                 if session
-                    .fetch("link_secret", &link_secret_id, false)
+                    .fetch(LINK_SECRET_CATEGORY, &link_secret_id, false)
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro check DB: {}", e)))?
                     .is_some()
@@ -427,52 +774,135 @@ impl IndyAgent {
                     return Ok(link_secret_id);
                 }
 
-                // 2. Gerar Seed Manualmente (CORREÇÃO DE THREAD SAFETY)
-                // O problema anterior era manter 'rng' vivo através de um .await.
-                // Aqui geramos e transformamos em String na mesma linha.
-                // O objeto ThreadRng é descartado imediatamente após essa linha.
-                let seed_str = rand::thread_rng().gen::<u128>().to_string();
-
-                // 3. Criar o Objeto Matemático (Isso é rápido e síncrono)
-                // LinkSecret::try_from aceita uma string numérica (que é a nossa seed)
-                let link_secret = LinkSecret::try_from(seed_str.as_str()).map_err(|e| {
-                    napi::Error::from_reason(format!("Erro criar LS matemático: {:?}", e))
-                })?;
-
-                // 4. Atualizar Cache (Memória - Síncrono)
-                {
-                    let mut cache = LINK_SECRET_CACHE.lock().unwrap();
-                    *cache = Some(std::sync::Arc::new(link_secret));
-                }
-
-                // 5. Salvar a SEED no Banco (Persistência - Assíncrono)
-                // Agora podemos fazer .await tranquilamente, pois 'rng' já morreu lá em cima.
-                session
-                    .insert(
-                        "link_secret",
-                        &link_secret_id,
-                        seed_str.as_bytes(), // Salvamos a string da seed
-                        None,
-                        None,
-                    )
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro salvar LS: {}", e)))?;
+                // 2. Gerar com o gerador nativo do AnonCreds (tamanho completo) e
+                //    persistir a forma canônica (decimal do BigNumber)
+                let (value, link_secret) = generate_link_secret()?;
+                insert_link_secret(&mut session, &link_secret_id, &value, Vec::new()).await?;
 
                 session
                     .commit()
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro commit LS: {}", e)))?;
 
+                // 3. Atualizar Cache (só depois do commit)
+                cache
+                    .lock()
+                    .unwrap()
+                    .insert(link_secret_id.clone(), Arc::new(link_secret));
+
                 Ok(link_secret_id)
             },
             |&mut env, data| env.create_string(&data),
         )
     }
 
+    // Link secrets da wallet aberta: [{ id, isDefault, format, weak, createdAt }].
+    #[napi]
+    pub async fn list_link_secrets(&self) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let default_id = default_link_secret_id(&mut session).await?;
+        let entries = session
+            .fetch_all(Some(LINK_SECRET_CATEGORY), None, None, None, false, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch link secrets: {}", e)))?;
+
+        let mut out: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|e| {
+                let created_at = e
+                    .tags
+                    .iter()
+                    .find(|t| t.name() == "created_at")
+                    .and_then(|t| t.value().parse::<u64>().ok());
+                let format = link_secret_format(&e.tags, &e.value);
+                serde_json::json!({
+                    "id": &e.name,
+                    "isDefault": default_id.as_deref() == Some(e.name.as_str()),
+                    "format": format,
+                    "weak": format == LINK_SECRET_FORMAT_U128,
+                    "createdAt": created_at
+                })
+            })
+            .collect();
+        out.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+        Ok(serde_json::Value::Array(out).to_string())
+    }
+
+    // Define o link secret default (usado quando create_credential_request /
+    // create_presentation_v2 não recebem um id explícito).
+    #[napi]
+    pub async fn set_default_link_secret(&self, link_secret_id: String) -> Result<bool> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        if session
+            .fetch(LINK_SECRET_CATEGORY, &link_secret_id, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro DB LS: {}", e)))?
+            .is_none()
+        {
+            return Err(link_secret_not_found(&link_secret_id));
+        }
+
+        remove_if_exists(&mut session, CONFIG_CATEGORY, KEY_DEFAULT_LINK_SECRET_ID).await?;
+        session
+            .insert(
+                CONFIG_CATEGORY,
+                KEY_DEFAULT_LINK_SECRET_ID,
+                link_secret_id.as_bytes(),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar config: {}", e)))?;
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(true)
+    }
+
+    #[napi]
+    pub async fn get_default_link_secret(&self) -> Result<Option<String>> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        default_link_secret_id(&mut session).await
+    }
+
     /// 2. Armazena a Oferta Recebida
+    /// expires_at: prazo informado pelo emissor (epoch s ou ISO); omitido = padrão da wallet.
     #[napi]
     // CORREÇÃO: Retorno alterado de Result<String> para Result<JsObject>
-    pub fn store_received_offer(&self, env: Env, offer_json: String) -> Result<JsObject> {
+    pub fn store_received_offer(
+        &self,
+        env: Env,
+        offer_json: String,
+        expires_at: Option<String>,
+    ) -> Result<JsObject> {
         let store = match &self.store {
             Some(s) => s.clone(),
             None => return Err(Error::from_reason("Wallet fechada!")),
@@ -503,12 +933,34 @@ impl IndyAgent {
                     .as_secs()
                     .to_string();
 
-                let tags = vec![
-                    EntryTag::Encrypted("schema_id".to_string(), schema_id),
-                    EntryTag::Encrypted("cred_def_id".to_string(), cred_def_id),
+                let expires_at = match expires_at.as_deref().map(str::trim) {
+                    Some(s) if !s.is_empty() => Some(
+                        parse_time(s)
+                            .map(|(t, _)| t)
+                            .ok_or_else(|| napi_err("OfferExpiryInvalid", s.to_string()))?,
+                    ),
+                    // sem prazo explícito: o já recebido (issueV2Receive) ou o padrão
+                    _ => match load_exchange(&mut session, ROLE_HOLDER, &nonce)
+                        .await?
+                        .and_then(|r| r.expires_at)
+                    {
+                        Some(exp) => Some(exp),
+                        None => offer_expires_at(&mut session, None).await?,
+                    },
+                };
+
+                let mut tags = vec![
+                    EntryTag::Encrypted("schema_id".to_string(), schema_id.clone()),
+                    EntryTag::Encrypted("cred_def_id".to_string(), cred_def_id.clone()),
                     EntryTag::Encrypted("status".to_string(), "pending".to_string()),
                     EntryTag::Encrypted("received_at".to_string(), now_ts),
                 ];
+                if let Some(exp) = expires_at {
+                    tags.push(EntryTag::Encrypted(
+                        "expires_at".to_string(),
+                        exp.to_string(),
+                    ));
+                }
 
                 session
                     .insert(
@@ -521,6 +973,24 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro salvar oferta: {}", e)))?;
 
+                // Troca do lado holder (thread = nonce da oferta)
+                let issuer_did = cred_def_id.split(':').next().map(|s| s.to_string());
+                exchange_advance(
+                    &mut session,
+                    ROLE_HOLDER,
+                    &nonce,
+                    "offer-received",
+                    ExchangePatch {
+                        cred_def_id: Some(cred_def_id),
+                        schema_id: Some(schema_id),
+                        offer_id: Some(id_local.clone()),
+                        counterparty_did: issuer_did,
+                        expires_at,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
                 // =============================================================
                 // CORREÇÃO FUNDAMENTAL: COMMIT
                 // =============================================================
@@ -538,6 +1008,8 @@ impl IndyAgent {
     // =========================================================================
     //  SOLICITAÇÃO: CRIAR CREDENTIAL REQUEST (CORRIGIDO: ENTROPY NONE)
     // =========================================================================
+    // link_secret_id vazio usa o default da wallet. O id efetivo fica registrado no
+    // request_metadata e é o mesmo que store_credential usa depois.
     #[napi]
     pub fn create_credential_request(
         &self,
@@ -549,12 +1021,12 @@ impl IndyAgent {
     ) -> Result<JsObject> {
         use anoncreds::data_types::cred_def::CredentialDefinition;
         use anoncreds::data_types::cred_offer::CredentialOffer;
-        use anoncreds::types::LinkSecret;
 
         let store = match &self.store {
             Some(s) => s.clone(),
             None => return Err(Error::from_reason("Wallet fechada!")),
         };
+        let cache = self.link_secrets.clone();
 
         env.execute_tokio_future(
             async move {
@@ -563,30 +1035,9 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-                // 1. LINK SECRET
-                let link_secret = {
-                    let cached_ls = { LINK_SECRET_CACHE.lock().unwrap().clone() };
-                    if let Some(ls) = cached_ls {
-                        ls
-                    } else {
-                        let entry = session
-                            .fetch("link_secret", &link_secret_id, false)
-                            .await
-                            .map_err(|e| napi::Error::from_reason(format!("Erro DB LS: {}", e)))?
-                            .ok_or_else(|| {
-                                napi::Error::from_reason("Link Secret não encontrado")
-                            })?;
-                        let seed_str = String::from_utf8(entry.value.to_vec()).unwrap_or_default();
-                        let ls_obj = LinkSecret::try_from(seed_str.as_str()).map_err(|e| {
-                            napi::Error::from_reason(format!("Erro LS math: {:?}", e))
-                        })?;
-                        let arc_ls = std::sync::Arc::new(ls_obj);
-                        {
-                            *LINK_SECRET_CACHE.lock().unwrap() = Some(arc_ls.clone());
-                        }
-                        arc_ls
-                    }
-                };
+                // 1. LINK SECRET (id vazio = default da wallet)
+                let (link_secret_id, link_secret) =
+                    load_link_secret(&mut session, &cache, Some(&link_secret_id)).await?;
 
                 // 2. PARSE CRED DEF
                 let initial_json: serde_json::Value = serde_json::from_str(&cred_def_json)
@@ -696,6 +1147,20 @@ impl IndyAgent {
                 let offer: CredentialOffer = serde_json::from_str(&offer_json)
                     .map_err(|e| napi::Error::from_reason(format!("Erro JSON Offer: {}", e)))?;
 
+                // Oferta vencida ou já usada: OfferExpired / OfferAlreadyUsed
+                let offer_nonce = offer.nonce.to_string();
+                check_offer_usable(&mut session, ROLE_HOLDER, &offer_nonce).await?;
+                let used = session
+                    .fetch("request_metadata", &offer_nonce, false)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro DB: {}", e)))?;
+                if used.is_some() {
+                    return Err(napi_err(
+                        "OfferAlreadyUsed",
+                        format!("Já existe request para a oferta {}", offer_nonce),
+                    ));
+                }
+
                 // 6. CRIAR REQUEST (CORREÇÃO AQUI: entropy = None)
                 let (request, metadata) = anoncreds::prover::create_credential_request(
                     None,                      // <--- CORREÇÃO: Entropy deve ser None se prover_did for fornecido
@@ -715,13 +1180,30 @@ impl IndyAgent {
                         "request_metadata",
                         &metadata_id,
                         metadata_json.as_bytes(),
-                        None,
+                        Some(&[EntryTag::Encrypted(
+                            "link_secret_id".to_string(),
+                            link_secret_id,
+                        )]),
                         None,
                     )
                     .await
                     .map_err(|e| {
                         napi::Error::from_reason(format!("Erro salvar Metadata: {}", e))
                     })?;
+
+                exchange_advance(
+                    &mut session,
+                    ROLE_HOLDER,
+                    &metadata_id,
+                    "request-sent",
+                    ExchangePatch {
+                        cred_def_id: Some(offer.cred_def_id.to_string()),
+                        schema_id: Some(offer.schema_id.to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
                 session
                     .commit()
                     .await
@@ -752,9 +1234,7 @@ impl IndyAgent {
         use anoncreds::data_types::cred_def::CredentialDefinitionId;
 
         // Importamos os tipos necessários
-        use anoncreds::types::{
-            AttributeValues, CredentialOffer, CredentialRequest, CredentialValues,
-        };
+        use anoncreds::types::{CredentialOffer, CredentialRequest};
 
         // fn hash_string_to_int_str(s: &str) -> String {
         //     use std::collections::hash_map::DefaultHasher;
@@ -764,15 +1244,6 @@ impl IndyAgent {
         //     hasher.finish().to_string()
         // }
 
-        fn hash_string_to_int_str(s: &str) -> String {
-            use num_bigint::BigUint;
-            use sha2::{Digest, Sha256};
-
-            let digest = Sha256::digest(s.as_bytes()); // 32 bytes
-            let n = BigUint::from_bytes_be(&digest); // big-endian integer
-            n.to_str_radix(10) // decimal string
-        }
-
         let store = match &self.store {
             Some(s) => s.clone(),
             None => return Err(Error::from_reason("Wallet fechada!")),
@@ -804,56 +1275,27 @@ impl IndyAgent {
                 let request: CredentialRequest = serde_json::from_str(&request_json)
                     .map_err(|e| napi::Error::from_reason(format!("Erro Request JSON: {}", e)))?;
 
+                // Troca encerrada / oferta vencida ou já emitida barram a emissão
+                // antes de assinar ou gravar qualquer registro
+                let thread_id = offer.nonce.to_string();
+                check_exchange_open(&mut session, ROLE_ISSUER, &thread_id).await?;
+                check_offer_usable(&mut session, ROLE_ISSUER, &thread_id).await?;
+
                 // 2. PREPARAR VALORES
                 let values_map: serde_json::Map<String, serde_json::Value> =
                     serde_json::from_str(&values_json).map_err(|e| {
                         napi::Error::from_reason(format!("Erro Values JSON: {}", e))
                     })?;
 
-                let mut cred_values = CredentialValues::default();
-
-                for (key, val) in values_map {
-                    let raw_val = val.as_str().unwrap_or("").to_string();
-
-                    let encoded_val =
-                        if raw_val.chars().all(|c| c.is_ascii_digit()) && !raw_val.is_empty() {
-                            raw_val.clone()
-                        } else {
-                            hash_string_to_int_str(&raw_val)
-                        };
-
-                    // CORREÇÃO: Construção manual e inserção no mapa interno (.0)
-                    let attr_val = AttributeValues {
-                        raw: raw_val,
-                        encoded: encoded_val,
-                    };
-
-                    // Acessa o HashMap interno via .0 e insere
-                    cred_values.0.insert(key, attr_val);
-                }
+                // Overlay de tipos do schema (se houver): valida e codifica canonicamente
+                let attr_types = load_attr_types_for_cred_def(&mut session, &cred_def_id)
+                    .await?
+                    .unwrap_or_default();
+                let cred_values = build_credential_values(&attr_types, values_map)?;
 
                 // 3. RECUPERAR CHAVES
-                let priv_entry = session
-                    .fetch("cred_def_private", &cred_def_id, false)
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro DB Priv: {}", e)))?
-                    .ok_or_else(|| napi::Error::from_reason("CredDef Private não achada"))?;
-
-                let cred_def_priv_str = String::from_utf8(priv_entry.value.to_vec()).unwrap();
-                let cred_def_priv: anoncreds::data_types::cred_def::CredentialDefinitionPrivate =
-                    serde_json::from_str(&cred_def_priv_str)
-                        .map_err(|e| napi::Error::from_reason(format!("Erro Parse Priv: {}", e)))?;
-
-                let pub_entry = session
-                    .fetch("cred_def", &cred_def_id, false)
-                    .await
-                    .map_err(|_e| napi::Error::from_reason("Erro DB Pub"))?
-                    .ok_or_else(|| napi::Error::from_reason("CredDef Public não achada"))?;
-
-                let cred_def_pub_str = String::from_utf8(pub_entry.value.to_vec()).unwrap();
-                let cred_def_pub: anoncreds::data_types::cred_def::CredentialDefinition =
-                    serde_json::from_str(&cred_def_pub_str)
-                        .map_err(|e| napi::Error::from_reason(format!("Erro Parse Pub: {}", e)))?;
+                let (cred_def_pub, cred_def_priv) =
+                    load_issuer_cred_def(&mut session, &cred_def_id).await?;
 
                 // 4. CRIAR CREDENCIAL
                 let credential = anoncreds::issuer::create_credential(
@@ -868,8 +1310,37 @@ impl IndyAgent {
                     napi::Error::from_reason(format!("Erro anoncreds create_credential: {}", e))
                 })?;
 
-                // 5. SERIALIZAR E RETORNAR
+                // 5. REGISTRAR EMISSÃO (oferta marcada + contador da cred def)
+                let offer_nonce = serde_json::from_str::<serde_json::Value>(&offer_json)
+                    .ok()
+                    .and_then(|v| v["nonce"].as_str().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                note_credential_issued(&mut session, &cred_def_id, &offer_nonce).await?;
+                let prover_did = serde_json::from_str::<serde_json::Value>(&request_json)
+                    .ok()
+                    .and_then(|v| v["prover_did"].as_str().map(|s| s.to_string()));
+                exchange_advance(
+                    &mut session,
+                    ROLE_ISSUER,
+                    &offer_nonce,
+                    "credential-issued",
+                    ExchangePatch {
+                        cred_def_id: Some(cred_def_id.clone()),
+                        counterparty_did: prover_did,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
+                // 6. SERIALIZAR + REGISTRO DE EMISSÃO (modules/issued_credentials.rs)
                 let cred_json = serde_json::to_string(&credential).unwrap();
+                record_issued_credential(&mut session, &cred_def_id, &offer_nonce, &cred_json)
+                    .await?;
+                session
+                    .commit()
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
+
                 Ok(cred_json)
             },
             |&mut env: &mut Env, data| env.create_string(&data),
@@ -879,6 +1350,8 @@ impl IndyAgent {
     //-----------------------------------------------------------
     // =========================================================================
     //  5. HOLDER: PROCESSAR E SALVAR (CORREÇÃO: COMMIT)
+    //  options_json: { replaces?: id local da credencial renovada por esta;
+    //  precisa ser do mesmo schema ou do mesmo emissor (CredentialReplaceMismatch) }
     // =========================================================================
     #[napi]
     pub fn store_credential(
@@ -889,10 +1362,9 @@ impl IndyAgent {
         request_metadata_id: String,
         cred_def_json: String,
         rev_reg_def_json: Option<String>,
+        options_json: Option<String>,
     ) -> Result<JsObject> {
         // 1. IMPORTS CORRIGIDOS (Caminhos Exatos)
-        use std::convert::TryFrom;
-
         // Dados Estruturais ficam em data_types
         use anoncreds::data_types::cred_def::CredentialDefinition;
         use anoncreds::data_types::credential::Credential;
@@ -907,6 +1379,24 @@ impl IndyAgent {
             Some(s) => s.clone(),
             None => return Err(Error::from_reason("Wallet fechada!")),
         };
+        let cache = self.link_secrets.clone();
+
+        // Opções: { replaces?: id local da credencial que esta renova }
+        let options: StoreCredentialOptions = match options_json.as_deref() {
+            Some(s) if !s.trim().is_empty() => serde_json::from_str(s)
+                .map_err(|e| napi_err("StoreOptionsInvalid", e.to_string()))?,
+            _ => StoreCredentialOptions::default(),
+        };
+        let replaces = options
+            .replaces
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+        if replaces.as_deref() == Some(credential_id.as_str()) {
+            return Err(napi_err(
+                "StoreOptionsInvalid",
+                "replaces não pode ser a própria credencial",
+            ));
+        }
 
         env.execute_tokio_future(
             async move {
@@ -915,44 +1405,31 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-                // 2. LAZY LOAD DO LINK SECRET
-                let link_secret_arc = {
-                    let cached_ls = {
-                        let cache = LINK_SECRET_CACHE.lock().unwrap();
-                        cache.clone()
-                    };
-
-                    if let Some(ls) = cached_ls {
-                        ls
-                    } else {
-                        println!("⚠️ [RUST] Cache vazio. Buscando Link Secret no DB...");
-                        let existing = session
-                            .fetch("link_secret", "default", false)
-                            .await
-                            .map_err(|e| napi::Error::from_reason(format!("Erro DB LS: {}", e)))?;
-
-                        let entry = existing.ok_or_else(|| {
-                            napi::Error::from_reason("Link Secret não encontrado!")
+                // Credencial substituída: precisa existir e ainda não ter sido renovada
+                let mut replaced_tags: Option<Vec<EntryTag>> = None;
+                if let Some(old_id) = replaces.as_deref() {
+                    let entry = session
+                        .fetch("credential", old_id, false)
+                        .await
+                        .map_err(|e| {
+                            napi::Error::from_reason(format!("Erro fetch credencial: {}", e))
+                        })?
+                        .ok_or_else(|| {
+                            napi_err(
+                                "CredentialNotFound",
+                                format!("Credencial a substituir não encontrada: {}", old_id),
+                            )
                         })?;
-
-                        let seed_str = String::from_utf8(entry.value.to_vec()).unwrap_or_default();
-
-                        // Importante: Usar o caminho completo se o import simples falhar
-                        let ls = anoncreds::types::LinkSecret::try_from(seed_str.as_str())
-                            .map_err(|e| {
-                                napi::Error::from_reason(format!("Erro recriar LS: {:?}", e))
-                            })?;
-
-                        let arc_ls = Arc::new(ls);
-                        {
-                            let mut cache = LINK_SECRET_CACHE.lock().unwrap();
-                            *cache = Some(arc_ls.clone());
-                        }
-                        arc_ls
+                    if is_superseded(&entry.tags) {
+                        return Err(napi_err(
+                            "CredentialSuperseded",
+                            format!("Credencial {} já foi substituída", old_id),
+                        ));
                     }
-                };
+                    replaced_tags = Some(entry.tags);
+                }
 
-                // 3. RECUPERAR METADATA
+                // 2. RECUPERAR METADATA (+ LINK SECRET DO REQUEST)
                 let meta_entry = session
                     .fetch("request_metadata", &request_metadata_id, false)
                     .await
@@ -963,6 +1440,15 @@ impl IndyAgent {
                 let cred_req_metadata: CredentialRequestMetadata = serde_json::from_str(&meta_str)
                     .map_err(|e| napi::Error::from_reason(format!("Erro parse Metadata: {}", e)))?;
 
+                // Link secret usado no request (requests antigos, sem a tag: default)
+                let ls_id = meta_entry
+                    .tags
+                    .iter()
+                    .find(|t| t.name() == "link_secret_id")
+                    .map(|t| t.value().to_string());
+                let (link_secret_id, link_secret_arc) =
+                    load_link_secret(&mut session, &cache, ls_id.as_deref()).await?;
+
                 // 4. CREDENCIAL MUTÁVEL (RAW)
                 // Usamos 'mut' porque process_credential altera in-place
                 let mut mutable_credential: Credential = serde_json::from_str(&credential_json)
@@ -970,6 +1456,28 @@ impl IndyAgent {
                         napi::Error::from_reason(format!("Erro parse Credential JSON: {}", e))
                     })?;
 
+                // Renovação só entre credenciais do mesmo schema ou do mesmo emissor
+                if let Some(old_tags) = &replaced_tags {
+                    let issuer_of = |cred_def_id: &str| {
+                        cred_def_id.split(':').next().unwrap_or_default().to_string()
+                    };
+                    let new_schema = mutable_credential.schema_id.to_string();
+                    let new_issuer = issuer_of(&mutable_credential.cred_def_id.to_string());
+                    let same_schema =
+                        tag_value(old_tags, "schema_id").as_deref() == Some(new_schema.as_str());
+                    let same_issuer = tag_value(old_tags, "cred_def_id")
+                        .is_some_and(|c| !new_issuer.is_empty() && issuer_of(&c) == new_issuer);
+                    if !same_schema && !same_issuer {
+                        return Err(napi_err(
+                            "CredentialReplaceMismatch",
+                            format!(
+                                "Credencial {} não é do mesmo schema nem do mesmo emissor",
+                                replaces.as_deref().unwrap_or_default()
+                            ),
+                        ));
+                    }
+                }
+
                 // 5. NORMALIZAÇÃO DO CRED DEF
                 let mut cred_def_value: serde_json::Value = serde_json::from_str(&cred_def_json)
                     .map_err(|e| {
@@ -1028,7 +1536,7 @@ impl IndyAgent {
                 let processed_json = serde_json::to_string(&mutable_credential)
                     .map_err(|_| napi::Error::from_reason("Erro serializar final"))?;
 
-                let tags = vec![
+                let mut tags = vec![
                     EntryTag::Encrypted(
                         "schema_id".to_string(),
                         mutable_credential.schema_id.to_string(),
@@ -1037,6 +1545,7 @@ impl IndyAgent {
                         "cred_def_id".to_string(),
                         mutable_credential.cred_def_id.to_string(),
                     ),
+                    EntryTag::Encrypted("link_secret_id".to_string(), link_secret_id),
                     EntryTag::Encrypted(
                         "stored_at".to_string(),
                         SystemTime::now()
@@ -1046,6 +1555,9 @@ impl IndyAgent {
                             .to_string(),
                     ),
                 ];
+                if let Some(old_id) = &replaces {
+                    tags.push(EntryTag::Encrypted("replaces".to_string(), old_id.clone()));
+                }
 
                 session
                     .insert(
@@ -1060,6 +1572,33 @@ impl IndyAgent {
                         napi::Error::from_reason(format!("Erro salvar credencial: {}", e))
                     })?;
 
+                // Marca a antiga como superseded (mantém valor e demais tags)
+                if let Some(old_id) = &replaces {
+                    set_credential_tags(
+                        &mut session,
+                        old_id,
+                        &[
+                            ("status", CREDENTIAL_STATUS_SUPERSEDED.to_string()),
+                            ("superseded_by", credential_id.clone()),
+                            ("superseded_at", now_ts().to_string()),
+                        ],
+                    )
+                    .await?;
+                }
+
+                exchange_advance(
+                    &mut session,
+                    ROLE_HOLDER,
+                    &request_metadata_id,
+                    "credential-received",
+                    ExchangePatch {
+                        cred_def_id: Some(mutable_credential.cred_def_id.to_string()),
+                        credential_id: Some(credential_id.clone()),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
                 // 9. COMMIT
                 session
                     .commit()
@@ -1075,6 +1614,7 @@ impl IndyAgent {
     // =========================================================================
     //  LISTAR CREDENCIAIS DO HOLDER (NOVO)
     //  Retorna um JSON array com metadados + um resumo de atributos (raw).
+    //  Credenciais substituídas por renovação ficam de fora (getCredentialHistory).
     // =========================================================================
     #[napi]
     pub fn list_credentials(&self, env: Env) -> Result<JsObject> {
@@ -1096,6 +1636,8 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?;
 
+                let entries = without_superseded(entries);
+
                 let mut results = Vec::new();
 
                 for entry in entries {
@@ -1312,6 +1854,13 @@ impl IndyAgent {
                     .map(|t| t.value().to_string())
                     .unwrap_or_else(|| "0".to_string());
 
+                // Link secret da credencial (precisa existir no destino: importLinkSecret)
+                let link_secret_id = entry
+                    .tags
+                    .iter()
+                    .find(|t| t.name() == "link_secret_id")
+                    .map(|t| t.value().to_string());
+
                 // Package versionado (útil para evoluir depois)
                 let pkg = serde_json::json!({
                     "type": "ssi.credential.package",
@@ -1320,6 +1869,7 @@ impl IndyAgent {
                     "schema_id": schema_id,
                     "cred_def_id": cred_def_id,
                     "stored_at": stored_at,
+                    "link_secret_id": link_secret_id,
                     "credential": cred_val
                 });
 
@@ -1461,11 +2011,17 @@ impl IndyAgent {
                     })?;
                 }
 
-                let tags = vec![
+                let mut tags = vec![
                     EntryTag::Encrypted("schema_id".to_string(), schema_id),
                     EntryTag::Encrypted("cred_def_id".to_string(), cred_def_id),
                     EntryTag::Encrypted("stored_at".to_string(), stored_at),
                 ];
+                if let Some(ls) = pkg.get("link_secret_id").and_then(|v| v.as_str()) {
+                    tags.push(EntryTag::Encrypted(
+                        "link_secret_id".to_string(),
+                        ls.to_string(),
+                    ));
+                }
 
                 session
                     .insert(
@@ -1536,6 +2092,8 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?;
 
+                let entries = without_superseded(entries);
+
                 let mut results = Vec::new();
 
                 for entry in entries {
@@ -1653,6 +2211,8 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?;
 
+                let entries = without_superseded(entries);
+
                 let mut total: u64 = 0;
                 let mut by_schema_id: HashMap<String, u64> = HashMap::new();
                 let mut by_cred_def_id: HashMap<String, u64> = HashMap::new();
@@ -1942,6 +2502,20 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro insert new: {}", e)))?;
 
+                // Elos de renovação apontam para o id novo
+                if let Some(prev) = tag_value(&old_entry.tags, "replaces") {
+                    set_credential_tags(
+                        &mut session,
+                        &prev,
+                        &[("superseded_by", new_id_local.clone())],
+                    )
+                    .await?;
+                }
+                if let Some(next) = tag_value(&old_entry.tags, "superseded_by") {
+                    set_credential_tags(&mut session, &next, &[("replaces", new_id_local.clone())])
+                        .await?;
+                }
+
                 session
                     .commit()
                     .await
@@ -1953,11 +2527,99 @@ impl IndyAgent {
         )
     }
 
+    // =========================================================================
+    //  HISTÓRICO DE RENOVAÇÕES (replaces / superseded_by)
+    //  Cadeia da credencial, da mais nova para a mais antiga, incluindo as
+    //  substituídas: [{ id_local, status, cred_def_id, stored_at, replaces?,
+    //  superseded_by?, superseded_at? }]. status: "active" | "superseded".
+    // =========================================================================
+    #[napi]
+    pub async fn get_credential_history(&self, credential_id: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let mut tags = session
+            .fetch("credential", &credential_id, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch: {}", e)))?
+            .map(|e| e.tags)
+            .ok_or_else(|| {
+                napi_err(
+                    "CredentialNotFound",
+                    format!("Credencial não encontrada: {}", credential_id),
+                )
+            })?;
+
+        // Avança até a mais nova; depois volta pelos "replaces"
+        let mut newest = credential_id.clone();
+        let mut seen = std::collections::HashSet::from([newest.clone()]);
+        while let Some(next) = tag_value(&tags, "superseded_by") {
+            if !seen.insert(next.clone()) {
+                break;
+            }
+            let entry = session
+                .fetch("credential", &next, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch: {}", e)))?;
+            match entry {
+                Some(e) => {
+                    tags = e.tags;
+                    newest = next;
+                }
+                None => break,
+            }
+        }
+
+        let mut chain: Vec<serde_json::Value> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut cursor = Some(newest);
+        while let Some(id) = cursor.take() {
+            if !seen.insert(id.clone()) {
+                break;
+            }
+            let entry = session
+                .fetch("credential", &id, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch: {}", e)))?;
+            let Some(entry) = entry else { break };
+            let status = if is_superseded(&entry.tags) {
+                CREDENTIAL_STATUS_SUPERSEDED
+            } else {
+                "active"
+            };
+            let mut item = serde_json::json!({
+                "id_local": id,
+                "status": status,
+                "cred_def_id": tag_value(&entry.tags, "cred_def_id").unwrap_or_default(),
+                "stored_at": tag_value(&entry.tags, "stored_at").unwrap_or_default(),
+            });
+            for name in ["replaces", "superseded_by", "superseded_at"] {
+                if let Some(v) = tag_value(&entry.tags, name) {
+                    item[name] = serde_json::Value::String(v);
+                }
+            }
+            cursor = tag_value(&entry.tags, "replaces");
+            chain.push(item);
+        }
+
+        serde_json::to_string(&chain)
+            .map_err(|e| Error::from_reason(format!("Erro serializar histórico: {}", e)))
+    }
+
     // =========================================================================
     //  LISTAR CREDENCIAIS COM "VIEW MODE" (NOVO)
     //  mode:
     //   - "compact": {id_local, alias?, schema_id, cred_def_id, stored_at}
     //   - "full": mesmo shape do listCredentials (inclui values_raw etc.)
+    //             + "display" quando houver overlay do schema/cred def
+    //  Os dois modos trazem "validity": {status: valid | expired | not_yet_valid,
+    //  valid_from, valid_until, source} (modules/validity.rs).
     // =========================================================================
     #[napi]
     pub fn list_credentials_view(&self, env: Env, mode: String) -> Result<JsObject> {
@@ -1986,6 +2648,18 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?;
 
+                let entries = without_superseded(entries);
+
+                // Overlays de exibição (rótulos/ordem/branding) só no modo full
+                let overlays = if mode_norm == "full" {
+                    load_display_overlays(&mut session).await?
+                } else {
+                    Default::default()
+                };
+                // Janela de validade (modules/validity.rs), nos dois modos
+                let validity_attrs = load_validity_attrs(&mut session).await?;
+                let now = now_ts();
+
                 let mut results: Vec<serde_json::Value> = Vec::new();
 
                 for entry in entries {
@@ -2018,7 +2692,11 @@ impl IndyAgent {
                         .map(|t| t.value().to_string());
 
                     if mode_norm == "compact" {
-                        // Compact: não parseia JSON da credencial
+                        // Compact: só os values, para a validade
+                        let parsed: serde_json::Value =
+                            serde_json::from_slice(&entry.value).unwrap_or_default();
+                        let validity =
+                            credential_validity(&values_raw_of(&parsed), &validity_attrs, now);
                         let mut m = serde_json::Map::new();
                         m.insert(
                             "id_local".to_string(),
@@ -2039,6 +2717,7 @@ impl IndyAgent {
                         if let Some(a) = alias {
                             m.insert("alias".to_string(), serde_json::Value::String(a));
                         }
+                        m.insert("validity".to_string(), serde_json::json!(validity));
                         results.push(serde_json::Value::Object(m));
                         continue;
                     }
@@ -2057,11 +2736,11 @@ impl IndyAgent {
                         );
                         map.insert(
                             "schema_id".to_string(),
-                            serde_json::Value::String(schema_id),
+                            serde_json::Value::String(schema_id.clone()),
                         );
                         map.insert(
                             "cred_def_id".to_string(),
-                            serde_json::Value::String(cred_def_id),
+                            serde_json::Value::String(cred_def_id.clone()),
                         );
                         map.insert(
                             "stored_at".to_string(),
@@ -2080,6 +2759,13 @@ impl IndyAgent {
                                 }
                             }
                         }
+                        if let Some(display) =
+                            credential_display(&overlays, &schema_id, &cred_def_id, &raw_map)
+                        {
+                            map.insert("display".to_string(), display);
+                        }
+                        let validity = credential_validity(&raw_map, &validity_attrs, now);
+                        map.insert("validity".to_string(), serde_json::json!(validity));
                         map.insert("values_raw".to_string(), serde_json::Value::Object(raw_map));
                     }
 
@@ -2140,6 +2826,8 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?;
 
+                let entries = without_superseded(entries);
+
                 let mut results: Vec<serde_json::Value> = Vec::new();
 
                 for entry in entries.into_iter().skip(offset).take(limit) {
@@ -2305,6 +2993,8 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?;
 
+                let entries = without_superseded(entries);
+
                 let total = entries.len();
                 let end = std::cmp::min(offset_usize + limit_usize, total);
 
@@ -2481,6 +3171,8 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro fetch: {}", e)))?;
 
+                let entries = without_superseded(entries);
+
                 // Aplica cursor (id_local > cursor) e limit localmente
                 let mut items: Vec<serde_json::Value> = Vec::new();
                 let mut last_id: Option<String> = None;
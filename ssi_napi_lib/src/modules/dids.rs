@@ -13,7 +13,7 @@ use tokio::time::{Instant, sleep};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // Re-importando do common o que for necessário
-use crate::modules::common::now_ts;
+use crate::modules::common::{canonical_json, napi_err, now_ts, record_ledger_txn, LEDGER_TXN_CATEGORY};
 
 #[derive(Debug, Deserialize, Default)]
 pub struct DidSearchFilter {
@@ -24,11 +24,19 @@ pub struct DidSearchFilter {
     pub createdTo: Option<u64>,   // epoch seconds
     pub isPublic: Option<bool>,   // default false
     pub role: Option<String>,     // "ENDORSER" | "TRUSTEE" | "STEWARD" | "none"
-    pub origin: Option<String>,   // "generated" | "imported_seed" | "manual" | "legacy"
+    pub origin: Option<String>,   // "generated" | "imported_seed" | "imported_batch:<did>" | "manual" | "legacy"
+    pub status: Option<String>,   // "active" | "archived" | "deactivated" | "all" (default: sem archived)
     pub limit: Option<usize>,     // default 50
     pub offset: Option<usize>,    // default 0
 }
 
+#[derive(Debug, Deserialize, Default)]
+pub struct DidHistoryOpts {
+    pub from: Option<u64>,          // seqNo inicial (inclusive)
+    pub to: Option<u64>,            // seqNo final (inclusive); default from+99
+    pub types: Option<Vec<String>>, // ex.: ["NYM","ATTRIB"] (default: todos)
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub struct CreateDidPolicy {
     pub requireTrusteeForEndorser: Option<bool>,
@@ -44,6 +52,442 @@ pub struct CreateDidOpts {
     pub policy: Option<CreateDidPolicy>,
 }
 
+// --- Helpers NYM (compartilhados por resolve v2 / verificação de contatos) ---
+fn role_name_from(role: Option<&str>) -> Option<&'static str> {
+    // Indy costuma devolver:
+    // "0" trustee, "2" steward, "101" endorser, null para "NONE"
+    match role {
+        Some("0") => Some("TRUSTEE"),
+        Some("2") => Some("STEWARD"),
+        Some("101") => Some("ENDORSER"),
+        Some(s) if s.eq_ignore_ascii_case("TRUSTEE") => Some("TRUSTEE"),
+        Some(s) if s.eq_ignore_ascii_case("STEWARD") => Some("STEWARD"),
+        Some(s) if s.eq_ignore_ascii_case("ENDORSER") => Some("ENDORSER"),
+        _ => None,
+    }
+}
+
+// Verkey do NYM por extenso: a abreviada "~..." é base58(did bytes ++ abbr bytes)
+fn full_verkey(did: &str, verkey: &str) -> String {
+    match verkey.strip_prefix('~') {
+        Some(abbr) => {
+            let mut bytes = bs58::decode(did).into_vec().unwrap_or_default();
+            bytes.extend(bs58::decode(abbr).into_vec().unwrap_or_default());
+            bs58::encode(bytes).into_string()
+        }
+        None => verkey.to_string(),
+    }
+}
+
+// Tenta extrair rawData (objeto) e campos comuns verkey/role
+fn extract_nym_fields(
+    ledger_json: &serde_json::Value,
+) -> (
+    bool,
+    Option<String>,
+    Option<String>,
+    Option<serde_json::Value>,
+) {
+    let data = ledger_json.get("result").and_then(|r| r.get("data"));
+    let data = match data {
+        Some(v) => v,
+        None => return (false, None, None, None),
+    };
+
+    // "data" pode vir como string JSON (caso mais comum) ou como objeto
+    let data_obj: serde_json::Value = if data.is_string() {
+        let s = data.as_str().unwrap_or("").trim();
+        if s.is_empty() || s == "null" {
+            return (false, None, None, None);
+        }
+        match serde_json::from_str::<serde_json::Value>(s) {
+            Ok(v) => v,
+            Err(_) => return (false, None, None, None),
+        }
+    } else if data.is_object() {
+        data.clone()
+    } else if data.is_null() {
+        return (false, None, None, None);
+    } else {
+        return (false, None, None, None);
+    };
+
+    let verkey = data_obj
+        .get("verkey")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    // role pode ser string ou number em alguns ledgers
+    let role = match data_obj.get("role") {
+        Some(serde_json::Value::String(s)) => Some(s.to_string()),
+        Some(serde_json::Value::Number(n)) => n.as_i64().map(|x| x.to_string()),
+        Some(serde_json::Value::Null) | None => None,
+        _ => None,
+    };
+
+    (true, verkey, role, Some(data_obj))
+}
+
+// Verifica UM DidRecord local contra o NYM atual do ledger e persiste o resultado
+// em record.ledgerCheck + tag "ledgerStatus" (remove+insert, preservando as outras tags).
+// Status possíveis:
+// - "verified"      -> verkey (e role, quando o record local tiver role) batem com o ledger
+// - "mismatch"      -> verkey ou role divergem do ledger
+// - "not_on_ledger" -> GET_NYM não retornou data
+async fn verify_did_record_against_ledger(
+    store: &aries_askar::Store,
+    pool: &indy_vdr::pool::PoolRunner,
+    did: &str,
+) -> Result<serde_json::Value> {
+    // 1) Record local
+    let mut session = store
+        .session(None)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+    let entry = session
+        .fetch("did", did, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?
+        .ok_or_else(|| napi_err("DidNotFound", format!("DID não existe na wallet: {}", did)))?;
+
+    let mut rec: serde_json::Value = serde_json::from_slice(&entry.value)
+        .map_err(|e| napi_err("DidRecordCorrupted", format!("DidRecord inválido: {}", e)))?;
+    if !rec.is_object() {
+        return Err(napi_err("DidRecordCorrupted", "DidRecord não é objeto JSON"));
+    }
+
+    let local_verkey = rec.get("verkey").and_then(|x| x.as_str()).unwrap_or("").to_string();
+    let local_role = rec
+        .get("role")
+        .and_then(|x| x.as_str())
+        .map(|s| s.to_uppercase());
+
+    // 2) NYM no ledger (leitura única; quem precisa de retry usa resolve_did_on_ledger_v2)
+    let ledger_str = ledger::get_nym(pool, did)
+        .await
+        .map_err(|e| napi_err("LedgerGetNymFailed", e))?;
+    let ledger_val: serde_json::Value = serde_json::from_str(&ledger_str)
+        .map_err(|_| napi_err("LedgerResponseNotJson", "Resposta do ledger não é JSON válido"))?;
+
+    let (found, ledger_verkey, ledger_role, _) = extract_nym_fields(&ledger_val);
+    let ledger_role_name = role_name_from(ledger_role.as_deref()).map(|s| s.to_string());
+
+    // 3) Comparação
+    //    - verkey: sempre comparada (verkey abreviada "~..." é expandida com o DID)
+    //    - role: só comparada quando o record local declara um role
+    let mut reasons: Vec<&str> = Vec::new();
+    let status = if !found {
+        "not_on_ledger"
+    } else {
+        let lv = ledger_verkey.clone().unwrap_or_default();
+        if full_verkey(did, &lv) != local_verkey && lv != local_verkey {
+            reasons.push("verkey");
+        }
+        if let Some(r) = local_role.as_deref() {
+            if ledger_role_name.as_deref() != Some(r) {
+                reasons.push("role");
+            }
+        }
+        if reasons.is_empty() { "verified" } else { "mismatch" }
+    };
+
+    let checked_at = now_ts();
+    let check = json!({
+        "status": status,
+        "checkedAt": checked_at,
+        "ledgerVerkey": ledger_verkey,
+        "ledgerRole": ledger_role,
+        "ledgerRoleName": ledger_role_name,
+        "mismatch": reasons
+    });
+
+    // 4) Persistir ledgerCheck (hardening igual aos demais updates de DidRecord)
+    if let Some(obj) = rec.as_object_mut() {
+        obj.insert("ledgerCheck".to_string(), check.clone());
+        obj.remove("seed");
+        obj.remove("seedHex");
+        obj.remove("seedB64");
+        obj.remove("privateKey");
+        obj.remove("secret");
+    }
+
+    let mut tags: Vec<EntryTag> = entry
+        .tags
+        .iter()
+        .filter(|t| t.name() != "ledgerStatus")
+        .cloned()
+        .collect();
+    tags.push(EntryTag::Encrypted("ledgerStatus".to_string(), status.to_string()));
+
+    session
+        .remove("did", did)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro remove DID: {}", e)))?;
+    session
+        .insert("did", did, rec.to_string().as_bytes(), Some(&tags), None)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro insert DID atualizado: {}", e)))?;
+    session
+        .commit()
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro commit ledgerCheck: {}", e)))?;
+
+    Ok(json!({
+        "did": did,
+        "verkey": local_verkey,
+        "role": local_role,
+        "ledgerCheck": check
+    }))
+}
+
+// Monta, assina (TAA + chave do submitter) e envia um NYM.
+// - verkey None + clear_verkey=false -> campo omitido (não altera a verkey)
+// - clear_verkey=true              -> operation.verkey = null (desativação)
+async fn submit_signed_nym(
+    store: &aries_askar::Store,
+    pool: &indy_vdr::pool::PoolRunner,
+    submitter_did: &str,
+    target_did: &str,
+    verkey: Option<String>,
+    role: Option<indy_vdr::ledger::constants::UpdateRole>,
+    clear_verkey: bool,
+) -> Result<String> {
+    let rb = indy_vdr::ledger::RequestBuilder::new(indy_vdr::pool::ProtocolVersion::Node1_4);
+
+    // A. TAA
+    let taa_req = rb
+        .build_get_txn_author_agreement_request(None, None)
+        .map_err(|e| Error::from_reason(format!("Erro build TAA req: {}", e)))?;
+    let taa_resp = send_request_async(pool, taa_req).await?;
+    let taa_val: serde_json::Value = serde_json::from_str(&taa_resp)
+        .map_err(|e| Error::from_reason(format!("Erro parse TAA response: {}", e)))?;
+
+    let taa_acceptance = if !taa_val["result"]["data"].is_null() {
+        Some(
+            rb.prepare_txn_author_agreement_acceptance_data(
+                taa_val["result"]["data"]["text"].as_str(),
+                taa_val["result"]["data"]["version"].as_str(),
+                taa_val["result"]["data"]["digest"].as_str(),
+                "wallet_agreement",
+                now_ts() as u64,
+            )
+            .map_err(|e| Error::from_reason(format!("Erro prepare TAA data: {}", e)))?,
+        )
+    } else {
+        None
+    };
+
+    // B. NYM
+    let submitter = indy_vdr::utils::did::DidValue(submitter_did.to_string());
+    let target = indy_vdr::utils::did::DidValue(target_did.to_string());
+    let mut req = rb
+        .build_nym_request(&submitter, &target, verkey, None, role, None, None)
+        .map_err(|e| Error::from_reason(format!("Erro build NYM: {}", e)))?;
+
+    if clear_verkey {
+        // O builder omite verkey=None; a desativação exige o campo explícito como null.
+        req.req_json["operation"]["verkey"] = serde_json::Value::Null;
+    }
+
+    if let Some(taa) = taa_acceptance {
+        req.set_txn_author_agreement_acceptance(&taa)
+            .map_err(|e| Error::from_reason(format!("Erro anexando TAA: {}", e)))?;
+    }
+
+    // C. Assinatura com a chave do submitter
+    let mut session = store
+        .session(None)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro sessão wallet: {}", e)))?;
+
+    let did_entry = session
+        .fetch("did", submitter_did, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro DB fetch: {}", e)))?
+        .ok_or_else(|| {
+            napi_err("DidNotFound", format!("DID Submitter {} não encontrado", submitter_did))
+        })?;
+    let did_json: serde_json::Value = serde_json::from_slice(&did_entry.value)
+        .map_err(|e| Error::from_reason(format!("JSON inválido: {}", e)))?;
+    let submitter_verkey = did_json["verkey"]
+        .as_str()
+        .ok_or_else(|| Error::from_reason("Campo verkey ausente no registro"))?;
+
+    let key_entry = session
+        .fetch_key(submitter_verkey, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch key: {}", e)))?
+        .ok_or_else(|| napi_err("SignerKeyNotFound", "Chave privada do submitter não encontrada"))?;
+    let local_key = key_entry
+        .load_local_key()
+        .map_err(|e| Error::from_reason(format!("Erro load key: {}", e)))?;
+
+    let signature_input = req
+        .get_signature_input()
+        .map_err(|e| Error::from_reason(format!("Erro sig input: {}", e)))?;
+    let signature = local_key
+        .sign_message(signature_input.as_bytes(), None)
+        .map_err(|e| Error::from_reason(format!("Erro assinando: {}", e)))?;
+    req.set_signature(&signature)
+        .map_err(|e| Error::from_reason(format!("Erro set sig: {}", e)))?;
+    drop(session);
+
+    // D. Envio
+    let response = send_request_async(pool, req).await?;
+    record_ledger_txn(store, submitter_did, Some(target_did), "NYM", &response).await;
+    Ok(response)
+}
+
+// Retorna o DID apontado por settings/primary_did (se houver)
+async fn current_primary_did(session: &mut aries_askar::Session) -> Result<Option<String>> {
+    let entry = session
+        .fetch("settings", "primary_did", false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch primary_did: {}", e)))?;
+    Ok(entry.and_then(|e| {
+        serde_json::from_slice::<serde_json::Value>(&e.value)
+            .ok()
+            .and_then(|v| v.get("did").and_then(|x| x.as_str()).map(|s| s.to_string()))
+    }))
+}
+
+// Atualiza o DidRecord (remove+insert) aplicando `patch` e trocando/adicionando tags.
+async fn update_did_record(
+    session: &mut aries_askar::Session,
+    did: &str,
+    patch: serde_json::Value,
+    tag_updates: &[(&str, String)],
+) -> Result<serde_json::Value> {
+    let entry = session
+        .fetch("did", did, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?
+        .ok_or_else(|| napi_err("DidNotFound", format!("DID não existe na wallet: {}", did)))?;
+
+    let mut rec: serde_json::Value = serde_json::from_slice(&entry.value)
+        .map_err(|e| napi_err("DidRecordCorrupted", format!("DidRecord inválido: {}", e)))?;
+    if let (Some(obj), Some(p)) = (rec.as_object_mut(), patch.as_object()) {
+        for (k, v) in p {
+            obj.insert(k.clone(), v.clone());
+        }
+        obj.remove("seed");
+        obj.remove("seedHex");
+        obj.remove("seedB64");
+        obj.remove("privateKey");
+        obj.remove("secret");
+    }
+
+    let mut tags: Vec<EntryTag> = entry
+        .tags
+        .iter()
+        .filter(|t| !tag_updates.iter().any(|(n, _)| t.name() == *n))
+        .cloned()
+        .collect();
+    for (n, v) in tag_updates {
+        tags.push(EntryTag::Encrypted(n.to_string(), v.clone()));
+    }
+
+    session
+        .remove("did", did)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro remove DID: {}", e)))?;
+    session
+        .insert("did", did, rec.to_string().as_bytes(), Some(&tags), None)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro insert DID atualizado: {}", e)))?;
+
+    Ok(rec)
+}
+
+// Nome legível do tipo de transação Indy (txn.type)
+fn txn_type_name(code: &str) -> &'static str {
+    match code {
+        "0" => "NODE",
+        "1" => "NYM",
+        "4" => "TXN_AUTHOR_AGREEMENT",
+        "5" => "TXN_AUTHOR_AGREEMENT_AML",
+        "100" => "ATTRIB",
+        "101" => "SCHEMA",
+        "102" => "CLAIM_DEF",
+        "113" => "REVOC_REG_DEF",
+        "114" => "REVOC_REG_ENTRY",
+        "120" => "AUTH_RULE",
+        _ => "UNKNOWN",
+    }
+}
+
+// Converte result.data de um GET_TXN em item tipado do histórico.
+// Retorna None se a txn não existe ou não envolve `did` (nem como autor, nem como dest).
+fn parse_history_txn(did: &str, seq_no: u64, data: &serde_json::Value) -> Option<serde_json::Value> {
+    let txn = data.get("txn")?;
+    let type_code = match txn.get("type") {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Number(n)) => n.to_string(),
+        _ => return None,
+    };
+    let type_name = txn_type_name(&type_code);
+    let author = txn["metadata"]["from"].as_str().unwrap_or("");
+    let body = &txn["data"];
+    let dest = body.get("dest").and_then(|x| x.as_str()).unwrap_or("");
+
+    let relation = if author == did && dest == did {
+        "self"
+    } else if author == did {
+        "author"
+    } else if dest == did {
+        "subject"
+    } else {
+        return None;
+    };
+
+    let details = match type_name {
+        "NYM" => json!({
+            "dest": dest,
+            "verkey": body.get("verkey").cloned().unwrap_or(serde_json::Value::Null),
+            "role": body.get("role").cloned().unwrap_or(serde_json::Value::Null),
+            "roleName": role_name_from(body.get("role").and_then(|x| x.as_str())),
+            "alias": body.get("alias").cloned().unwrap_or(serde_json::Value::Null)
+        }),
+        "ATTRIB" => {
+            // raw vem como string JSON {"chave": valor}
+            let raw_keys: Vec<String> = body
+                .get("raw")
+                .and_then(|x| x.as_str())
+                .and_then(|r| serde_json::from_str::<serde_json::Value>(r).ok())
+                .and_then(|v| v.as_object().map(|o| o.keys().cloned().collect()))
+                .unwrap_or_default();
+            json!({
+                "dest": dest,
+                "rawKeys": raw_keys,
+                "hash": body.get("hash").cloned().unwrap_or(serde_json::Value::Null),
+                "enc": body.get("enc").is_some()
+            })
+        }
+        "SCHEMA" => json!({
+            "name": body["data"]["name"],
+            "version": body["data"]["version"],
+            "attrNames": body["data"]["attr_names"]
+        }),
+        "CLAIM_DEF" => json!({
+            "schemaSeqNo": body.get("ref").cloned().unwrap_or(serde_json::Value::Null),
+            "tag": body.get("tag").cloned().unwrap_or(serde_json::Value::Null),
+            "signatureType": body.get("signature_type").cloned().unwrap_or(serde_json::Value::Null)
+        }),
+        _ => body.clone(),
+    };
+
+    Some(json!({
+        "seqNo": seq_no,
+        "type": type_name,
+        "typeCode": type_code,
+        "txnTime": data["txnMetadata"]["txnTime"].as_u64(),
+        "txnId": data["txnMetadata"].get("txnId").cloned().unwrap_or(serde_json::Value::Null),
+        "author": author,
+        "relation": relation,
+        "details": details
+    }))
+}
+
 #[napi]
 impl IndyAgent {
     // =========================================================================
@@ -217,6 +661,7 @@ impl IndyAgent {
         let want_is_public = f.isPublic; // Option<bool>
         let want_role = f.role.clone().map(|s| s.to_lowercase()); // Option<String>
         let want_origin = f.origin.clone().map(|s| s.to_lowercase()); // Option<String>
+        let want_status = f.status.clone().map(|s| s.to_lowercase()); // Option<String>
 
         let offset = f.offset.unwrap_or(0);
         let limit = f.limit.unwrap_or(50);
@@ -395,6 +840,26 @@ impl IndyAgent {
                     }
                 }
 
+                // status: registros sem campo são "active"; archived só aparece se pedido
+                let status_norm = v
+                    .get("status")
+                    .and_then(|x| x.as_str())
+                    .unwrap_or("active")
+                    .to_lowercase();
+                match want_status.as_deref() {
+                    Some("all") => {}
+                    Some(want_s) => {
+                        if status_norm != want_s {
+                            continue;
+                        }
+                    }
+                    None => {
+                        if status_norm == "archived" {
+                            continue;
+                        }
+                    }
+                }
+
                 results.push(v);
             }
         }
@@ -426,7 +891,11 @@ impl IndyAgent {
     // 3) Export/Import em lote (DID + verkey, sem seed)
     // =========================================================
     #[napi]
-    pub async unsafe fn export_dids_batch(&self, filter_json: String) -> Result<String> {
+    pub async unsafe fn export_dids_batch(
+        &self,
+        filter_json: String,
+        signer_did: Option<String>, // opcional: DID próprio que assina o lote
+    ) -> Result<String> {
         // 1) Reutiliza o search_dids para aplicar filtros e normalizar DidRecord
         let arr_str = self.search_dids(filter_json).await?;
 
@@ -487,13 +956,68 @@ impl IndyAgent {
             .as_secs();
 
         // 4) Monta o batch v1
-        let batch = json!({
+        let mut batch = json!({
             "type": "ssi-did-batch-v1",
             "exportedAt": exported_at,
             "count": out_items.len(),
             "items": out_items
         });
 
+        // 4.1) Assinatura destacada (Ed25519) por um DID próprio
+        //      - signedBy {did, verkey} entra no conteúdo assinado
+        //      - signature.value = sig(canonical_json(batch sem "signature"))
+        if let Some(signer) = signer_did.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) {
+            let store = match &self.store {
+                Some(s) => s.clone(),
+                None => return Err(Error::from_reason("Carteira fechada!")),
+            };
+            let mut session = store
+                .session(None)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro Sessão: {}", e)))?;
+
+            let signer_entry = session
+                .fetch("did", &signer, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch signer DID: {}", e)))?
+                .ok_or_else(|| {
+                    napi_err("DidNotFound", format!("DID assinante não existe na wallet: {}", signer))
+                })?;
+            let signer_rec: serde_json::Value =
+                serde_json::from_slice(&signer_entry.value).unwrap_or(serde_json::Value::Null);
+            let signer_verkey = signer_rec
+                .get("verkey")
+                .and_then(|x| x.as_str())
+                .unwrap_or("")
+                .to_string();
+
+            let key_entry = session
+                .fetch_key(&signer_verkey, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch key: {}", e)))?
+                .ok_or_else(|| {
+                    napi_err(
+                        "SignerKeyNotFound",
+                        format!("Chave privada do DID assinante não encontrada: {}", signer),
+                    )
+                })?;
+            let local_key = key_entry
+                .load_local_key()
+                .map_err(|e| Error::from_reason(format!("Erro load key: {}", e)))?;
+
+            batch["signedBy"] = json!({ "did": signer, "verkey": signer_verkey });
+            let payload = canonical_json(&batch);
+            let sig = local_key
+                .sign_message(payload.as_bytes(), None)
+                .map_err(|e| Error::from_reason(format!("Erro assinando batch: {}", e)))?;
+
+            batch["signature"] = json!({
+                "alg": "Ed25519",
+                "canon": "ssi-canonical-json-v1",
+                "value": general_purpose::STANDARD.encode(sig)
+            });
+        }
+
         // 5) Serializa
         serde_json::to_string(&batch)
             .map_err(|e| Error::from_reason(format!("Erro serializar export_dids_batch: {}", e)))
@@ -504,7 +1028,8 @@ impl IndyAgent {
     pub async unsafe fn import_dids_batch(
         &mut self,
         batch_json: String,
-        mode: Option<String>, // default: "external"
+        mode: Option<String>,               // default: "external"
+        require_signature: Option<bool>,    // default: false
     ) -> Result<String> {
         // 1) Validar store aberta
         let store = match &self.store {
@@ -555,6 +1080,73 @@ impl IndyAgent {
             .as_array()
             .ok_or_else(|| Error::from_reason("Campo 'items' deve ser um array"))?;
 
+        // 4.1) Assinatura (opcional): se presente, TEM que verificar
+        let signed_by: Option<(String, String)> = match v.get("signature") {
+            None => None,
+            Some(sig_obj) => {
+                let signer = v.get("signedBy").ok_or_else(|| {
+                    napi_err("DidBatchSignatureMalformed", "batch assinado sem 'signedBy'")
+                })?;
+                let signer_did = signer.get("did").and_then(|x| x.as_str()).unwrap_or("").to_string();
+                let signer_vk = signer.get("verkey").and_then(|x| x.as_str()).unwrap_or("").to_string();
+                let sig_b64 = sig_obj.get("value").and_then(|x| x.as_str()).unwrap_or("");
+                let alg = sig_obj.get("alg").and_then(|x| x.as_str()).unwrap_or("");
+
+                if signer_did.is_empty() || signer_vk.is_empty() || sig_b64.is_empty() || alg != "Ed25519" {
+                    return Err(napi_err(
+                        "DidBatchSignatureMalformed",
+                        "signature/signedBy incompletos (esperado alg=Ed25519, value, did, verkey)",
+                    ));
+                }
+
+                let sig = general_purpose::STANDARD.decode(sig_b64).map_err(|_| {
+                    napi_err("DidBatchSignatureMalformed", "signature.value não é base64")
+                })?;
+                let pk = bs58::decode(&signer_vk).into_vec().map_err(|_| {
+                    napi_err("DidBatchSignatureMalformed", "signedBy.verkey não é base58")
+                })?;
+                let pub_key = LocalKey::from_public_bytes(KeyAlg::Ed25519, &pk).map_err(|e| {
+                    napi_err("DidBatchSignatureMalformed", format!("signedBy.verkey inválida: {}", e))
+                })?;
+
+                let mut unsigned = v.clone();
+                if let Some(obj) = unsigned.as_object_mut() {
+                    obj.remove("signature");
+                }
+                let payload = canonical_json(&unsigned);
+
+                let valid = pub_key
+                    .verify_signature(payload.as_bytes(), &sig, None)
+                    .unwrap_or(false);
+                if !valid {
+                    return Err(napi_err(
+                        "DidBatchSignatureInvalid",
+                        format!("Assinatura do batch não confere (signer={}): conteúdo adulterado", signer_did),
+                    ));
+                }
+
+                // count declarado também precisa bater (coberto pela assinatura, mas checamos)
+                if let Some(c) = v.get("count").and_then(|x| x.as_u64()) {
+                    if c as usize != items.len() {
+                        return Err(napi_err(
+                            "DidBatchSignatureInvalid",
+                            format!("count={} não bate com items={}", c, items.len()),
+                        ));
+                    }
+                }
+
+                Some((signer_did, signer_vk))
+            }
+        };
+
+        let require_signature = require_signature.unwrap_or(false);
+        if require_signature && signed_by.is_none() {
+            return Err(napi_err(
+                "DidBatchUnsigned",
+                "batch sem assinatura (requireSignature=true)",
+            ));
+        }
+
         // 5) Sessão
         let mut session = store
             .session(None)
@@ -572,6 +1164,62 @@ impl IndyAgent {
             .unwrap_or_default()
             .as_secs();
 
+        // 7.1) A verkey de signedBy vem do próprio batch: só vale se bater com o
+        //      DidRecord do signer na wallet ou, sem record, com o NYM no ledger.
+        //      Sem nenhum dos dois o signer fica "unverified" (origin marcado).
+        let mut signer_source: Option<&str> = None;
+        if let Some((signer_did, signer_vk)) = &signed_by {
+            let mismatch = |known: &str, source: &str| {
+                napi_err(
+                    "DidBatchSignerMismatch",
+                    format!(
+                        "Signer {} conhecido com verkey diferente ({}={} batch={})",
+                        signer_did, source, known, signer_vk
+                    ),
+                )
+            };
+            let known = session
+                .fetch("did", signer_did, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch signer DID: {}", e)))?
+                .and_then(|e| serde_json::from_slice::<serde_json::Value>(&e.value).ok())
+                .and_then(|ev| ev.get("verkey").and_then(|x| x.as_str()).map(String::from))
+                .filter(|vk| !vk.is_empty());
+            if let Some(known_vk) = known {
+                if &known_vk != signer_vk {
+                    return Err(mismatch(&known_vk, "wallet"));
+                }
+                signer_source = Some("wallet");
+            } else if let Some(pool) = self.pool.clone() {
+                // falha de leitura no ledger não confirma nada: segue unverified
+                let nym = ledger::get_nym(&pool, signer_did)
+                    .await
+                    .ok()
+                    .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok());
+                if let Some((true, Some(lv), _, _)) = nym.as_ref().map(extract_nym_fields) {
+                    if full_verkey(signer_did, &lv) != *signer_vk && lv != *signer_vk {
+                        return Err(mismatch(&lv, "ledger"));
+                    }
+                    signer_source = Some("ledger");
+                }
+            }
+            if signer_source.is_none() && require_signature {
+                return Err(napi_err(
+                    "DidBatchSignerUnverified",
+                    format!(
+                        "Signer {} não está na wallet nem no ledger (requireSignature=true)",
+                        signer_did
+                    ),
+                ));
+            }
+        }
+
+        let origin = match (&signed_by, signer_source) {
+            (Some((d, _)), Some(_)) => format!("imported_batch:{}", d),
+            (Some((d, _)), None) => format!("imported_batch_unverified:{}", d),
+            (None, _) => "imported_json".to_string(),
+        };
+
         // 8) Processar cada item
         for (idx, it) in items.iter().enumerate() {
             let did = it
@@ -629,7 +1277,7 @@ impl IndyAgent {
                 EntryTag::Encrypted("alias".to_string(), alias.clone()),
                 EntryTag::Encrypted("createdAt".to_string(), created_at.to_string()),
                 EntryTag::Encrypted("isPublic".to_string(), "false".to_string()),
-                EntryTag::Encrypted("origin".to_string(), "imported_json".to_string()),
+                EntryTag::Encrypted("origin".to_string(), origin.clone()),
                 EntryTag::Encrypted("role".to_string(), "none".to_string()),
             ];
 
@@ -639,7 +1287,7 @@ impl IndyAgent {
                 "method": "sov",
                 "alias": alias,
                 "type": "external",
-                "origin": "imported_json",
+                "origin": origin,
                 "createdAt": created_at,
                 "isPublic": false,
                 "role": serde_json::Value::Null
@@ -667,7 +1315,11 @@ impl IndyAgent {
             "mode": mode_norm,
             "imported": imported,
             "skipped": skipped,
-            "updated": updated
+            "updated": updated,
+            "signedBy": signed_by.as_ref().map(|(d, _)| d.clone()),
+            "signatureVerified": signed_by.is_some(),
+            "signerVerified": signer_source.is_some(),
+            "signerSource": signer_source
         });
 
         serde_json::to_string(&summary)
@@ -994,6 +1646,7 @@ impl IndyAgent {
 
         // D) Enviar
         let ledger_response = send_request_async(&pool, req).await?;
+        record_ledger_txn(&store, &submitter_did, Some(&did_str), "NYM", &ledger_response).await;
 
         // 10) Atualizar DID record (isPublic=true e role) — transação atômica
         let mut session3 = store
@@ -1908,6 +2561,7 @@ impl IndyAgent {
             // D. ENVIO
             // =================================================================
             let response = send_request_async(&pool, req).await?;
+            record_ledger_txn(&store, &submitter_did, Some(&target_did_s), "NYM", &response).await;
 
             // =================================================================
             // E. UPDATE LOCAL (PR-01) - se sucesso e target existir
@@ -2086,69 +2740,6 @@ impl IndyAgent {
             .and_then(|s| s.parse::<u64>().ok())
             .unwrap_or(400);
 
-        // --- Helpers locais ---
-        fn role_name_from(role: Option<&str>) -> Option<&'static str> {
-            // Indy costuma devolver:
-            // "0" trustee, "2" steward, "101" endorser, null para "NONE"
-            match role {
-                Some("0") => Some("TRUSTEE"),
-                Some("2") => Some("STEWARD"),
-                Some("101") => Some("ENDORSER"),
-                Some(s) if s.eq_ignore_ascii_case("TRUSTEE") => Some("TRUSTEE"),
-                Some(s) if s.eq_ignore_ascii_case("STEWARD") => Some("STEWARD"),
-                Some(s) if s.eq_ignore_ascii_case("ENDORSER") => Some("ENDORSER"),
-                _ => None,
-            }
-        }
-
-        // Tenta extrair rawData (objeto) e campos comuns verkey/role
-        fn extract_nym_fields(
-            ledger_json: &serde_json::Value,
-        ) -> (
-            bool,
-            Option<String>,
-            Option<String>,
-            Option<serde_json::Value>,
-        ) {
-            let data = ledger_json.get("result").and_then(|r| r.get("data"));
-            let data = match data {
-                Some(v) => v,
-                None => return (false, None, None, None),
-            };
-
-            // "data" pode vir como string JSON (caso mais comum) ou como objeto
-            let data_obj: serde_json::Value = if data.is_string() {
-                let s = data.as_str().unwrap_or("").trim();
-                if s.is_empty() || s == "null" {
-                    return (false, None, None, None);
-                }
-                match serde_json::from_str::<serde_json::Value>(s) {
-                    Ok(v) => v,
-                    Err(_) => return (false, None, None, None),
-                }
-            } else if data.is_object() {
-                data.clone()
-            } else if data.is_null() {
-                return (false, None, None, None);
-            } else {
-                return (false, None, None, None);
-            };
-
-            let verkey = data_obj
-                .get("verkey")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-            // role pode ser string ou number em alguns ledgers
-            let role = match data_obj.get("role") {
-                Some(serde_json::Value::String(s)) => Some(s.to_string()),
-                Some(serde_json::Value::Number(n)) => n.as_i64().map(|x| x.to_string()),
-                Some(serde_json::Value::Null) | None => None,
-                _ => None,
-            };
-
-            (true, verkey, role, Some(data_obj))
-        }
-
         let start = Instant::now();
         let mut last_ledger_str: Option<String> = None;
         let mut last_ledger_val: Option<serde_json::Value> = None;
@@ -2260,18 +2851,31 @@ impl IndyAgent {
             .await
             .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-        // 4) Validar que esse DID existe na wallet (own ou external)
-        let did_exists = session
+        // 4) Validar que esse DID existe na wallet (own ou external) e está ativo
+        let did_entry = session
             .fetch("did", &did_trim, false)
             .await
-            .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?
-            .is_some();
+            .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?;
 
-        if !did_exists {
-            return Err(Error::from_reason(format!(
-                "DID não existe na wallet: {}",
-                did_trim
-            )));
+        let did_entry = match did_entry {
+            Some(e) => e,
+            None => {
+                return Err(Error::from_reason(format!(
+                    "DID não existe na wallet: {}",
+                    did_trim
+                )))
+            }
+        };
+
+        let did_status = serde_json::from_slice::<serde_json::Value>(&did_entry.value)
+            .ok()
+            .and_then(|v| v.get("status").and_then(|x| x.as_str()).map(|s| s.to_string()))
+            .unwrap_or_else(|| "active".to_string());
+        if did_status != "active" {
+            return Err(napi_err(
+                "DidNotActive",
+                format!("DID {} está {} e não pode ser primary", did_trim, did_status),
+            ));
         }
 
         // 5) Montar registro settings/primary_did
@@ -2385,4 +2989,683 @@ impl IndyAgent {
             .map_err(|e| Error::from_reason(format!("Erro serializar get_primary_did: {}", e)))
     }
 
+    // =========================================================
+    //  VERIFICAÇÃO DE CONTATOS CONTRA O LEDGER
+    // =========================================================
+
+    #[napi]
+    pub async unsafe fn verify_contact_against_ledger(&self, did: String) -> Result<String> {
+        // 1) Wallet aberta + pool conectado
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => {
+                return Err(napi_err(
+                    "PoolNotConnected",
+                    "Pool não conectado. Execute connectNetwork antes.",
+                ))
+            }
+        };
+
+        let did_trim = did.trim().to_string();
+        if did_trim.is_empty() {
+            return Err(napi_err("InvalidDid", "did vazio"));
+        }
+
+        // 2) Verificar e persistir
+        let mut out = verify_did_record_against_ledger(&store, &pool, &did_trim).await?;
+        if let Some(obj) = out.as_object_mut() {
+            obj.insert("ok".to_string(), json!(true));
+        }
+
+        serde_json::to_string(&out)
+            .map_err(|e| Error::from_reason(format!("Erro serializar verificação: {}", e)))
+    }
+
+    #[napi]
+    pub async unsafe fn refresh_external_dids(&self) -> Result<String> {
+        // 1) Wallet aberta + pool conectado
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => {
+                return Err(napi_err(
+                    "PoolNotConnected",
+                    "Pool não conectado. Execute connectNetwork antes.",
+                ))
+            }
+        };
+
+        // 2) Listar DIDs externos (tag type=external)
+        let dids: Vec<String> = {
+            let mut session = store
+                .session(None)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+            let filter = TagFilter::is_eq("type", "external".to_string());
+            session
+                .fetch_all(Some("did"), Some(filter), None, None, false, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch_all DIDs: {}", e)))?
+                .into_iter()
+                .map(|e| e.name)
+                .collect()
+        };
+
+        // 3) Verificar um a um (falha de um item não aborta o lote)
+        let mut verified: u64 = 0;
+        let mut mismatch: u64 = 0;
+        let mut not_on_ledger: u64 = 0;
+        let mut errors: u64 = 0;
+        let mut items: Vec<serde_json::Value> = Vec::new();
+
+        for did in &dids {
+            match verify_did_record_against_ledger(&store, &pool, did).await {
+                Ok(v) => {
+                    let status = v["ledgerCheck"]["status"].as_str().unwrap_or("").to_string();
+                    match status.as_str() {
+                        "verified" => verified += 1,
+                        "mismatch" => mismatch += 1,
+                        _ => not_on_ledger += 1,
+                    }
+                    items.push(json!({ "did": did, "status": status }));
+                }
+                Err(e) => {
+                    errors += 1;
+                    items.push(json!({ "did": did, "status": "error", "error": e.reason }));
+                }
+            }
+        }
+
+        let out = json!({
+            "ok": true,
+            "total": dids.len(),
+            "verified": verified,
+            "mismatch": mismatch,
+            "notOnLedger": not_on_ledger,
+            "errors": errors,
+            "items": items
+        });
+
+        serde_json::to_string(&out)
+            .map_err(|e| Error::from_reason(format!("Erro serializar refresh: {}", e)))
+    }
+
+    // =========================================================
+    //  RETIRADA DE DIDs (desativação no ledger / arquivo / delete local)
+    // =========================================================
+
+    #[napi]
+    pub async unsafe fn deactivate_did(
+        &self,
+        did: String,
+        submitter_did: Option<String>, // default: o próprio DID
+    ) -> Result<String> {
+        // 1) Wallet aberta
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let did_trim = did.trim().to_string();
+        if did_trim.is_empty() {
+            return Err(napi_err("InvalidDid", "did vazio"));
+        }
+
+        // 2) Validar record + guard do primary
+        let (is_public, status) = {
+            let mut session = store
+                .session(None)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+            if current_primary_did(&mut session).await?.as_deref() == Some(did_trim.as_str()) {
+                return Err(napi_err(
+                    "DidIsPrimary",
+                    format!("DID {} é o primary DID. Defina outro primary antes.", did_trim),
+                ));
+            }
+            let entry = session
+                .fetch("did", &did_trim, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?
+                .ok_or_else(|| {
+                    napi_err("DidNotFound", format!("DID não existe na wallet: {}", did_trim))
+                })?;
+            let rec: serde_json::Value =
+                serde_json::from_slice(&entry.value).unwrap_or(serde_json::Value::Null);
+            if rec.get("type").and_then(|x| x.as_str()) == Some("external") {
+                return Err(napi_err(
+                    "DidNotOwn",
+                    "Somente DIDs próprios podem ser desativados",
+                ));
+            }
+            (
+                rec.get("isPublic").and_then(|x| x.as_bool()).unwrap_or(false),
+                rec.get("status").and_then(|x| x.as_str()).unwrap_or("active").to_string(),
+            )
+        };
+
+        if status == "deactivated" {
+            return Err(napi_err("DidAlreadyDeactivated", format!("DID {} já desativado", did_trim)));
+        }
+
+        // 3) Ledger: NYM com verkey=null (somente se o DID foi publicado)
+        let mut ledger_resp: Option<serde_json::Value> = None;
+        if is_public {
+            let pool = match &self.pool {
+                Some(p) => p.clone(),
+                None => {
+                    return Err(napi_err(
+                        "PoolNotConnected",
+                        "Pool não conectado. Execute connectNetwork antes.",
+                    ))
+                }
+            };
+            let submitter = submitter_did
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| did_trim.clone());
+
+            let resp = submit_signed_nym(&store, &pool, &submitter, &did_trim, None, None, true)
+                .await
+                .map_err(|e| napi_err("LedgerDeactivateFailed", e.reason))?;
+            let v: serde_json::Value = serde_json::from_str(&resp).unwrap_or(serde_json::Value::Null);
+            if v.get("op").and_then(|x| x.as_str()) != Some("REPLY") {
+                return Err(napi_err("LedgerDeactivateFailed", resp));
+            }
+            ledger_resp = Some(v);
+        }
+
+        // 4) Local: status=deactivated (a chave é mantida para decifrar histórico)
+        let ts = now_ts();
+        let seq_no = ledger_resp
+            .as_ref()
+            .and_then(|v| v["result"]["txnMetadata"]["seqNo"].as_u64());
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let rec = update_did_record(
+            &mut session,
+            &did_trim,
+            json!({
+                "status": "deactivated",
+                "deactivatedAt": ts,
+                "deactivation": { "onLedger": is_public, "seqNo": seq_no }
+            }),
+            &[("status", "deactivated".to_string())],
+        )
+        .await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit deactivate: {}", e)))?;
+
+        let out = json!({
+            "ok": true,
+            "did": did_trim,
+            "status": "deactivated",
+            "onLedger": is_public,
+            "seqNo": seq_no,
+            "deactivatedAt": ts,
+            "record": rec
+        });
+        serde_json::to_string(&out)
+            .map_err(|e| Error::from_reason(format!("Erro serializar deactivate_did: {}", e)))
+    }
+
+    #[napi]
+    pub async unsafe fn archive_did(&self, did: String) -> Result<String> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let did_trim = did.trim().to_string();
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        // Guards: primary + somente local
+        if current_primary_did(&mut session).await?.as_deref() == Some(did_trim.as_str()) {
+            return Err(napi_err(
+                "DidIsPrimary",
+                format!("DID {} é o primary DID. Defina outro primary antes.", did_trim),
+            ));
+        }
+        let entry = session
+            .fetch("did", &did_trim, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?
+            .ok_or_else(|| napi_err("DidNotFound", format!("DID não existe na wallet: {}", did_trim)))?;
+        let rec: serde_json::Value =
+            serde_json::from_slice(&entry.value).unwrap_or(serde_json::Value::Null);
+        if rec.get("isPublic").and_then(|x| x.as_bool()).unwrap_or(false) {
+            return Err(napi_err(
+                "DidIsPublic",
+                "DID publicado no ledger: use deactivateDid",
+            ));
+        }
+
+        let ts = now_ts();
+        let rec = update_did_record(
+            &mut session,
+            &did_trim,
+            json!({ "status": "archived", "archivedAt": ts }),
+            &[("status", "archived".to_string())],
+        )
+        .await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit archive: {}", e)))?;
+
+        let out = json!({ "ok": true, "did": did_trim, "status": "archived", "archivedAt": ts, "record": rec });
+        serde_json::to_string(&out)
+            .map_err(|e| Error::from_reason(format!("Erro serializar archive_did: {}", e)))
+    }
+
+    // Remove o registro do DID. DID próprio precisa estar archived/deactivated;
+    // a chave do KMS só é apagada com delete_key=true (sem ela, mensagens e
+    // credenciais antigas deixam de ser decifráveis).
+    #[napi]
+    pub async unsafe fn delete_did(&self, did: String, delete_key: Option<bool>) -> Result<String> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let did_trim = did.trim().to_string();
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        // Guards: primary + somente local
+        if current_primary_did(&mut session).await?.as_deref() == Some(did_trim.as_str()) {
+            return Err(napi_err(
+                "DidIsPrimary",
+                format!("DID {} é o primary DID. Defina outro primary antes.", did_trim),
+            ));
+        }
+        let entry = session
+            .fetch("did", &did_trim, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?
+            .ok_or_else(|| napi_err("DidNotFound", format!("DID não existe na wallet: {}", did_trim)))?;
+        let rec: serde_json::Value =
+            serde_json::from_slice(&entry.value).unwrap_or(serde_json::Value::Null);
+        if rec.get("isPublic").and_then(|x| x.as_bool()).unwrap_or(false) {
+            return Err(napi_err(
+                "DidIsPublic",
+                "DID publicado no ledger: use deactivateDid",
+            ));
+        }
+
+        let is_own = rec.get("type").and_then(|x| x.as_str()) != Some("external");
+        let status = rec.get("status").and_then(|x| x.as_str()).unwrap_or("active");
+        if is_own && status != "archived" && status != "deactivated" {
+            return Err(napi_err(
+                "DidNotRetired",
+                format!(
+                    "DID {} está '{}': use archiveDid ou deactivateDid antes",
+                    did_trim, status
+                ),
+            ));
+        }
+
+        // Chave do KMS: só com opt-in explícito
+        let mut key_removed = false;
+        if is_own && delete_key.unwrap_or(false) {
+            if let Some(vk) = rec.get("verkey").and_then(|x| x.as_str()) {
+                if session
+                    .fetch_key(vk, false)
+                    .await
+                    .map_err(|e| Error::from_reason(format!("Erro fetch key: {}", e)))?
+                    .is_some()
+                {
+                    session
+                        .remove_key(vk)
+                        .await
+                        .map_err(|e| Error::from_reason(format!("Erro remove key: {}", e)))?;
+                    key_removed = true;
+                }
+            }
+        }
+
+        session
+            .remove("did", &did_trim)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro remove DID: {}", e)))?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit delete: {}", e)))?;
+
+        let out = json!({ "ok": true, "did": did_trim, "deleted": true, "keyRemoved": key_removed });
+        serde_json::to_string(&out)
+            .map_err(|e| Error::from_reason(format!("Erro serializar delete_did: {}", e)))
+    }
+
+    // =========================================================
+    //  GESTÃO DE ROLE NO LEDGER (NYM role update)
+    // =========================================================
+
+    #[napi]
+    pub async unsafe fn set_ledger_role(
+        &self,
+        submitter_did: String,
+        target_did: String,
+        role: String, // "ENDORSER" | "TRUSTEE" | "STEWARD" | "none"
+    ) -> Result<String> {
+        use indy_vdr::ledger::constants::{LedgerRole, UpdateRole};
+
+        // 1) Wallet + pool
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => {
+                return Err(napi_err(
+                    "PoolNotConnected",
+                    "Pool não conectado. Execute connectNetwork antes.",
+                ))
+            }
+        };
+
+        let submitter = submitter_did.trim().to_string();
+        let target = target_did.trim().to_string();
+        if submitter.is_empty() || target.is_empty() {
+            return Err(napi_err("InvalidDid", "submitterDid/targetDid vazios"));
+        }
+
+        // 2) Role alvo
+        let role_up = role.trim().to_uppercase();
+        let (update_role, new_role): (UpdateRole, Option<&str>) = match role_up.as_str() {
+            "ENDORSER" => (UpdateRole::Set(LedgerRole::Endorser), Some("ENDORSER")),
+            "TRUSTEE" => (UpdateRole::Set(LedgerRole::Trustee), Some("TRUSTEE")),
+            "STEWARD" => (UpdateRole::Set(LedgerRole::Steward), Some("STEWARD")),
+            "NONE" | "" => (UpdateRole::Reset, None),
+            other => {
+                return Err(napi_err(
+                    "InvalidRole",
+                    format!("Role inválido: {} (use ENDORSER|TRUSTEE|STEWARD|none)", other),
+                ))
+            }
+        };
+
+        // 3) Role do submitter no ledger (resolve v2)
+        //    - TRUSTEE: pode atribuir/remover qualquer role
+        //    - STEWARD: só pode atribuir ENDORSER
+        let sub_res: serde_json::Value =
+            serde_json::from_str(&self.resolve_did_on_ledger_v2(submitter.clone()).await?)
+                .unwrap_or(serde_json::Value::Null);
+        if sub_res.get("ok").and_then(|x| x.as_bool()) != Some(true) {
+            return Err(napi_err(
+                sub_res.get("code").and_then(|x| x.as_str()).unwrap_or("LedgerGetNymFailed"),
+                sub_res.get("message").and_then(|x| x.as_str()).unwrap_or("Falha ao resolver submitter"),
+            ));
+        }
+        if sub_res.get("found").and_then(|x| x.as_bool()) != Some(true) {
+            return Err(napi_err(
+                "SubmitterNotOnLedger",
+                format!("Submitter {} não encontrado no ledger", submitter),
+            ));
+        }
+        let submitter_role = sub_res.get("roleName").and_then(|x| x.as_str()).unwrap_or("");
+        let allowed = match submitter_role {
+            "TRUSTEE" => true,
+            "STEWARD" => new_role == Some("ENDORSER"),
+            _ => false,
+        };
+        if !allowed {
+            return Err(napi_err(
+                "SubmitterRoleInsufficient",
+                format!(
+                    "Submitter {} (role={}) não pode definir role {}",
+                    submitter,
+                    if submitter_role.is_empty() { "none" } else { submitter_role },
+                    new_role.unwrap_or("none")
+                ),
+            ));
+        }
+
+        // 4) Target precisa existir no ledger (role update não cria DID)
+        let tgt_res: serde_json::Value =
+            serde_json::from_str(&self.resolve_did_on_ledger_v2(target.clone()).await?)
+                .unwrap_or(serde_json::Value::Null);
+        if tgt_res.get("found").and_then(|x| x.as_bool()) != Some(true) {
+            return Err(napi_err(
+                "TargetNotOnLedger",
+                format!("Target {} não encontrado no ledger", target),
+            ));
+        }
+        let previous_role = tgt_res.get("roleName").cloned().unwrap_or(serde_json::Value::Null);
+
+        // 5) NYM role update (sem verkey: mantém a atual)
+        let resp = submit_signed_nym(&store, &pool, &submitter, &target, None, Some(update_role), false)
+            .await
+            .map_err(|e| napi_err("LedgerRoleUpdateFailed", e.reason))?;
+        let resp_val: serde_json::Value = serde_json::from_str(&resp).unwrap_or(serde_json::Value::Null);
+        if resp_val.get("op").and_then(|x| x.as_str()) != Some("REPLY") {
+            return Err(napi_err("LedgerRoleUpdateFailed", resp));
+        }
+        let seq_no = resp_val["result"]["txnMetadata"]["seqNo"].as_u64();
+
+        // 6) Atualizar record local (se o target existir na wallet)
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let has_local = session
+            .fetch("did", &target, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?
+            .is_some();
+        if has_local {
+            update_did_record(
+                &mut session,
+                &target,
+                json!({
+                    "role": new_role,
+                    "roleUpdatedAt": now_ts(),
+                    "roleUpdatedBy": submitter
+                }),
+                &[("role", new_role.unwrap_or("none").to_string())],
+            )
+            .await?;
+            session
+                .commit()
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro commit role: {}", e)))?;
+        }
+
+        let out = json!({
+            "ok": true,
+            "submitterDid": submitter,
+            "targetDid": target,
+            "role": new_role,
+            "previousRole": previous_role,
+            "seqNo": seq_no,
+            "localUpdated": has_local
+        });
+        serde_json::to_string(&out)
+            .map_err(|e| Error::from_reason(format!("Erro serializar set_ledger_role: {}", e)))
+    }
+
+    // =========================================================
+    //  HISTÓRICO DE TRANSAÇÕES NO LEDGER (GET_TXN)
+    // =========================================================
+
+    #[napi]
+    pub async unsafe fn get_did_ledger_history(
+        &self,
+        did: String,
+        opts_json: Option<String>,
+    ) -> Result<String> {
+        // 1) Wallet + pool
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => {
+                return Err(napi_err(
+                    "PoolNotConnected",
+                    "Pool não conectado. Execute connectNetwork antes.",
+                ))
+            }
+        };
+
+        let did_trim = did.trim().to_string();
+        if did_trim.is_empty() {
+            return Err(napi_err("InvalidDid", "did vazio"));
+        }
+
+        // 2) Opts
+        let opts: DidHistoryOpts = match opts_json.as_deref().map(|s| s.trim()) {
+            None | Some("") => DidHistoryOpts::default(),
+            Some(s) => serde_json::from_str(s)
+                .map_err(|e| napi_err("InvalidOpts", format!("opts_json inválido: {}", e)))?,
+        };
+
+        let max_range: u64 = std::env::var("SSI_HISTORY_MAX_RANGE")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(500);
+
+        let range: Option<(u64, u64)> = match (opts.from, opts.to) {
+            (None, None) => None,
+            (from, to) => {
+                let from = from.unwrap_or(1).max(1);
+                let to = to.unwrap_or(from.saturating_add(99));
+                if to < from {
+                    return Err(napi_err("InvalidRange", format!("to ({}) < from ({})", to, from)));
+                }
+                // GET_TXN recebe seqNo i32
+                if i32::try_from(to).is_err() {
+                    return Err(napi_err(
+                        "InvalidRange",
+                        format!("to ({}) excede o seqNo máximo ({})", to, i32::MAX),
+                    ));
+                }
+                if to - from + 1 > max_range {
+                    return Err(napi_err(
+                        "HistoryRangeTooLarge",
+                        format!("Intervalo {}..{} excede o máximo de {} seqNos", from, to, max_range),
+                    ));
+                }
+                Some((from, to))
+            }
+        };
+
+        let want_types: Option<Vec<String>> = opts
+            .types
+            .map(|v| v.into_iter().map(|t| t.trim().to_uppercase()).collect());
+
+        // 3) seqNos a consultar: intervalo + nossas escritas registradas localmente
+        let mut seq_nos: std::collections::BTreeSet<u64> = std::collections::BTreeSet::new();
+        if let Some((from, to)) = range {
+            seq_nos.extend(from..=to);
+        }
+        let local_count = {
+            let mut session = store
+                .session(None)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+            let filter = TagFilter::any_of(vec![
+                TagFilter::is_eq("from", did_trim.clone()),
+                TagFilter::is_eq("dest", did_trim.clone()),
+            ]);
+            let entries = session
+                .fetch_all(Some(LEDGER_TXN_CATEGORY), Some(filter), None, None, false, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch_all ledger_txn: {}", e)))?;
+            let mut n = 0;
+            for e in entries {
+                if let Ok(seq) = e.name.parse::<u64>() {
+                    let in_range = range.map(|(f, t)| seq >= f && seq <= t).unwrap_or(true);
+                    if in_range {
+                        seq_nos.insert(seq);
+                        n += 1;
+                    }
+                }
+            }
+            n
+        };
+
+        // 4) GET_TXN (domain ledger = 1) para cada seqNo
+        let rb = indy_vdr::ledger::RequestBuilder::new(indy_vdr::pool::ProtocolVersion::Node1_4);
+        let mut items: Vec<serde_json::Value> = Vec::new();
+        let mut errors: Vec<serde_json::Value> = Vec::new();
+
+        for seq in &seq_nos {
+            let seq_i32 = match i32::try_from(*seq) {
+                Ok(n) => n,
+                Err(_) => {
+                    errors.push(json!({ "seqNo": seq, "error": "seqNo excede i32" }));
+                    continue;
+                }
+            };
+            let req = match rb.build_get_txn_request(None, 1, seq_i32) {
+                Ok(r) => r,
+                Err(e) => {
+                    errors.push(json!({ "seqNo": seq, "error": format!("Erro build GET_TXN: {}", e) }));
+                    continue;
+                }
+            };
+            let resp = match send_request_async(&pool, req).await {
+                Ok(r) => r,
+                Err(e) => {
+                    errors.push(json!({ "seqNo": seq, "error": e.reason }));
+                    continue;
+                }
+            };
+            let v: serde_json::Value = match serde_json::from_str(&resp) {
+                Ok(v) => v,
+                Err(_) => {
+                    errors.push(json!({ "seqNo": seq, "error": "Resposta GET_TXN não é JSON" }));
+                    continue;
+                }
+            };
+            let data = &v["result"]["data"];
+            if data.is_null() {
+                continue; // seqNo ainda não existe
+            }
+            if let Some(item) = parse_history_txn(&did_trim, *seq, data) {
+                let t = item["type"].as_str().unwrap_or("");
+                let keep = want_types
+                    .as_ref()
+                    .map(|w| w.iter().any(|x| x == t))
+                    .unwrap_or(true);
+                if keep {
+                    items.push(item);
+                }
+            }
+        }
+
+        let out = json!({
+            "ok": true,
+            "did": did_trim,
+            "from": range.map(|r| r.0),
+            "to": range.map(|r| r.1),
+            "scanned": seq_nos.len(),
+            "localSeqNos": local_count,
+            "count": items.len(),
+            "items": items,
+            "errors": errors
+        });
+        serde_json::to_string(&out)
+            .map_err(|e| Error::from_reason(format!("Erro serializar histórico: {}", e)))
+    }
+
 }
@@ -0,0 +1,274 @@
+// src/modules/encoding.rs
+//
+// Registro de codificação raw -> encoded dos valores de credencial.
+//
+// Regras canônicas (encoded inteiro em 0..=PREDICATE_INT_MAX, apto a >=/<=):
+//   int        "42"; com min < 0 aceita negativos: encoded = n - min
+//   decimal    escala fixa: "12.5" (scale 2) -> raw "12.50", encoded 1250 (- min escalado)
+//   date       "YYYY-MM-DD" -> dias desde 1970-01-01
+//   timestamp  RFC 3339 UTC / epoch em segundos -> minutos desde 1970-01-01T00:00Z
+//   boolean    true/false -> 1/0
+// Textos (string/enum) viram SHA-256 decimal: servem para revelação e igualdade,
+// nunca para predicados. Sem tipo declarado vale a regra legada: só dígitos ->
+// o próprio número, qualquer outra coisa -> hash.
+
+use crate::modules::common::{
+    check_attr_type_def, encode_typed_attr_value, hash_attr_string, is_reserved_control_attr,
+    iso_date_to_epoch_days, napi_err, parse_scaled_decimal, parse_timestamp_utc, AttrType,
+    PREDICATE_INT_MAX, TIMESTAMP_ENCODING_UNIT_SECS,
+};
+use crate::IndyAgent;
+use anoncreds::data_types::credential::Credential;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+// Regra para atributos sem tipo declarado (compatível com as credenciais já emitidas).
+pub fn encode_untyped_attr_value(val: &serde_json::Value) -> (String, String) {
+    let raw = match val {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    let encoded = if !raw.is_empty() && raw.chars().all(|c| c.is_ascii_digit()) {
+        raw.clone()
+    } else {
+        hash_attr_string(&raw)
+    };
+    (raw, encoded)
+}
+
+// Classificação de um par (raw, encoded) já emitido. O holder normalmente não
+// conhece os tipos do emissor, então a regra é inferida do próprio par.
+pub struct EncodingInfo {
+    pub kind: &'static str,
+    pub predicate_ready: bool,
+    pub offset: Option<i64>,
+    pub scale: Option<u32>,
+    pub hint: Option<String>,
+}
+
+fn info(kind: &'static str, ready: bool) -> EncodingInfo {
+    EncodingInfo {
+        kind,
+        predicate_ready: ready,
+        offset: None,
+        scale: None,
+        hint: None,
+    }
+}
+
+pub fn classify_encoding(raw: &str, encoded: &str) -> EncodingInfo {
+    let n: i64 = match encoded.parse::<i64>() {
+        Ok(n) => n,
+        Err(_) => {
+            if !encoded.is_empty() && encoded.chars().all(|c| c.is_ascii_digit()) {
+                if encoded == hash_attr_string(raw) {
+                    return info("hash", false);
+                }
+                return info("integer_out_of_range", false);
+            }
+            return info("invalid", false);
+        }
+    };
+    if !(0..=PREDICATE_INT_MAX).contains(&n) {
+        return info("integer_out_of_range", false);
+    }
+
+    if (raw == "true" && n == 1) || (raw == "false" && n == 0) {
+        return info("boolean", true);
+    }
+    if iso_date_to_epoch_days(raw) == Some(n) {
+        let mut i = info("date", true);
+        i.hint = Some("dias desde 1970-01-01".to_string());
+        return i;
+    }
+    if raw.contains('T') {
+        if let Some(secs) = parse_timestamp_utc(raw) {
+            if secs.div_euclid(TIMESTAMP_ENCODING_UNIT_SECS) == n {
+                let mut i = info("timestamp", true);
+                i.hint = Some("minutos desde 1970-01-01T00:00:00Z".to_string());
+                return i;
+            }
+        }
+    }
+    if let Ok(v) = raw.parse::<i64>() {
+        // raw vem do emissor: deslocamento fora de i64 não é codificação conhecida
+        let offset = match v.checked_sub(n) {
+            Some(d) => d,
+            None => return info("custom", true),
+        };
+        let mut i = info("integer", true);
+        if offset != 0 {
+            i.offset = Some(offset);
+            i.hint = Some(format!("encoded = valor - ({})", offset));
+        }
+        return i;
+    }
+    if let Some((_, frac)) = raw.split_once('.') {
+        let scale = frac.len() as u32;
+        if let Some(v) = parse_scaled_decimal(raw, scale) {
+            let offset = match v.checked_sub(n) {
+                Some(d) => d,
+                None => return info("custom", true),
+            };
+            let mut i = info("decimal", true);
+            i.scale = Some(scale);
+            i.hint = Some(format!("encoded = valor x 10^{}", scale));
+            if offset != 0 {
+                i.offset = Some(offset);
+                i.hint = Some(format!("encoded = valor x 10^{} - ({})", scale, offset));
+            }
+            return i;
+        }
+    }
+    info("custom", true)
+}
+
+// Nome de atributo como o AnonCreds compara (minúsculas, sem espaços).
+fn attr_common_view(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+// Antes de create_presentation: o atributo de cada predicado precisa ter sido
+// codificado como inteiro. Sem isso o AnonCreds devolve erro matemático opaco.
+pub(crate) fn check_predicate_attr_encoding(
+    pres_req: &serde_json::Value,
+    referent: &str,
+    cred_id: &str,
+    credential: &Credential,
+) -> Result<()> {
+    let name = match pres_req["requested_predicates"][referent]["name"].as_str() {
+        Some(n) => attr_common_view(n),
+        None => return Ok(()), // referent inválido: o AnonCreds reporta
+    };
+    let value = match credential
+        .values
+        .0
+        .iter()
+        .find(|(k, _)| attr_common_view(k) == name)
+    {
+        Some((_, v)) => v,
+        None => return Ok(()),
+    };
+
+    let i = classify_encoding(&value.raw, &value.encoded);
+    if i.predicate_ready {
+        return Ok(());
+    }
+    Err(napi_err(
+        "PredicateAttrNotInteger",
+        format!(
+            "Predicado '{}': atributo '{}' da credencial {} foi codificado como {} e não serve \
+             para >=/<= (use credentialEncodingReport)",
+            referent, name, cred_id, i.kind
+        ),
+    ))
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  RELATÓRIO DE CODIFICAÇÃO (HOLDER)
+    //  Quais atributos da credencial servem para predicados >= / <=.
+    // =========================================================================
+    #[napi]
+    pub async fn credential_encoding_report(&self, cred_id: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entry = session
+            .fetch("credential", &cred_id, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch credential: {}", e)))?
+            .ok_or_else(|| {
+                napi_err(
+                    "CredentialNotFound",
+                    format!("Credencial não encontrada: {}", cred_id),
+                )
+            })?;
+
+        let v: serde_json::Value = serde_json::from_slice(&entry.value)
+            .map_err(|e| Error::from_reason(format!("Credencial corrompida: {}", e)))?;
+        let cred = v.get("credential").unwrap_or(&v);
+
+        let mut attributes = Vec::new();
+        let mut ready = Vec::new();
+        let mut not_ready = Vec::new();
+
+        if let Some(values) = cred["values"].as_object() {
+            let mut names: Vec<&String> = values.keys().collect();
+            names.sort();
+            for name in names {
+                let raw = values[name]["raw"].as_str().unwrap_or_default();
+                let encoded = values[name]["encoded"].as_str().unwrap_or_default();
+                let i = classify_encoding(raw, encoded);
+                if i.predicate_ready {
+                    ready.push(name.clone());
+                } else {
+                    not_ready.push(name.clone());
+                }
+                let mut a = serde_json::json!({
+                    "name": name,
+                    "raw": raw,
+                    "encoded": encoded,
+                    "encoding": i.kind,
+                    "predicateReady": i.predicate_ready,
+                    "control": is_reserved_control_attr(name)
+                });
+                if let Some(o) = i.offset {
+                    a["offset"] = o.into();
+                }
+                if let Some(s) = i.scale {
+                    a["scale"] = s.into();
+                }
+                if let Some(h) = i.hint {
+                    a["hint"] = h.into();
+                }
+                attributes.push(a);
+            }
+        }
+
+        Ok(serde_json::json!({
+            "ok": true,
+            "credentialId": cred_id,
+            "schemaId": cred["schema_id"],
+            "credDefId": cred["cred_def_id"],
+            "predicateIntMax": PREDICATE_INT_MAX,
+            "attributes": attributes,
+            "predicateReady": ready,
+            "notPredicateReady": not_ready
+        })
+        .to_string())
+    }
+
+    // Converte um limiar de predicado para o domínio codificado do tipo
+    // (ex.: { "type": "date" } + "2030-01-01" -> dias desde epoch). Verifier usa
+    // o resultado como p_value em requested_predicates.
+    #[napi]
+    pub fn encoding_predicate_value(&self, attr_type_json: String, value: String) -> Result<i64> {
+        let t: AttrType = serde_json::from_str(&attr_type_json)
+            .map_err(|e| napi_err("AttrTypeInvalid", format!("Tipo inválido: {}", e)))?;
+        if matches!(t, AttrType::Enum { .. } | AttrType::String { .. }) {
+            return Err(napi_err(
+                "PredicateAttrNotInteger",
+                "Tipos textuais são codificados por hash e não admitem predicados",
+            ));
+        }
+        check_attr_type_def("predicate", &t)?;
+        let (_, encoded) =
+            encode_typed_attr_value("predicate", &t, &serde_json::Value::String(value))?;
+        encoded
+            .parse::<i64>()
+            .map_err(|_| Error::from_reason("Valor codificado não inteiro"))
+    }
+}
@@ -1,5 +1,8 @@
 // src/modules/envelope.rs
 use crate::IndyAgent;
+use crate::modules::common::{
+    napi_err, remove_if_exists, CONFIG_CATEGORY, KEY_CONTACT_MISMATCH_POLICY,
+};
 use napi::{Env, Error, JsObject, Result};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
@@ -184,12 +187,90 @@ fn validate_envelope_basic(env: &EnvelopeV1) -> Result<()> {
     Ok(())
 }
 
+// Política da wallet para pack_authcrypt a contato em ledgerStatus "mismatch":
+// "refuse" (padrão) falha com ContactLedgerMismatch; "warn" só avisa.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ContactMismatchPolicy {
+    #[default]
+    Refuse,
+    Warn,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct ContactMismatchConfig {
+    #[serde(default)]
+    pub policy: ContactMismatchPolicy,
+}
+
+async fn load_contact_mismatch_config(
+    session: &mut aries_askar::Session,
+) -> Result<ContactMismatchConfig> {
+    let entry = session
+        .fetch(CONFIG_CATEGORY, KEY_CONTACT_MISMATCH_POLICY, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch config: {}", e)))?;
+    Ok(entry
+        .and_then(|e| serde_json::from_slice(&e.value).ok())
+        .unwrap_or_default())
+}
+
 // ============================================================================
 // NAPI: novos métodos (aditivos)
 // ============================================================================
 
 #[napi]
 impl IndyAgent {
+    // config_json: { policy?: "refuse" | "warn" } para contatos em ledgerStatus "mismatch"
+    #[napi]
+    pub async fn set_contact_mismatch_config(&self, config_json: String) -> Result<String> {
+        let cfg: ContactMismatchConfig = serde_json::from_str(&config_json)
+            .map_err(|e| napi_err("ContactMismatchConfigInvalid", e.to_string()))?;
+        let json = serde_json::to_string(&cfg)
+            .map_err(|e| Error::from_reason(format!("Erro serializar config: {}", e)))?;
+
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        remove_if_exists(&mut session, CONFIG_CATEGORY, KEY_CONTACT_MISMATCH_POLICY).await?;
+        session
+            .insert(
+                CONFIG_CATEGORY,
+                KEY_CONTACT_MISMATCH_POLICY,
+                json.as_bytes(),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar config: {}", e)))?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+        Ok(json)
+    }
+
+    #[napi]
+    pub async fn get_contact_mismatch_config(&self) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let cfg = load_contact_mismatch_config(&mut session).await?;
+        serde_json::to_string(&cfg)
+            .map_err(|e| Error::from_reason(format!("Erro serializar config: {}", e)))
+    }
+
     // envelope_pack_authcrypt(...) empacota um plaintext em um EnvelopeV1 com "authcrypt"
     // e retorna um objeto JS (string JSON do envelope) via N-API usando tokio_future.
     // Parâmetros:
@@ -205,6 +286,9 @@ impl IndyAgent {
     // Fluxo interno:
     // - Abre sessão no store; busca DID do sender e extrai sender_verkey.
     // - Busca chave privada do sender (ed25519) e converte para X25519.
+    // - Se o recipient for um contato com ledgerStatus "mismatch", recusa (ou só avisa
+    //   com setContactMismatchConfig({ policy: "warn" }): o aviso vai no campo "warnings"
+    //   do retorno, fora do EnvelopeV1; unpack ignora o campo).
     // - Decodifica recipient_verkey (Base58), cria LocalKey pública e converte p/ X25519.
     // - Gera nonce (24 bytes), cifra com aries_askar::kms::crypto_box (authcrypt).
     // - Monta "encrypted_pkg" compatível com decrypt_message legado (base64 ciphertext/nonce).
@@ -212,7 +296,8 @@ impl IndyAgent {
     // - Serializa EnvelopeV1 para JSON e retorna como string JS.
     // Erros típicos:
     // - Wallet fechada, DID/chave não encontrados, verkey inválida, falha de conversão/cifra,
-    //   meta_json inválido, envelope inválido/expirado, falha de serialização.
+    //   meta_json inválido, envelope inválido/expirado, falha de serialização,
+    //   ContactLedgerMismatch (contato divergente do ledger).
     #[napi]
     pub fn envelope_pack_authcrypt(
         &self,
@@ -271,6 +356,36 @@ impl IndyAgent {
                     .load_local_key()
                     .map_err(|e| Error::from_reason(format!("Erro load local key: {e}")))?;
 
+                // Contato do recipient: se estiver em "mismatch" com o ledger
+                // (verify_contact_against_ledger / refresh_external_dids), recusa ou avisa
+                // conforme a política da wallet (setContactMismatchConfig).
+                let contact_filter = aries_askar::entry::TagFilter::all_of(vec![
+                    aries_askar::entry::TagFilter::is_eq("verkey", recipient_verkey.clone()),
+                    aries_askar::entry::TagFilter::is_eq("ledgerStatus", "mismatch".to_string()),
+                ]);
+                let mismatched = session
+                    .fetch_all(Some("did"), Some(contact_filter), Some(1), None, false, false)
+                    .await
+                    .map_err(|e| Error::from_reason(format!("Erro DB contato: {e}")))?;
+                let mut warnings: Vec<String> = Vec::new();
+                if let Some(contact) = mismatched.first() {
+                    let cfg = load_contact_mismatch_config(&mut session).await?;
+                    if cfg.policy == ContactMismatchPolicy::Warn {
+                        warnings.push(format!(
+                            "Contato {} diverge do ledger (ledgerStatus=mismatch)",
+                            contact.name
+                        ));
+                    } else {
+                        return Err(napi_err(
+                            "ContactLedgerMismatch",
+                            format!(
+                                "Contato {} diverge do ledger (verkey/role). Reverifique com verifyContactAgainstLedger.",
+                                contact.name
+                            ),
+                        ));
+                    }
+                }
+
                 // Chave pública do recipient (ed25519) -> x25519
                 let target_bytes = bs58::decode(&recipient_verkey)
                     .into_vec()
@@ -361,9 +476,12 @@ impl IndyAgent {
                 };
 
                 validate_envelope_basic(&env_obj)?;
-                let out = serde_json::to_string(&env_obj)
+                let mut out = serde_json::to_value(&env_obj)
                     .map_err(|e| Error::from_reason(format!("Erro serializando envelope: {e}")))?;
-                Ok(out)
+                if !warnings.is_empty() {
+                    out["warnings"] = serde_json::json!(warnings);
+                }
+                Ok(out.to_string())
             },
             |&mut env, data| env.create_string(&data),
         )
@@ -0,0 +1,601 @@
+// src/modules/exchanges.rs
+//
+// Registro de troca de emissão (issue-credential) por thread, dos dois lados.
+// O thread id é o nonce da oferta: é o único identificador que emissor e holder
+// compartilham em todas as etapas (a oferta o carrega, o request_metadata do
+// holder é gravado com ele e create_credential o recebe na oferta).
+//
+//   issuer: offer-sent -> request-received -> credential-issued -> acked
+//   holder: offer-received -> request-sent -> credential-received -> acked
+//   qualquer estado não terminal -> abandoned | problem-reported
+//
+// As chamadas de emissão existentes avançam o registro sozinhas
+// (exchange_advance); acked/abandoned/problem-reported vêm de exchangeTransition.
+// expires_at é o prazo da oferta (offer_expiry.rs), checado por check_offer_usable.
+
+use crate::modules::common::{napi_err, now_ts, remove_if_exists};
+use crate::modules::offer_expiry::stored_offer_expires_at;
+use crate::IndyAgent;
+use aries_askar::entry::{EntryTag, TagFilter};
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+pub(crate) const EXCHANGE_CATEGORY: &str = "credential_exchange";
+
+pub(crate) const ROLE_ISSUER: &str = "issuer";
+pub(crate) const ROLE_HOLDER: &str = "holder";
+
+const ISSUER_FLOW: &[&str] = &[
+    "offer-sent",
+    "request-received",
+    "credential-issued",
+    "acked",
+];
+const HOLDER_FLOW: &[&str] = &[
+    "offer-received",
+    "request-sent",
+    "credential-received",
+    "acked",
+];
+const STATE_ACKED: &str = "acked";
+const CLOSED_STATES: &[&str] = &["abandoned", "problem-reported"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeEvent {
+    #[serde(default)]
+    pub from: Option<String>,
+    pub to: String,
+    pub at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRecord {
+    pub thread_id: String,
+    pub role: String,
+    pub state: String,
+    #[serde(default)]
+    pub cred_def_id: Option<String>,
+    #[serde(default)]
+    pub schema_id: Option<String>,
+    #[serde(default)]
+    pub offer_id: Option<String>,
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    #[serde(default)]
+    pub counterparty_did: Option<String>,
+    // thid das mensagens DIDComm (Issue Credential 2.0) desta troca, quando houver
+    #[serde(default)]
+    pub message_thread_id: Option<String>,
+    // registro de emissão renovado por esta troca (createRenewalOffer), quando houver
+    #[serde(default)]
+    pub renews: Option<String>,
+    // validade da oferta (epoch s); None = oferta sem expiração
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    #[serde(default)]
+    pub problem: Option<serde_json::Value>,
+    #[serde(default)]
+    pub history: Vec<ExchangeEvent>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Campos opcionais que uma etapa acrescenta ao registro (None = mantém).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ExchangePatch {
+    #[serde(default)]
+    pub cred_def_id: Option<String>,
+    #[serde(default)]
+    pub schema_id: Option<String>,
+    #[serde(default)]
+    pub offer_id: Option<String>,
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    #[serde(default)]
+    pub counterparty_did: Option<String>,
+    #[serde(default)]
+    pub message_thread_id: Option<String>,
+    #[serde(default)]
+    pub renews: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    #[serde(default)]
+    pub problem: Option<serde_json::Value>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+fn flow(role: &str) -> Result<&'static [&'static str]> {
+    match role {
+        ROLE_ISSUER => Ok(ISSUER_FLOW),
+        ROLE_HOLDER => Ok(HOLDER_FLOW),
+        other => Err(napi_err(
+            "ExchangeRoleInvalid",
+            format!("role deve ser issuer|holder, veio '{}'", other),
+        )),
+    }
+}
+
+pub(crate) fn is_terminal(state: &str) -> bool {
+    state == STATE_ACKED || CLOSED_STATES.contains(&state)
+}
+
+fn exchange_name(role: &str, thread_id: &str) -> String {
+    format!("{}:{}", role, thread_id)
+}
+
+fn exchange_tags(rec: &ExchangeRecord) -> Vec<EntryTag> {
+    let mut tags = vec![
+        EntryTag::Encrypted("role".to_string(), rec.role.clone()),
+        EntryTag::Encrypted("state".to_string(), rec.state.clone()),
+        EntryTag::Encrypted("thread_id".to_string(), rec.thread_id.clone()),
+        EntryTag::Encrypted("active".to_string(), (!is_terminal(&rec.state)).to_string()),
+        EntryTag::Encrypted("created_at".to_string(), rec.created_at.to_string()),
+        EntryTag::Encrypted("updated_at".to_string(), rec.updated_at.to_string()),
+    ];
+    if let Some(c) = &rec.cred_def_id {
+        tags.push(EntryTag::Encrypted("cred_def_id".to_string(), c.clone()));
+    }
+    if let Some(m) = &rec.message_thread_id {
+        tags.push(EntryTag::Encrypted(
+            "message_thread_id".to_string(),
+            m.clone(),
+        ));
+    }
+    tags
+}
+
+fn apply_patch(rec: &mut ExchangeRecord, patch: &ExchangePatch) {
+    let set = |dst: &mut Option<String>, src: &Option<String>| {
+        if let Some(v) = src.as_ref().filter(|v| !v.trim().is_empty()) {
+            *dst = Some(v.clone());
+        }
+    };
+    set(&mut rec.cred_def_id, &patch.cred_def_id);
+    set(&mut rec.schema_id, &patch.schema_id);
+    set(&mut rec.offer_id, &patch.offer_id);
+    set(&mut rec.credential_id, &patch.credential_id);
+    set(&mut rec.counterparty_did, &patch.counterparty_did);
+    set(&mut rec.message_thread_id, &patch.message_thread_id);
+    set(&mut rec.renews, &patch.renews);
+    if patch.expires_at.is_some() {
+        rec.expires_at = patch.expires_at;
+    }
+    if patch.problem.is_some() {
+        rec.problem = patch.problem.clone();
+    }
+}
+
+pub(crate) async fn load_exchange(
+    session: &mut aries_askar::Session,
+    role: &str,
+    thread_id: &str,
+) -> Result<Option<ExchangeRecord>> {
+    let entry = session
+        .fetch(EXCHANGE_CATEGORY, &exchange_name(role, thread_id), false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch exchange: {}", e)))?;
+    match entry {
+        Some(e) => serde_json::from_slice(&e.value)
+            .map(Some)
+            .map_err(|e| Error::from_reason(format!("Exchange corrompida: {}", e))),
+        None => Ok(None),
+    }
+}
+
+async fn save_exchange(session: &mut aries_askar::Session, rec: &ExchangeRecord) -> Result<()> {
+    let name = exchange_name(&rec.role, &rec.thread_id);
+    let json = serde_json::to_string(rec)
+        .map_err(|e| Error::from_reason(format!("Erro serializar exchange: {}", e)))?;
+    remove_if_exists(session, EXCHANGE_CATEGORY, &name).await?;
+    session
+        .insert(
+            EXCHANGE_CATEGORY,
+            &name,
+            json.as_bytes(),
+            Some(&exchange_tags(rec)),
+            None,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro salvar exchange: {}", e)))?;
+    Ok(())
+}
+
+fn new_exchange(role: &str, thread_id: &str, now: i64) -> ExchangeRecord {
+    ExchangeRecord {
+        thread_id: thread_id.to_string(),
+        role: role.to_string(),
+        state: String::new(),
+        cred_def_id: None,
+        schema_id: None,
+        offer_id: None,
+        credential_id: None,
+        counterparty_did: None,
+        message_thread_id: None,
+        renews: None,
+        expires_at: None,
+        problem: None,
+        history: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn push_state(rec: &mut ExchangeRecord, to: &str, reason: Option<String>, now: i64) {
+    rec.history.push(ExchangeEvent {
+        from: Some(rec.state.clone()).filter(|s| !s.is_empty()),
+        to: to.to_string(),
+        at: now,
+        reason,
+    });
+    rec.state = to.to_string();
+    rec.updated_at = now;
+}
+
+// Usado pelas chamadas de emissão (sem commit). Avança o registro até `to`
+// passando pelos estados intermediários; sem registro cria direto em `to`.
+// Já em `to` ou adiante: só aplica o patch. Encerrada (abandoned /
+// problem-reported): ExchangeClosed, e a etapa não acontece.
+pub(crate) async fn exchange_advance(
+    session: &mut aries_askar::Session,
+    role: &str,
+    thread_id: &str,
+    to: &str,
+    patch: ExchangePatch,
+) -> Result<()> {
+    if thread_id.trim().is_empty() {
+        return Ok(());
+    }
+    let steps = flow(role)?;
+    let target = steps
+        .iter()
+        .position(|s| *s == to)
+        .ok_or_else(|| Error::from_reason(format!("Estado fora do fluxo {}: {}", role, to)))?;
+
+    let now = now_ts();
+    let mut rec = match load_exchange(session, role, thread_id).await? {
+        Some(rec) => rec,
+        None => {
+            let mut rec = new_exchange(role, thread_id, now);
+            push_state(&mut rec, to, None, now);
+            apply_patch(&mut rec, &patch);
+            return save_exchange(session, &rec).await;
+        }
+    };
+
+    if CLOSED_STATES.contains(&rec.state.as_str()) {
+        return Err(napi_err(
+            "ExchangeClosed",
+            format!(
+                "Troca {} ({}) está em '{}': não avança para '{}'",
+                thread_id, role, rec.state, to
+            ),
+        ));
+    }
+    let current = steps.iter().position(|s| *s == rec.state).unwrap_or(0);
+    for step in steps.iter().take(target + 1).skip(current + 1) {
+        push_state(&mut rec, step, None, now);
+    }
+    apply_patch(&mut rec, &patch);
+    rec.updated_at = now;
+    save_exchange(session, &rec).await
+}
+
+// Checagem prévia de exchange_advance: troca encerrada responde ExchangeClosed
+// antes de a etapa gravar qualquer coisa (a sessão do Askar não é transacional).
+pub(crate) async fn check_exchange_open(
+    session: &mut aries_askar::Session,
+    role: &str,
+    thread_id: &str,
+) -> Result<()> {
+    if thread_id.trim().is_empty() {
+        return Ok(());
+    }
+    match load_exchange(session, role, thread_id).await? {
+        Some(rec) if CLOSED_STATES.contains(&rec.state.as_str()) => Err(napi_err(
+            "ExchangeClosed",
+            format!("Troca {} ({}) está em '{}'", thread_id, role, rec.state),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Oferta ainda utilizável para a próxima etapa deste lado: issuer antes de
+// credential-issued, holder antes de request-sent. Sem registro de troca
+// (ofertas anteriores ao registro) vale o prazo da oferta gravada, pela mesma
+// regra do purge (created_at / received_at + padrão). Encerradas seguem para
+// exchange_advance, que responde ExchangeClosed.
+pub(crate) async fn check_offer_usable(
+    session: &mut aries_askar::Session,
+    role: &str,
+    thread_id: &str,
+) -> Result<()> {
+    let steps = flow(role)?;
+    let expires_at = match load_exchange(session, role, thread_id).await? {
+        Some(rec) => {
+            if CLOSED_STATES.contains(&rec.state.as_str()) {
+                return Ok(());
+            }
+            let consumed_at = if role == ROLE_ISSUER { 2 } else { 1 };
+            let current = steps.iter().position(|s| *s == rec.state).unwrap_or(0);
+            if current >= consumed_at {
+                return Err(napi_err(
+                    "OfferAlreadyUsed",
+                    format!(
+                        "Oferta {} já consumida ({} em '{}')",
+                        thread_id, role, rec.state
+                    ),
+                ));
+            }
+            rec.expires_at
+        }
+        None => stored_offer_expires_at(session, role, thread_id).await?,
+    };
+    if let Some(exp) = expires_at.filter(|exp| *exp <= now_ts()) {
+        return Err(napi_err(
+            "OfferExpired",
+            format!("Oferta {} expirou em {}", thread_id, exp),
+        ));
+    }
+    Ok(())
+}
+
+// Transição explícita (UI): só o próximo estado do fluxo ou abandoned /
+// problem-reported a partir de estado não terminal. Registro inexistente só
+// pode nascer no primeiro estado do fluxo.
+fn check_transition(role: &str, from: Option<&str>, to: &str) -> Result<()> {
+    let steps = flow(role)?;
+    let invalid = |msg: String| Err(napi_err("ExchangeTransitionInvalid", msg));
+
+    let from = match from {
+        None if to == steps[0] => return Ok(()),
+        None => return invalid(format!("troca nova deve começar em '{}'", steps[0])),
+        Some(f) => f,
+    };
+    if is_terminal(from) {
+        return invalid(format!("'{}' é estado final", from));
+    }
+    if CLOSED_STATES.contains(&to) {
+        return Ok(());
+    }
+    let i = steps.iter().position(|s| *s == from);
+    let j = steps.iter().position(|s| *s == to);
+    match (i, j) {
+        (Some(i), Some(j)) if j == i + 1 => Ok(()),
+        (_, None) => invalid(format!("estado desconhecido para {}: '{}'", role, to)),
+        _ => invalid(format!("'{}' -> '{}' não permitido", from, to)),
+    }
+}
+
+// Transição validada (sem commit); devolve o registro gravado.
+pub(crate) async fn exchange_transition_to(
+    session: &mut aries_askar::Session,
+    role: &str,
+    thread_id: &str,
+    to: &str,
+    patch: ExchangePatch,
+) -> Result<ExchangeRecord> {
+    let existing = load_exchange(session, role, thread_id).await?;
+    check_transition(role, existing.as_ref().map(|r| r.state.as_str()), to)?;
+
+    let now = now_ts();
+    let mut rec = existing.unwrap_or_else(|| new_exchange(role, thread_id, now));
+    push_state(&mut rec, to, patch.reason.clone(), now);
+    apply_patch(&mut rec, &patch);
+    save_exchange(session, &rec).await?;
+    Ok(rec)
+}
+
+// Trocas ligadas a um thid DIDComm (role None = issuer e holder).
+pub(crate) async fn exchanges_by_message_thread(
+    session: &mut aries_askar::Session,
+    role: Option<&str>,
+    message_thread_id: &str,
+) -> Result<Vec<ExchangeRecord>> {
+    let mut filters = vec![TagFilter::is_eq("message_thread_id", message_thread_id)];
+    if let Some(r) = role {
+        filters.push(TagFilter::is_eq("role", r));
+    }
+    let entries = session
+        .fetch_all(
+            Some(EXCHANGE_CATEGORY),
+            Some(TagFilter::all_of(filters)),
+            None,
+            None,
+            false,
+            false,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch_all exchange: {}", e)))?;
+    Ok(entries
+        .iter()
+        .filter_map(|e| serde_json::from_slice::<ExchangeRecord>(&e.value).ok())
+        .collect())
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ExchangeFilter {
+    #[serde(default)]
+    role: Option<String>,
+    #[serde(default)]
+    state: Option<serde_json::Value>, // "offer-sent" | ["offer-sent", ...]
+    #[serde(default)]
+    cred_def_id: Option<String>,
+    #[serde(default)]
+    thread_id: Option<String>,
+    #[serde(default)]
+    message_thread_id: Option<String>,
+    #[serde(default)]
+    active: Option<bool>,
+    #[serde(default)]
+    updated_since: Option<i64>,
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  TROCAS DE EMISSÃO (ISSUER + HOLDER)
+    // =========================================================================
+
+    // Transição explícita. details_json opcional:
+    // { reason?, counterpartyDid?, problem?: { code, description }, ... (ExchangePatch) }
+    #[napi]
+    pub async fn exchange_transition(
+        &self,
+        role: String,
+        thread_id: String,
+        state: String,
+        details_json: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let patch: ExchangePatch = match details_json.filter(|s| !s.trim().is_empty()) {
+            Some(s) => serde_json::from_str(&s)
+                .map_err(|e| napi_err("ExchangeDetailsInvalid", e.to_string()))?,
+            None => ExchangePatch::default(),
+        };
+        if thread_id.trim().is_empty() {
+            return Err(napi_err("ExchangeDetailsInvalid", "thread_id vazio"));
+        }
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let rec = exchange_transition_to(&mut session, &role, &thread_id, &state, patch).await?;
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        serde_json::to_string(&rec)
+            .map_err(|e| Error::from_reason(format!("Erro serializar exchange: {}", e)))
+    }
+
+    #[napi]
+    pub async fn exchange_get(&self, role: String, thread_id: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        flow(&role)?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let rec = load_exchange(&mut session, &role, &thread_id)
+            .await?
+            .ok_or_else(|| {
+                napi_err(
+                    "ExchangeNotFound",
+                    format!("Troca não encontrada: {} ({})", thread_id, role),
+                )
+            })?;
+        serde_json::to_string(&rec)
+            .map_err(|e| Error::from_reason(format!("Erro serializar exchange: {}", e)))
+    }
+
+    // filter_json opcional: { role?, state?: string | string[], credDefId?, threadId?,
+    //   messageThreadId?, active?, updatedSince? }
+    // Ordenado por updatedAt desc (trocas mais recentes primeiro).
+    #[napi]
+    pub async fn list_exchanges(&self, filter_json: Option<String>) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let filter: ExchangeFilter = match filter_json.filter(|s| !s.trim().is_empty()) {
+            Some(s) => serde_json::from_str(&s)
+                .map_err(|e| napi_err("ExchangeFilterInvalid", e.to_string()))?,
+            None => ExchangeFilter::default(),
+        };
+        let states: Option<Vec<String>> = match &filter.state {
+            None => None,
+            Some(serde_json::Value::String(s)) => Some(vec![s.clone()]),
+            Some(serde_json::Value::Array(a)) => Some(
+                a.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect(),
+            ),
+            Some(_) => {
+                return Err(napi_err(
+                    "ExchangeFilterInvalid",
+                    "state deve ser string ou array de strings",
+                ))
+            }
+        };
+
+        let mut tag_filters = Vec::new();
+        if let Some(r) = &filter.role {
+            flow(r)?;
+            tag_filters.push(TagFilter::is_eq("role", r));
+        }
+        if let Some(c) = &filter.cred_def_id {
+            tag_filters.push(TagFilter::is_eq("cred_def_id", c));
+        }
+        if let Some(t) = &filter.thread_id {
+            tag_filters.push(TagFilter::is_eq("thread_id", t));
+        }
+        if let Some(m) = &filter.message_thread_id {
+            tag_filters.push(TagFilter::is_eq("message_thread_id", m));
+        }
+        if let Some(a) = filter.active {
+            tag_filters.push(TagFilter::is_eq("active", a.to_string()));
+        }
+        if let Some(states) = &states {
+            tag_filters.push(TagFilter::any_of(
+                states
+                    .iter()
+                    .map(|s| TagFilter::is_eq("state", s))
+                    .collect(),
+            ));
+        }
+        let tag_filter = if tag_filters.is_empty() {
+            None
+        } else {
+            Some(TagFilter::all_of(tag_filters))
+        };
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entries = session
+            .fetch_all(
+                Some(EXCHANGE_CATEGORY),
+                tag_filter,
+                None,
+                None,
+                false,
+                false,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch_all exchange: {}", e)))?;
+
+        let mut list: Vec<ExchangeRecord> = entries
+            .iter()
+            .filter_map(|e| serde_json::from_slice::<ExchangeRecord>(&e.value).ok())
+            .filter(|r| filter.updated_since.is_none_or(|t| r.updated_at >= t))
+            .collect();
+        list.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+        serde_json::to_string(&list)
+            .map_err(|e| Error::from_reason(format!("Erro serializar exchanges: {}", e)))
+    }
+}
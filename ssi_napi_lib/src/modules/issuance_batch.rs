@@ -0,0 +1,274 @@
+// src/modules/issuance_batch.rs
+//
+// Emissão em lote para uma cred def: o material do emissor (pública + privada)
+// e o overlay de tipos são carregados uma vez, cada credencial é assinada em
+// paralelo no pool de blocking do Tokio e o registro (troca + contador da cred
+// def + registro de emissão) é gravado numa única sessão no fim.
+//
+// Um item ruim não derruba o lote: cada item volta com ok/erro próprio, e só os
+// itens emitidos avançam a troca do emissor.
+
+use crate::modules::common::{napi_err, AttrType};
+use crate::modules::creddefs::note_credentials_issued;
+use crate::modules::credentials::{build_credential_values, load_issuer_cred_def};
+use crate::modules::exchanges::{
+    check_exchange_open, check_offer_usable, exchange_advance, ExchangePatch, ROLE_ISSUER,
+};
+use crate::modules::issued_credentials::record_issued_credential;
+use crate::modules::schemas::load_attr_types_for_cred_def;
+use crate::IndyAgent;
+use anoncreds::types::{CredentialOffer, CredentialRequest, CredentialValues};
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+const MAX_BATCH_ITEMS: usize = 1000;
+
+// Item aceito como string JSON (saída das outras chamadas) ou objeto.
+fn item_field<T: serde::de::DeserializeOwned>(item: &Value, field: &str) -> Result<T> {
+    let v = &item[field];
+    let parsed = match v {
+        Value::String(s) => serde_json::from_str(s),
+        Value::Null => {
+            return Err(napi_err(
+                "BatchItemInvalid",
+                format!("campo '{}' ausente", field),
+            ))
+        }
+        other => serde_json::from_value(other.clone()),
+    };
+    parsed.map_err(|e| napi_err("BatchItemInvalid", format!("'{}' inválido: {}", field, e)))
+}
+
+// { code, message } de um erro napi (napi_err) ou de um erro legado em texto.
+fn error_body(e: &Error) -> Value {
+    match serde_json::from_str::<Value>(&e.reason) {
+        Ok(v) if v["code"].is_string() => json!({ "code": v["code"], "message": v["message"] }),
+        _ => json!({ "code": "CredentialIssueFailed", "message": e.reason }),
+    }
+}
+
+struct PreparedItem {
+    index: usize,
+    nonce: String,
+    prover_did: Option<String>,
+    offer: CredentialOffer,
+    request: CredentialRequest,
+    values: CredentialValues,
+}
+
+// Valida um item e monta os valores; a oferta tem de ser da cred def do lote.
+fn prepare_item(
+    index: usize,
+    item: &Value,
+    cred_def_id: &str,
+    attr_types: &BTreeMap<String, AttrType>,
+    seen_nonces: &mut HashSet<String>,
+) -> Result<PreparedItem> {
+    let offer: CredentialOffer = item_field(item, "offer")?;
+    if offer.cred_def_id.to_string() != cred_def_id {
+        return Err(napi_err(
+            "BatchItemMismatch",
+            format!("oferta é da cred def {}", offer.cred_def_id),
+        ));
+    }
+    let nonce = offer.nonce.to_string();
+    if !seen_nonces.insert(nonce.clone()) {
+        return Err(napi_err(
+            "BatchDuplicateOffer",
+            format!("oferta {} repetida no lote", nonce),
+        ));
+    }
+    let request_v: Value = item_field(item, "request")?;
+    let prover_did = request_v["prover_did"].as_str().map(|s| s.to_string());
+    let request: CredentialRequest = serde_json::from_value(request_v)
+        .map_err(|e| napi_err("BatchItemInvalid", format!("'request' inválido: {}", e)))?;
+    let values_map: serde_json::Map<String, Value> = item_field(item, "values")?;
+
+    Ok(PreparedItem {
+        index,
+        nonce,
+        prover_did,
+        offer,
+        request,
+        values: build_credential_values(attr_types, values_map)?,
+    })
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  EMISSÃO EM LOTE
+    // =========================================================================
+
+    // items_json: [{ offer, request, values, ref? }, ...] (offer/request como
+    // string JSON ou objeto; ref é devolvido como veio, para correlação).
+    // Retorno: { credDefId, total, issued, failed, results: [{ index, ref?, ok,
+    //   threadId?, credentialJson?, error?: { code, message } }], registryError? } na
+    //   ordem dos itens. Item com erro depois de assinado ainda traz credentialJson.
+    #[napi]
+    pub async fn create_credentials_batch(
+        &self,
+        cred_def_id: String,
+        items_json: String,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let items: Vec<Value> = serde_json::from_str(&items_json)
+            .map_err(|e| napi_err("BatchItemsInvalid", format!("items_json: {}", e)))?;
+        if items.is_empty() {
+            return Err(napi_err("BatchItemsInvalid", "lote vazio"));
+        }
+        if items.len() > MAX_BATCH_ITEMS {
+            return Err(napi_err(
+                "BatchTooLarge",
+                format!("{} itens (máximo {})", items.len(), MAX_BATCH_ITEMS),
+            ));
+        }
+
+        // 1. MATERIAL DO EMISSOR (uma vez)
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let material = Arc::new(load_issuer_cred_def(&mut session, &cred_def_id).await?);
+        let attr_types = load_attr_types_for_cred_def(&mut session, &cred_def_id)
+            .await?
+            .unwrap_or_default();
+
+        // 2. VALIDAÇÃO POR ITEM (erros ficam no item)
+        let mut results: Vec<Value> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let mut r = json!({ "index": index, "ok": false });
+                if !item["ref"].is_null() {
+                    r["ref"] = item["ref"].clone();
+                }
+                r
+            })
+            .collect();
+
+        let mut seen_nonces = HashSet::new();
+        let mut prepared = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            let prep = prepare_item(index, item, &cred_def_id, &attr_types, &mut seen_nonces);
+            let prep = match prep {
+                Ok(p) => p,
+                Err(e) => {
+                    results[index]["error"] = error_body(&e);
+                    continue;
+                }
+            };
+            // Troca encerrada / oferta vencida ou já emitida não chega a ser assinada
+            let usable = match check_exchange_open(&mut session, ROLE_ISSUER, &prep.nonce).await {
+                Ok(()) => check_offer_usable(&mut session, ROLE_ISSUER, &prep.nonce).await,
+                Err(e) => Err(e),
+            };
+            match usable {
+                Ok(()) => prepared.push(prep),
+                Err(e) => results[index]["error"] = error_body(&e),
+            }
+        }
+        drop(session);
+
+        // 3. ASSINATURA EM PARALELO (CPU: pool de blocking do Tokio)
+        let handles: Vec<_> = prepared
+            .into_iter()
+            .map(|p| {
+                let material = material.clone();
+                tokio::task::spawn_blocking(move || {
+                    let (cred_def_pub, cred_def_priv) = &*material;
+                    let out = anoncreds::issuer::create_credential(
+                        cred_def_pub,
+                        cred_def_priv,
+                        &p.offer,
+                        &p.request,
+                        p.values,
+                        None,
+                    )
+                    .map_err(|e| format!("Erro anoncreds create_credential: {}", e))
+                    .and_then(|c| serde_json::to_string(&c).map_err(|e| e.to_string()));
+                    (p.index, p.nonce, p.prover_did, out)
+                })
+            })
+            .collect();
+
+        let mut issued = Vec::new();
+        for h in handles {
+            let (index, nonce, prover_did, out) = h
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro tarefa de emissão: {}", e)))?;
+            match out {
+                Ok(cred_json) => issued.push((index, nonce, prover_did, cred_json)),
+                Err(msg) => {
+                    results[index]["error"] =
+                        json!({ "code": "CredentialIssueFailed", "message": msg });
+                }
+            }
+        }
+
+        // 4. REGISTRO (uma sessão). Erro de um item (troca encerrada, registro de
+        //    emissão) fica no item, que mantém a credencial assinada; oferta e
+        //    contador da cred def são marcados uma vez no fim.
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let mut noted: Vec<String> = Vec::new();
+        for (index, nonce, prover_did, cred_json) in issued {
+            let advanced = exchange_advance(
+                &mut session,
+                ROLE_ISSUER,
+                &nonce,
+                "credential-issued",
+                ExchangePatch {
+                    cred_def_id: Some(cred_def_id.clone()),
+                    counterparty_did: prover_did,
+                    ..Default::default()
+                },
+            )
+            .await;
+            if let Err(e) = advanced {
+                results[index]["error"] = error_body(&e);
+                continue;
+            }
+            noted.push(nonce.clone());
+            results[index]["threadId"] = json!(nonce);
+            results[index]["credentialJson"] = json!(cred_json);
+            match record_issued_credential(&mut session, &cred_def_id, &nonce, &cred_json).await {
+                Ok(()) => results[index]["ok"] = json!(true),
+                Err(e) => results[index]["error"] = error_body(&e),
+            }
+        }
+        let nonces: Vec<&str> = noted.iter().map(|n| n.as_str()).collect();
+        let registry_error = note_credentials_issued(&mut session, &cred_def_id, &nonces)
+            .await
+            .err()
+            .map(|e| error_body(&e));
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        let ok_count = results.iter().filter(|r| r["ok"] == json!(true)).count();
+        let mut out = json!({
+            "credDefId": cred_def_id,
+            "total": results.len(),
+            "issued": ok_count,
+            "failed": results.len() - ok_count,
+            "results": results,
+        });
+        // Falha ao marcar ofertas/contador não desfaz as emissões já gravadas
+        if let Some(e) = registry_error {
+            out["registryError"] = e;
+        }
+        serde_json::to_string(&out)
+            .map_err(|e| Error::from_reason(format!("Erro serializar lote: {}", e)))
+    }
+}
@@ -0,0 +1,617 @@
+// src/modules/issue_v2.rs
+//
+// Mensagens DIDComm Issue Credential 2.0 (Aries RFC 0453) com anexos AnonCreds
+// (RFC 0771). Só monta/interpreta as mensagens: a criptografia continua nas
+// funções existentes, na mesma ordem do protocolo:
+//
+//   issuer: createCredentialOffer -> issueV2BuildOffer            (offer-credential)
+//   holder: issueV2Receive -> storeReceivedOffer
+//           createCredentialRequest -> issueV2BuildRequest        (request-credential)
+//   issuer: issueV2Receive -> createCredential
+//           -> issueV2BuildCredential                             (issue-credential)
+//   holder: issueV2Receive -> storeCredential -> issueV2BuildAck  (ack)
+//   issuer: issueV2Receive                                        (ack / problem-report)
+//
+// O thid DIDComm é o @id da offer-credential; ele fica gravado na troca
+// (exchanges.rs, messageThreadId), que continua indexada pelo nonce da oferta.
+// O prazo da oferta (offer_expiry.rs) viaja no decorator ~timing.expires_time.
+
+use crate::modules::common::{format_timestamp_utc, napi_err};
+use crate::modules::exchanges::{
+    exchange_advance, exchange_transition_to, exchanges_by_message_thread, is_terminal,
+    load_exchange, ExchangePatch, ExchangeRecord, ROLE_HOLDER, ROLE_ISSUER,
+};
+use crate::modules::validity::parse_time;
+use crate::IndyAgent;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::{json, Value};
+
+const PIURI_PREFIX: &str = "https://didcomm.org/issue-credential/2.0/";
+// Prefixo legado ainda emitido por agentes Aries mais antigos
+const PIURI_PREFIX_LEGACY: &str = "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/2.0/";
+
+const MSG_OFFER: &str = "offer-credential";
+const MSG_REQUEST: &str = "request-credential";
+const MSG_CREDENTIAL: &str = "issue-credential";
+const MSG_ACK: &str = "ack";
+const MSG_PROBLEM_REPORT: &str = "problem-report";
+
+// (formato emitido, formatos aceitos na leitura)
+const OFFER_FORMATS: (&str, &[&str]) = (
+    "anoncreds/credential-offer@v1.0",
+    &[
+        "anoncreds/credential-offer@v1.0",
+        "hlindy/cred-abstract@v2.0",
+    ],
+);
+const REQUEST_FORMATS: (&str, &[&str]) = (
+    "anoncreds/credential-request@v1.0",
+    &["anoncreds/credential-request@v1.0", "hlindy/cred-req@v2.0"],
+);
+const CREDENTIAL_FORMATS: (&str, &[&str]) = (
+    "anoncreds/credential@v1.0",
+    &["anoncreds/credential@v1.0", "hlindy/cred@v2.0"],
+);
+
+fn invalid(msg: impl Into<String>) -> Error {
+    napi_err("IssueV2MessageInvalid", msg)
+}
+
+// @id no formato UUID v4 (sem dependência extra).
+fn message_id() -> String {
+    let mut b: [u8; 16] = rand::random();
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let h: String = b.iter().map(|x| format!("{:02x}", x)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &h[0..8],
+        &h[8..12],
+        &h[12..16],
+        &h[16..20],
+        &h[20..32]
+    )
+}
+
+fn parse_json(s: &str, what: &str) -> Result<Value> {
+    serde_json::from_str(s).map_err(|e| invalid(format!("{} inválido: {}", what, e)))
+}
+
+fn to_string(v: &Value) -> Result<String> {
+    serde_json::to_string(v).map_err(|e| Error::from_reason(format!("Erro serializar: {}", e)))
+}
+
+// Monta formats + anexo único (base64 padrão) com o JSON AnonCreds.
+fn with_attachment(mut msg: Value, attach_field: &str, format: &str, payload: &Value) -> Value {
+    let attach_id = message_id();
+    msg["formats"] = json!([{ "attach_id": attach_id, "format": format }]);
+    msg[attach_field] = json!([{
+        "@id": attach_id,
+        "mime-type": "application/json",
+        "data": { "base64": STANDARD.encode(payload.to_string()) }
+    }]);
+    msg
+}
+
+fn threaded(msg_type: &str, thread_id: &str) -> Result<Value> {
+    if thread_id.trim().is_empty() {
+        return Err(invalid("thread_id vazio"));
+    }
+    Ok(json!({
+        "@type": format!("{}{}", PIURI_PREFIX, msg_type),
+        "@id": message_id(),
+        "~thread": { "thid": thread_id }
+    }))
+}
+
+fn decode_b64(s: &str) -> Option<Vec<u8>> {
+    [STANDARD, URL_SAFE, STANDARD_NO_PAD, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(s.trim()).ok())
+}
+
+// Primeiro anexo cujo formato está entre os aceitos. data.base64 ou data.json.
+fn read_attachment(msg: &Value, attach_field: &str, accepted: &[&str]) -> Result<Value> {
+    let formats = msg["formats"]
+        .as_array()
+        .ok_or_else(|| invalid("mensagem sem 'formats'"))?;
+    let attachments = msg[attach_field]
+        .as_array()
+        .ok_or_else(|| invalid(format!("mensagem sem '{}'", attach_field)))?;
+
+    let mut seen = Vec::new();
+    for f in formats {
+        let format = f["format"].as_str().unwrap_or_default();
+        seen.push(format.to_string());
+        if !accepted.contains(&format) {
+            continue;
+        }
+        let attach_id = f["attach_id"].as_str().unwrap_or_default();
+        let att = attachments
+            .iter()
+            .find(|a| a["@id"].as_str() == Some(attach_id))
+            .ok_or_else(|| invalid(format!("anexo '{}' não encontrado", attach_id)))?;
+        let data = &att["data"];
+        if let Some(b64) = data["base64"].as_str() {
+            let bytes = decode_b64(b64).ok_or_else(|| invalid("anexo com base64 inválido"))?;
+            return serde_json::from_slice(&bytes)
+                .map_err(|e| invalid(format!("anexo não é JSON: {}", e)));
+        }
+        if data["json"].is_object() {
+            return Ok(data["json"].clone());
+        }
+        return Err(invalid("anexo sem data.base64/data.json"));
+    }
+    Err(napi_err(
+        "IssueV2FormatUnsupported",
+        format!(
+            "formatos {:?} não suportados (aceitos: {:?})",
+            seen, accepted
+        ),
+    ))
+}
+
+// "offer-credential" a partir do @type (aceita o prefixo legado did:sov).
+fn message_name(msg: &Value) -> Result<String> {
+    let t = msg["@type"]
+        .as_str()
+        .ok_or_else(|| invalid("mensagem sem '@type'"))?;
+    t.strip_prefix(PIURI_PREFIX)
+        .or_else(|| t.strip_prefix(PIURI_PREFIX_LEGACY))
+        .map(|s| s.to_string())
+        .ok_or_else(|| {
+            napi_err(
+                "IssueV2UnsupportedType",
+                format!("tipo não suportado: {}", t),
+            )
+        })
+}
+
+fn message_thread_id(msg: &Value) -> Result<String> {
+    msg["~thread"]["thid"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .ok_or_else(|| invalid("mensagem sem '~thread.thid'"))
+}
+
+// credential_preview: aceita { nome: valor } ou [{ name, value, mime-type? }].
+fn build_preview(preview: &Value) -> Result<Value> {
+    let attributes: Vec<Value> = match preview {
+        Value::Object(map) => map
+            .iter()
+            .map(|(name, v)| {
+                let value = v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string());
+                json!({ "name": name, "value": value })
+            })
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .map(|a| {
+                let name = a["name"]
+                    .as_str()
+                    .ok_or_else(|| invalid("atributo do preview sem 'name'"))?;
+                let mut attr = json!({ "name": name, "value": a["value"].as_str().unwrap_or("") });
+                if let Some(m) = a["mime-type"].as_str() {
+                    attr["mime-type"] = json!(m);
+                }
+                Ok(attr)
+            })
+            .collect::<Result<_>>()?,
+        _ => return Err(invalid("preview deve ser objeto ou lista")),
+    };
+    Ok(json!({
+        "@type": format!("{}credential-preview", PIURI_PREFIX),
+        "attributes": attributes
+    }))
+}
+
+async fn exchange_for_thread(
+    session: &mut aries_askar::Session,
+    role: &str,
+    thid: &str,
+) -> Result<ExchangeRecord> {
+    exchanges_by_message_thread(session, Some(role), thid)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            napi_err(
+                "IssueV2ThreadUnknown",
+                format!("nenhuma troca ({}) para o thid {}", role, thid),
+            )
+        })
+}
+
+// Marca como problem-reported as trocas abertas do thid (issuer e/ou holder).
+// Devolve a última atualizada; None se não havia troca aberta.
+async fn report_problem(
+    session: &mut aries_askar::Session,
+    thid: &str,
+    code: &str,
+    description: &str,
+) -> Result<Option<ExchangeRecord>> {
+    let mut last = None;
+    for rec in exchanges_by_message_thread(session, None, thid).await? {
+        if is_terminal(&rec.state) {
+            continue;
+        }
+        let patch = ExchangePatch {
+            problem: Some(json!({ "code": code, "description": description })),
+            reason: Some(code.to_string()),
+            ..Default::default()
+        };
+        last = Some(
+            exchange_transition_to(
+                session,
+                &rec.role,
+                &rec.thread_id,
+                "problem-reported",
+                patch,
+            )
+            .await?,
+        );
+    }
+    Ok(last)
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  ISSUE CREDENTIAL 2.0 (DIDCOMM) - BUILDERS
+    // =========================================================================
+
+    // offer_json vem de createCredentialOffer. preview_json opcional:
+    // { nome: "Alice" } ou [{ name, value, "mime-type"? }].
+    // Grava o @id da mensagem como messageThreadId da troca do emissor.
+    #[napi]
+    pub async fn issue_v2_build_offer(
+        &self,
+        offer_json: String,
+        preview_json: Option<String>,
+        comment: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let offer = parse_json(&offer_json, "offer_json")?;
+        let nonce = offer["nonce"]
+            .as_str()
+            .ok_or_else(|| invalid("oferta sem nonce"))?
+            .to_string();
+
+        let mut msg = json!({
+            "@type": format!("{}{}", PIURI_PREFIX, MSG_OFFER),
+            "@id": message_id(),
+        });
+        if let Some(c) = comment.filter(|c| !c.is_empty()) {
+            msg["comment"] = json!(c);
+        }
+        if let Some(p) = preview_json.filter(|s| !s.trim().is_empty()) {
+            msg["credential_preview"] = build_preview(&parse_json(&p, "preview_json")?)?;
+        }
+        let mut msg = with_attachment(msg, "offers~attach", OFFER_FORMATS.0, &offer);
+        let thid = msg["@id"].as_str().unwrap_or_default().to_string();
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let expires_at = load_exchange(&mut session, ROLE_ISSUER, &nonce)
+            .await?
+            .and_then(|r| r.expires_at);
+        if let Some(exp) = expires_at {
+            msg["~timing"] = json!({ "expires_time": format_timestamp_utc(exp) });
+        }
+        exchange_advance(
+            &mut session,
+            ROLE_ISSUER,
+            &nonce,
+            "offer-sent",
+            ExchangePatch {
+                message_thread_id: Some(thid),
+                ..Default::default()
+            },
+        )
+        .await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        to_string(&msg)
+    }
+
+    // request_json vem de createCredentialRequest; thread_id = thid da oferta.
+    #[napi]
+    pub fn issue_v2_build_request(
+        &self,
+        request_json: String,
+        thread_id: String,
+    ) -> Result<String> {
+        let request = parse_json(&request_json, "request_json")?;
+        let msg = threaded(MSG_REQUEST, &thread_id)?;
+        to_string(&with_attachment(
+            msg,
+            "requests~attach",
+            REQUEST_FORMATS.0,
+            &request,
+        ))
+    }
+
+    // credential_json vem de createCredential; thread_id = thid da oferta.
+    #[napi]
+    pub fn issue_v2_build_credential(
+        &self,
+        credential_json: String,
+        thread_id: String,
+    ) -> Result<String> {
+        let credential = parse_json(&credential_json, "credential_json")?;
+        let msg = threaded(MSG_CREDENTIAL, &thread_id)?;
+        to_string(&with_attachment(
+            msg,
+            "credentials~attach",
+            CREDENTIAL_FORMATS.0,
+            &credential,
+        ))
+    }
+
+    // Ack do holder: fecha a troca local (credential-received -> acked).
+    #[napi]
+    pub async fn issue_v2_build_ack(&self, thread_id: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut msg = threaded(MSG_ACK, &thread_id)?;
+        msg["status"] = json!("OK");
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let rec = exchange_for_thread(&mut session, ROLE_HOLDER, &thread_id).await?;
+        exchange_transition_to(
+            &mut session,
+            ROLE_HOLDER,
+            &rec.thread_id,
+            "acked",
+            ExchangePatch::default(),
+        )
+        .await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        to_string(&msg)
+    }
+
+    // Qualquer lado. A troca local do thid (se houver e estiver aberta) vai
+    // para problem-reported com { code, description }.
+    #[napi]
+    pub async fn issue_v2_build_problem_report(
+        &self,
+        thread_id: String,
+        code: String,
+        description: String,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        if code.trim().is_empty() {
+            return Err(invalid("code vazio"));
+        }
+        let mut msg = threaded(MSG_PROBLEM_REPORT, &thread_id)?;
+        msg["description"] = json!({ "code": code, "en": description });
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        report_problem(&mut session, &thread_id, &code, &description).await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        to_string(&msg)
+    }
+
+    // =========================================================================
+    //  ISSUE CREDENTIAL 2.0 (DIDCOMM) - RECEBIMENTO
+    // =========================================================================
+
+    // Interpreta uma mensagem recebida e atualiza a troca correspondente.
+    // Retorno (sempre com type, threadId = nonce da oferta, messageThreadId):
+    // - offer-credential:   offerJson, credDefId, schemaId, preview?, comment?
+    // - request-credential: requestJson, offerJson, offerId, credDefId
+    // - issue-credential:   credentialJson, credDefId, requestMetadataId
+    // - ack:                status
+    // - problem-report:     problem { code, description }
+    #[napi]
+    pub async fn issue_v2_receive(&self, message_json: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let msg = parse_json(&message_json, "message_json")?;
+        let name = message_name(&msg)?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let out = match name.as_str() {
+            MSG_OFFER => {
+                let thid = msg["@id"]
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+                    .ok_or_else(|| invalid("offer-credential sem '@id'"))?
+                    .to_string();
+                let offer = read_attachment(&msg, "offers~attach", OFFER_FORMATS.1)?;
+                let nonce = offer["nonce"]
+                    .as_str()
+                    .ok_or_else(|| invalid("oferta sem nonce"))?
+                    .to_string();
+                let cred_def_id = offer["cred_def_id"].as_str().unwrap_or("").to_string();
+                let schema_id = offer["schema_id"].as_str().unwrap_or("").to_string();
+                // ~timing.expires_time ilegível conta como ausente (prazo padrão)
+                let expires_at = msg["~timing"]["expires_time"]
+                    .as_str()
+                    .and_then(parse_time)
+                    .map(|(t, _)| t);
+
+                exchange_advance(
+                    &mut session,
+                    ROLE_HOLDER,
+                    &nonce,
+                    "offer-received",
+                    ExchangePatch {
+                        cred_def_id: Some(cred_def_id.clone()),
+                        schema_id: Some(schema_id.clone()),
+                        counterparty_did: cred_def_id.split(':').next().map(|s| s.to_string()),
+                        message_thread_id: Some(thid.clone()),
+                        expires_at,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
+                json!({
+                    "type": MSG_OFFER,
+                    "threadId": nonce,
+                    "messageThreadId": thid,
+                    "offerJson": offer.to_string(),
+                    "credDefId": cred_def_id,
+                    "schemaId": schema_id,
+                    "expiresAt": expires_at,
+                    "preview": msg["credential_preview"]["attributes"],
+                    "comment": msg["comment"],
+                })
+            }
+            MSG_REQUEST => {
+                let thid = message_thread_id(&msg)?;
+                let request = read_attachment(&msg, "requests~attach", REQUEST_FORMATS.1)?;
+                let rec = exchange_for_thread(&mut session, ROLE_ISSUER, &thid).await?;
+                let offer_id = rec
+                    .offer_id
+                    .clone()
+                    .ok_or_else(|| invalid("troca do emissor sem oferta gravada"))?;
+                let offer_entry = session
+                    .fetch("cred_offer", &offer_id, false)
+                    .await
+                    .map_err(|e| Error::from_reason(format!("Erro fetch oferta: {}", e)))?
+                    .ok_or_else(|| invalid(format!("oferta {} não encontrada", offer_id)))?;
+                let offer_json = String::from_utf8(offer_entry.value.to_vec())
+                    .map_err(|_| Error::from_reason("Oferta gravada não é UTF-8"))?;
+
+                exchange_advance(
+                    &mut session,
+                    ROLE_ISSUER,
+                    &rec.thread_id,
+                    "request-received",
+                    ExchangePatch {
+                        counterparty_did: request["prover_did"].as_str().map(|s| s.to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
+                json!({
+                    "type": MSG_REQUEST,
+                    "threadId": rec.thread_id,
+                    "messageThreadId": thid,
+                    "requestJson": request.to_string(),
+                    "offerJson": offer_json,
+                    "offerId": offer_id,
+                    "credDefId": rec.cred_def_id,
+                })
+            }
+            MSG_CREDENTIAL => {
+                let thid = message_thread_id(&msg)?;
+                let credential = read_attachment(&msg, "credentials~attach", CREDENTIAL_FORMATS.1)?;
+                let rec = exchange_for_thread(&mut session, ROLE_HOLDER, &thid).await?;
+                let cred_def_id = credential["cred_def_id"].as_str().unwrap_or("");
+                if let Some(expected) = rec.cred_def_id.as_deref() {
+                    if !expected.is_empty() && cred_def_id != expected {
+                        return Err(invalid(format!(
+                            "credencial de {} não corresponde à oferta ({})",
+                            cred_def_id, expected
+                        )));
+                    }
+                }
+
+                json!({
+                    "type": MSG_CREDENTIAL,
+                    "threadId": rec.thread_id,
+                    "messageThreadId": thid,
+                    "credentialJson": credential.to_string(),
+                    "credDefId": cred_def_id,
+                    "requestMetadataId": rec.thread_id,
+                })
+            }
+            MSG_ACK => {
+                let thid = message_thread_id(&msg)?;
+                let rec = exchange_for_thread(&mut session, ROLE_ISSUER, &thid).await?;
+                exchange_transition_to(
+                    &mut session,
+                    ROLE_ISSUER,
+                    &rec.thread_id,
+                    "acked",
+                    ExchangePatch::default(),
+                )
+                .await?;
+
+                json!({
+                    "type": MSG_ACK,
+                    "threadId": rec.thread_id,
+                    "messageThreadId": thid,
+                    "status": msg["status"],
+                })
+            }
+            MSG_PROBLEM_REPORT => {
+                let thid = message_thread_id(&msg)?;
+                let code = msg["description"]["code"]
+                    .as_str()
+                    .ok_or_else(|| invalid("problem-report sem description.code"))?
+                    .to_string();
+                let description = msg["description"]["en"].as_str().unwrap_or("").to_string();
+                let rec = report_problem(&mut session, &thid, &code, &description)
+                    .await?
+                    .ok_or_else(|| {
+                        napi_err(
+                            "IssueV2ThreadUnknown",
+                            format!("nenhuma troca aberta para o thid {}", thid),
+                        )
+                    })?;
+
+                json!({
+                    "type": MSG_PROBLEM_REPORT,
+                    "threadId": rec.thread_id,
+                    "messageThreadId": thid,
+                    "problem": { "code": code, "description": description },
+                })
+            }
+            other => {
+                return Err(napi_err(
+                    "IssueV2UnsupportedType",
+                    format!("mensagem issue-credential/2.0 não suportada: {}", other),
+                ))
+            }
+        };
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+        to_string(&out)
+    }
+}
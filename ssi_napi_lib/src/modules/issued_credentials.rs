@@ -0,0 +1,632 @@
+// src/modules/issued_credentials.rs
+//
+// Registro de emissão do issuer: uma entrada "issued_credential" por credencial
+// emitida (id = nonce da oferta, o mesmo thread da troca), com cred def, DID do
+// holder (contraparte da troca), valores dos atributos, índice de revogação
+// (quando houver) e data. Gravada na mesma sessão da emissão.
+//
+// Os valores seguem a política da wallet (config "issued_values_policy"):
+//   mode: "plain" (valor bruto) | "hashed" (sha256 com salt da wallet) | "none"
+//   retentionDays: após N dias os valores são descartados (o resto do registro
+//   fica). A retenção é aplicada em purgeIssuedValues e antes de listar/exportar.
+
+use crate::modules::common::{
+    napi_err, now_ts, remove_if_exists, CONFIG_CATEGORY, KEY_ISSUED_VALUES_POLICY,
+    KEY_ISSUED_VALUES_SALT,
+};
+use crate::modules::credentials::create_offer_in_session;
+use crate::modules::exchanges::{load_exchange, ExchangePatch, ROLE_ISSUER};
+use crate::IndyAgent;
+use aries_askar::entry::{EntryTag, TagFilter};
+use napi::{Error, Result};
+use napi_derive::napi;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub(crate) const ISSUED_CATEGORY: &str = "issued_credential";
+
+const MODE_PLAIN: &str = "plain";
+const MODE_HASHED: &str = "hashed";
+const MODE_NONE: &str = "none";
+const MODE_PURGED: &str = "purged";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct IssuedValuesPolicy {
+    #[serde(default = "default_mode")]
+    mode: String,
+    #[serde(default)]
+    retention_days: Option<u32>,
+}
+
+fn default_mode() -> String {
+    MODE_PLAIN.to_string()
+}
+
+impl Default for IssuedValuesPolicy {
+    fn default() -> Self {
+        Self {
+            mode: default_mode(),
+            retention_days: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedCredentialRecord {
+    pub id: String,
+    pub cred_def_id: String,
+    #[serde(default)]
+    pub schema_id: Option<String>,
+    #[serde(default)]
+    pub holder_did: Option<String>,
+    // plain | hashed | none | purged (descartados pela retenção)
+    pub values_mode: String,
+    #[serde(default)]
+    pub values: BTreeMap<String, String>,
+    #[serde(default)]
+    pub rev_reg_id: Option<String>,
+    #[serde(default)]
+    pub rev_index: Option<u64>,
+    pub issued_at: i64,
+    #[serde(default)]
+    pub values_purged_at: Option<i64>,
+    // Renovação: registro anterior que esta emissão renova / emissão que renovou esta
+    #[serde(default)]
+    pub renews: Option<String>,
+    #[serde(default)]
+    pub superseded_by: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct IssuedFilter {
+    #[serde(default)]
+    cred_def_id: Option<String>,
+    #[serde(default)]
+    schema_id: Option<String>,
+    #[serde(default)]
+    holder_did: Option<String>,
+    #[serde(default)]
+    thread_id: Option<String>,
+    #[serde(default)]
+    issued_since: Option<i64>,
+    #[serde(default)]
+    issued_until: Option<i64>,
+}
+
+async fn load_policy(session: &mut aries_askar::Session) -> Result<IssuedValuesPolicy> {
+    let entry = session
+        .fetch(CONFIG_CATEGORY, KEY_ISSUED_VALUES_POLICY, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch config: {}", e)))?;
+    Ok(entry
+        .and_then(|e| serde_json::from_slice(&e.value).ok())
+        .unwrap_or_default())
+}
+
+fn check_policy(p: &IssuedValuesPolicy) -> Result<()> {
+    if ![MODE_PLAIN, MODE_HASHED, MODE_NONE].contains(&p.mode.as_str()) {
+        return Err(napi_err(
+            "IssuedPolicyInvalid",
+            format!("mode deve ser plain, hashed ou none: '{}'", p.mode),
+        ));
+    }
+    if p.retention_days == Some(0) {
+        return Err(napi_err(
+            "IssuedPolicyInvalid",
+            "retentionDays deve ser > 0",
+        ));
+    }
+    Ok(())
+}
+
+// Salt da wallet para os hashes (criado na primeira emissão em modo hashed).
+async fn values_salt(session: &mut aries_askar::Session) -> Result<Vec<u8>> {
+    let existing = session
+        .fetch(CONFIG_CATEGORY, KEY_ISSUED_VALUES_SALT, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch config: {}", e)))?;
+    if let Some(e) = existing {
+        return Ok(e.value.to_vec());
+    }
+    let mut salt = vec![0u8; 32];
+    OsRng.fill_bytes(&mut salt);
+    session
+        .insert(CONFIG_CATEGORY, KEY_ISSUED_VALUES_SALT, &salt, None, None)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro salvar config: {}", e)))?;
+    Ok(salt)
+}
+
+fn hash_value(salt: &[u8], attr: &str, raw: &str) -> String {
+    let mut h = Sha256::new();
+    h.update(salt);
+    h.update(attr.as_bytes());
+    h.update([0u8]);
+    h.update(raw.as_bytes());
+    let hex: String = h.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256:{}", hex)
+}
+
+fn issued_tags(rec: &IssuedCredentialRecord) -> Vec<EntryTag> {
+    let mut tags = vec![
+        EntryTag::Encrypted("cred_def_id".to_string(), rec.cred_def_id.clone()),
+        EntryTag::Encrypted("issued_at".to_string(), rec.issued_at.to_string()),
+        EntryTag::Encrypted("values_mode".to_string(), rec.values_mode.clone()),
+    ];
+    if let Some(s) = &rec.schema_id {
+        tags.push(EntryTag::Encrypted("schema_id".to_string(), s.clone()));
+    }
+    if let Some(d) = &rec.holder_did {
+        tags.push(EntryTag::Encrypted("holder_did".to_string(), d.clone()));
+    }
+    if let Some(r) = &rec.rev_reg_id {
+        tags.push(EntryTag::Encrypted("rev_reg_id".to_string(), r.clone()));
+    }
+    tags
+}
+
+async fn save_issued(
+    session: &mut aries_askar::Session,
+    rec: &IssuedCredentialRecord,
+) -> Result<()> {
+    let json = serde_json::to_string(rec)
+        .map_err(|e| Error::from_reason(format!("Erro serializar registro: {}", e)))?;
+    remove_if_exists(session, ISSUED_CATEGORY, &rec.id).await?;
+    session
+        .insert(
+            ISSUED_CATEGORY,
+            &rec.id,
+            json.as_bytes(),
+            Some(&issued_tags(rec)),
+            None,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro salvar registro de emissão: {}", e)))
+}
+
+// Chamado na emissão (sem commit). thread_id = nonce da oferta.
+pub(crate) async fn record_issued_credential(
+    session: &mut aries_askar::Session,
+    cred_def_id: &str,
+    thread_id: &str,
+    credential_json: &str,
+) -> Result<()> {
+    let cred: Value = serde_json::from_str(credential_json)
+        .map_err(|e| Error::from_reason(format!("Erro parse credencial: {}", e)))?;
+    let policy = load_policy(session).await?;
+    let exchange = load_exchange(session, ROLE_ISSUER, thread_id).await?;
+    let holder_did = exchange.as_ref().and_then(|r| r.counterparty_did.clone());
+    let renews = exchange.and_then(|r| r.renews);
+
+    let raw_values: BTreeMap<String, String> = cred["values"]
+        .as_object()
+        .map(|m| {
+            m.iter()
+                .map(|(k, v)| (k.clone(), v["raw"].as_str().unwrap_or("").to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let values = match policy.mode.as_str() {
+        MODE_HASHED => {
+            let salt = values_salt(session).await?;
+            raw_values
+                .iter()
+                .map(|(k, v)| (k.clone(), hash_value(&salt, k, v)))
+                .collect()
+        }
+        MODE_NONE => BTreeMap::new(),
+        _ => raw_values,
+    };
+
+    let rec = IssuedCredentialRecord {
+        id: thread_id.to_string(),
+        cred_def_id: cred_def_id.to_string(),
+        schema_id: cred["schema_id"].as_str().map(|s| s.to_string()),
+        holder_did,
+        values_mode: policy.mode.clone(),
+        values,
+        rev_reg_id: cred["rev_reg_id"].as_str().map(|s| s.to_string()),
+        rev_index: cred["signature"]["r_credential"]["i"].as_u64(),
+        issued_at: now_ts(),
+        values_purged_at: None,
+        renews: renews.clone(),
+        superseded_by: None,
+    };
+    save_issued(session, &rec).await?;
+
+    if let Some(prev) = renews {
+        if let Some(prev_rec) = load_issued(session, &prev).await? {
+            let prev_rec = IssuedCredentialRecord {
+                superseded_by: Some(thread_id.to_string()),
+                ..prev_rec
+            };
+            save_issued(session, &prev_rec).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn load_issued(
+    session: &mut aries_askar::Session,
+    id: &str,
+) -> Result<Option<IssuedCredentialRecord>> {
+    let entry = session
+        .fetch(ISSUED_CATEGORY, id, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch registro de emissão: {}", e)))?;
+    Ok(entry.and_then(|e| serde_json::from_slice(&e.value).ok()))
+}
+
+// Descarta valores de registros além da retenção. Devolve quantos mudaram.
+async fn apply_retention(session: &mut aries_askar::Session) -> Result<u32> {
+    let policy = load_policy(session).await?;
+    let days = match policy.retention_days {
+        Some(d) => d as i64,
+        None => return Ok(0),
+    };
+    let now = now_ts();
+    let cutoff = now - days * 86_400;
+
+    let mut purged = 0;
+    for rec in fetch_issued(session, None).await? {
+        if rec.issued_at > cutoff || rec.values_mode == MODE_PURGED {
+            continue;
+        }
+        let rec = IssuedCredentialRecord {
+            values: BTreeMap::new(),
+            values_mode: MODE_PURGED.to_string(),
+            values_purged_at: Some(now),
+            ..rec
+        };
+        save_issued(session, &rec).await?;
+        purged += 1;
+    }
+    Ok(purged)
+}
+
+async fn fetch_issued(
+    session: &mut aries_askar::Session,
+    filter: Option<&IssuedFilter>,
+) -> Result<Vec<IssuedCredentialRecord>> {
+    let mut tag_filters = Vec::new();
+    if let Some(f) = filter {
+        if let Some(c) = &f.cred_def_id {
+            tag_filters.push(TagFilter::is_eq("cred_def_id", c));
+        }
+        if let Some(s) = &f.schema_id {
+            tag_filters.push(TagFilter::is_eq("schema_id", s));
+        }
+        if let Some(d) = &f.holder_did {
+            tag_filters.push(TagFilter::is_eq("holder_did", d));
+        }
+    }
+    let tag_filter = if tag_filters.is_empty() {
+        None
+    } else {
+        Some(TagFilter::all_of(tag_filters))
+    };
+
+    let entries = session
+        .fetch_all(Some(ISSUED_CATEGORY), tag_filter, None, None, false, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch_all issued: {}", e)))?;
+
+    let mut out: Vec<IssuedCredentialRecord> = entries
+        .iter()
+        .filter_map(|e| serde_json::from_slice(&e.value).ok())
+        .filter(|r: &IssuedCredentialRecord| match filter {
+            Some(f) => {
+                f.thread_id.as_deref().map(|t| t == r.id).unwrap_or(true)
+                    && f.issued_since.map(|t| r.issued_at >= t).unwrap_or(true)
+                    && f.issued_until.map(|t| r.issued_at <= t).unwrap_or(true)
+            }
+            None => true,
+        })
+        .collect();
+    out.sort_by(|a, b| b.issued_at.cmp(&a.issued_at).then(a.id.cmp(&b.id)));
+    Ok(out)
+}
+
+fn parse_filter(filter_json: Option<String>) -> Result<IssuedFilter> {
+    match filter_json.filter(|s| !s.trim().is_empty()) {
+        Some(s) => {
+            serde_json::from_str(&s).map_err(|e| napi_err("IssuedFilterInvalid", e.to_string()))
+        }
+        None => Ok(IssuedFilter::default()),
+    }
+}
+
+// Célula iniciada por = + - @ \t \r vira fórmula no Excel/LibreOffice: prefixa com '.
+fn csv_cell(s: &str) -> String {
+    let s = if s.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", s)
+    } else {
+        s.to_string()
+    };
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
+
+// Uma linha por credencial; uma coluna "attr:<nome>" por atributo visto.
+fn issued_csv(records: &[IssuedCredentialRecord]) -> String {
+    let attrs: BTreeSet<&String> = records.iter().flat_map(|r| r.values.keys()).collect();
+    let mut header: Vec<String> = [
+        "id",
+        "issued_at",
+        "cred_def_id",
+        "schema_id",
+        "holder_did",
+        "rev_reg_id",
+        "rev_index",
+        "values_mode",
+        "renews",
+        "superseded_by",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    header.extend(attrs.iter().map(|a| format!("attr:{}", a)));
+
+    let mut lines = vec![header
+        .iter()
+        .map(|h| csv_cell(h))
+        .collect::<Vec<_>>()
+        .join(",")];
+    for r in records {
+        let mut row = vec![
+            r.id.clone(),
+            r.issued_at.to_string(),
+            r.cred_def_id.clone(),
+            r.schema_id.clone().unwrap_or_default(),
+            r.holder_did.clone().unwrap_or_default(),
+            r.rev_reg_id.clone().unwrap_or_default(),
+            r.rev_index.map(|i| i.to_string()).unwrap_or_default(),
+            r.values_mode.clone(),
+            r.renews.clone().unwrap_or_default(),
+            r.superseded_by.clone().unwrap_or_default(),
+        ];
+        row.extend(
+            attrs
+                .iter()
+                .map(|a| r.values.get(*a).cloned().unwrap_or_default()),
+        );
+        lines.push(
+            row.iter()
+                .map(|c| csv_cell(c))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+    lines.join("\n") + "\n"
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  REGISTRO DE EMISSÃO (ISSUER)
+    // =========================================================================
+
+    // policy_json: { mode?: "plain" | "hashed" | "none", retentionDays?: number }
+    // Vale para as próximas emissões; a retenção também vale para as antigas.
+    #[napi]
+    pub async fn set_issued_values_policy(&self, policy_json: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let policy: IssuedValuesPolicy = serde_json::from_str(&policy_json)
+            .map_err(|e| napi_err("IssuedPolicyInvalid", e.to_string()))?;
+        check_policy(&policy)?;
+        let json = serde_json::to_string(&policy)
+            .map_err(|e| Error::from_reason(format!("Erro serializar política: {}", e)))?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        remove_if_exists(&mut session, CONFIG_CATEGORY, KEY_ISSUED_VALUES_POLICY).await?;
+        session
+            .insert(
+                CONFIG_CATEGORY,
+                KEY_ISSUED_VALUES_POLICY,
+                json.as_bytes(),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar config: {}", e)))?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+        Ok(json)
+    }
+
+    #[napi]
+    pub async fn get_issued_values_policy(&self) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let policy = load_policy(&mut session).await?;
+        serde_json::to_string(&policy)
+            .map_err(|e| Error::from_reason(format!("Erro serializar política: {}", e)))
+    }
+
+    // Aplica a retenção agora; devolve quantos registros perderam os valores.
+    #[napi]
+    pub async fn purge_issued_values(&self) -> Result<u32> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let purged = apply_retention(&mut session).await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+        Ok(purged)
+    }
+
+    // filter_json opcional: { credDefId?, schemaId?, holderDid?, threadId?,
+    //   issuedSince?, issuedUntil? } (epoch s). Ordenado por issuedAt desc.
+    #[napi]
+    pub async fn list_issued_credentials(&self, filter_json: Option<String>) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let filter = parse_filter(filter_json)?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        apply_retention(&mut session).await?;
+        let records = fetch_issued(&mut session, Some(&filter)).await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        serde_json::to_string(&records)
+            .map_err(|e| Error::from_reason(format!("Erro serializar lista: {}", e)))
+    }
+
+    // format: "csv" | "json". Mesmo filtro de listIssuedCredentials.
+    // json: { type, exportedAt, policy, count, items }.
+    #[napi]
+    pub async fn export_issued_credentials(
+        &self,
+        format: String,
+        filter_json: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        if format != "csv" && format != "json" {
+            return Err(napi_err(
+                "IssuedExportFormatInvalid",
+                format!("formato deve ser csv ou json: '{}'", format),
+            ));
+        }
+        let filter = parse_filter(filter_json)?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        apply_retention(&mut session).await?;
+        let records = fetch_issued(&mut session, Some(&filter)).await?;
+        let policy = load_policy(&mut session).await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        if format == "csv" {
+            return Ok(issued_csv(&records));
+        }
+        serde_json::to_string(&json!({
+            "type": "ssi-issued-credentials-export",
+            "exportedAt": now_ts(),
+            "policy": policy,
+            "count": records.len(),
+            "items": records,
+        }))
+        .map_err(|e| Error::from_reason(format!("Erro serializar export: {}", e)))
+    }
+
+    // Oferta de renovação de uma credencial já emitida (previous_issued_id = id
+    // do registro de emissão, o nonce da oferta original). Usa a cred def ativa
+    // (segue rotação), abre a troca já com o DID do holder e o elo "renews"; a
+    // emissão marca o registro anterior com supersededBy.
+    // Retorna { offerJson, threadId, credDefId, previousIssuedId, holderDid,
+    // valuesMode, prefilled, values } — values só vem preenchido quando o
+    // registro guardou os valores em claro (política plain).
+    #[napi]
+    pub async fn create_renewal_offer(
+        &self,
+        previous_issued_id: String,
+        offer_id_local: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let prev = load_issued(&mut session, &previous_issued_id)
+            .await?
+            .ok_or_else(|| {
+                napi_err(
+                    "IssuedCredentialNotFound",
+                    format!("Registro de emissão não encontrado: {}", previous_issued_id),
+                )
+            })?;
+        if let Some(by) = &prev.superseded_by {
+            return Err(napi_err(
+                "IssuedAlreadyRenewed",
+                format!("{} já foi renovada pela emissão {}", previous_issued_id, by),
+            ));
+        }
+
+        let offer_id_local = offer_id_local
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| format!("renewal-{}-{:08x}", previous_issued_id, OsRng.next_u32()));
+        let (offer_json, thread_id) = create_offer_in_session(
+            &mut session,
+            &prev.cred_def_id,
+            &offer_id_local,
+            None,
+            ExchangePatch {
+                counterparty_did: prev.holder_did.clone(),
+                renews: Some(previous_issued_id.clone()),
+                ..Default::default()
+            },
+        )
+        .await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        let offer: Value = serde_json::from_str(&offer_json).unwrap_or_default();
+        let prefilled = prev.values_mode == MODE_PLAIN;
+        serde_json::to_string(&json!({
+            "offerJson": offer_json,
+            "threadId": thread_id,
+            "offerIdLocal": offer_id_local,
+            "credDefId": offer["cred_def_id"],
+            "previousIssuedId": previous_issued_id,
+            "holderDid": prev.holder_did,
+            "valuesMode": prev.values_mode,
+            "prefilled": prefilled,
+            "values": if prefilled { prev.values } else { BTreeMap::new() },
+        }))
+        .map_err(|e| Error::from_reason(format!("Erro serializar oferta: {}", e)))
+    }
+}
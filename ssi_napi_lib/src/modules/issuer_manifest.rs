@@ -0,0 +1,468 @@
+// src/modules/issuer_manifest.rs
+//
+// Publicação declarativa de schemas + cred defs de um emissor (ex.: nova
+// região do espaço aéreo). Idempotente contra ledger e wallet, executa em
+// ordem de dependência (schemas -> cred defs) e devolve relatório por item.
+
+use crate::modules::common::{build_final_attr_names, napi_err, send_request_async};
+use crate::modules::creddefs::{register_cred_def_with_pool, republish_cred_def_with_pool};
+use crate::modules::schema_templates::find_schema_template;
+use crate::modules::schemas::register_schema_with_pool;
+use crate::IndyAgent;
+use indy_data_types::{CredentialDefinitionId, SchemaId as LedgerSchemaId};
+use indy_vdr::ledger::RequestBuilder;
+use indy_vdr::pool::{PoolRunner, ProtocolVersion};
+use napi::{Env, Error, JsObject, Result};
+use napi_derive::napi;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuerManifest {
+    pub issuer_did: String,
+    #[serde(default)]
+    pub schemas: Vec<ManifestSchema>,
+    #[serde(default)]
+    pub cred_defs: Vec<ManifestCredDef>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSchema {
+    #[serde(rename = "ref")]
+    pub reference: String,
+    pub template_id: Option<String>, // alternativa a name/version/attrNames
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub attr_names: Option<Vec<String>>,
+    #[serde(default)]
+    pub revocable: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestCredDef {
+    pub schema_ref: Option<String>, // ref de um schema do manifesto
+    pub schema_id: Option<String>,  // ou schema já existente no ledger
+    #[serde(default = "default_cred_def_tag")]
+    pub tag: String,
+    #[serde(default)]
+    pub support_revocation: bool,
+}
+
+fn default_cred_def_tag() -> String {
+    "default".to_string()
+}
+
+// Schema resolvido a partir do manifesto (template ou declaração explícita).
+struct PlannedSchema {
+    reference: String,
+    name: String,
+    version: String,
+    final_attrs: Vec<String>,
+}
+
+fn plan_schemas(m: &IssuerManifest) -> std::result::Result<Vec<PlannedSchema>, Vec<String>> {
+    let mut problems = Vec::new();
+    let mut out = Vec::new();
+
+    for (i, s) in m.schemas.iter().enumerate() {
+        if s.reference.trim().is_empty() {
+            problems.push(format!("schemas[{}]: ref vazio", i));
+            continue;
+        }
+        if out.iter().any(|p: &PlannedSchema| p.reference == s.reference) {
+            problems.push(format!("schemas[{}]: ref duplicado {}", i, s.reference));
+            continue;
+        }
+
+        let (name, version, attrs, revocable) = match &s.template_id {
+            Some(tid) => match find_schema_template(tid) {
+                Some(t) => (
+                    t.name.to_string(),
+                    t.version.to_string(),
+                    t.attr_names.iter().map(|a| a.to_string()).collect(),
+                    t.revocable,
+                ),
+                None => {
+                    problems.push(format!("schemas[{}]: template desconhecido {}", i, tid));
+                    continue;
+                }
+            },
+            None => match (&s.name, &s.version, &s.attr_names) {
+                (Some(n), Some(v), Some(a)) => (n.clone(), v.clone(), a.clone(), s.revocable),
+                _ => {
+                    problems.push(format!(
+                        "schemas[{}]: informe templateId ou name/version/attrNames",
+                        i
+                    ));
+                    continue;
+                }
+            },
+        };
+
+        match build_final_attr_names(attrs, revocable) {
+            Ok(final_attrs) => out.push(PlannedSchema {
+                reference: s.reference.clone(),
+                name,
+                version,
+                final_attrs,
+            }),
+            Err(e) => problems.push(format!("schemas[{}]: {}", i, e.reason)),
+        }
+    }
+
+    for (i, c) in m.cred_defs.iter().enumerate() {
+        match (&c.schema_ref, &c.schema_id) {
+            (Some(r), None) => {
+                if !out.iter().any(|p| &p.reference == r) {
+                    problems.push(format!("credDefs[{}]: schemaRef desconhecido {}", i, r));
+                }
+            }
+            (None, Some(_)) => {}
+            _ => problems.push(format!(
+                "credDefs[{}]: informe exatamente um de schemaRef/schemaId",
+                i
+            )),
+        }
+        if c.tag.trim().is_empty() || c.tag.contains(':') {
+            problems.push(format!("credDefs[{}]: tag inválida \"{}\"", i, c.tag));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(out)
+    } else {
+        Err(problems)
+    }
+}
+
+// GET_SCHEMA -> (seqNo, attrNames) se existir no ledger.
+async fn ledger_schema(
+    pool: &PoolRunner,
+    schema_id: &str,
+) -> Result<Option<(u64, Vec<String>)>> {
+    let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
+    let req = rb
+        .build_get_schema_request(None, &LedgerSchemaId(schema_id.to_string()))
+        .map_err(|e| napi::Error::from_reason(format!("Erro build GET_SCHEMA: {}", e)))?;
+    let resp = send_request_async(pool, req).await?;
+    let v: serde_json::Value = serde_json::from_str(&resp).unwrap_or_default();
+
+    let seq_no = match v["result"]["seqNo"].as_u64() {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let data: serde_json::Value = match &v["result"]["data"] {
+        serde_json::Value::String(s) => serde_json::from_str(s).unwrap_or_default(),
+        other => other.clone(),
+    };
+    let attrs = data
+        .get("attr_names")
+        .or_else(|| data.get("attrNames"))
+        .and_then(|a| a.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|x| x.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(Some((seq_no, attrs)))
+}
+
+async fn ledger_has_cred_def(pool: &PoolRunner, cred_def_id: &str) -> Result<bool> {
+    let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
+    let req = rb
+        .build_get_cred_def_request(None, &CredentialDefinitionId(cred_def_id.to_string()))
+        .map_err(|e| napi::Error::from_reason(format!("Erro build GET_CRED_DEF: {}", e)))?;
+    let resp = send_request_async(pool, req).await?;
+    let v: serde_json::Value = serde_json::from_str(&resp).unwrap_or_default();
+    Ok(!v["result"]["data"].is_null())
+}
+
+fn same_attr_set(a: &[String], b: &[String]) -> bool {
+    let mut x: Vec<String> = a.iter().map(|s| s.to_lowercase()).collect();
+    let mut y: Vec<String> = b.iter().map(|s| s.to_lowercase()).collect();
+    x.sort();
+    y.sort();
+    x == y
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  PUBLICAÇÃO EM LOTE (MANIFESTO DO EMISSOR)
+    // =========================================================================
+    // manifest_json:
+    // {
+    //   "issuerDid": "...", "dryRun"?: false,
+    //   "schemas":  [{ "ref": "pilot", "templateId": "uam.pilot_license.v1" },
+    //                { "ref": "x", "name": "..", "version": "..", "attrNames": [..],
+    //                  "revocable"?: false }],
+    //   "credDefs": [{ "schemaRef": "pilot" | "schemaId": "...", "tag"?: "default",
+    //                  "supportRevocation"?: false }]
+    // }
+    // Status por item: published | skipped (já existe) | conflict | failed | planned (dryRun).
+    #[napi]
+    pub fn publish_issuer_manifest(&self, env: Env, manifest_json: String) -> Result<JsObject> {
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => {
+                return Err(Error::from_reason(
+                    "Não conectado à rede. Execute connect_network antes.",
+                ))
+            }
+        };
+
+        env.execute_tokio_future(
+            async move {
+                let manifest: IssuerManifest = serde_json::from_str(&manifest_json)
+                    .map_err(|e| napi_err("ManifestInvalid", e.to_string()))?;
+                let issuer_did = manifest.issuer_did.clone();
+                if issuer_did.trim().is_empty() {
+                    return Err(napi_err("ManifestInvalid", "issuerDid vazio"));
+                }
+
+                // Valida tudo antes de qualquer escrita
+                let planned = plan_schemas(&manifest)
+                    .map_err(|problems| napi_err("ManifestInvalid", problems.join("; ")))?;
+
+                {
+                    let mut session = store
+                        .session(None)
+                        .await
+                        .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+                    let has_did = session
+                        .fetch("did", &issuer_did, false)
+                        .await
+                        .map_err(|e| napi::Error::from_reason(format!("Erro fetch did: {}", e)))?
+                        .is_some();
+                    if !has_did {
+                        return Err(napi_err(
+                            "ManifestIssuerNotInWallet",
+                            format!("DID emissor não está na wallet: {}", issuer_did),
+                        ));
+                    }
+                }
+
+                let dry_run = manifest.dry_run;
+                let mut schema_report: Vec<serde_json::Value> = Vec::new();
+                // ref -> (schemaId, seqNo) dos schemas disponíveis para as cred defs
+                let mut available: HashMap<String, (String, Option<u64>)> = HashMap::new();
+
+                // 1) SCHEMAS
+                for p in planned.iter() {
+                    let schema_id = format!("{}:2:{}:{}", issuer_did, p.name, p.version);
+                    let mut item = serde_json::json!({
+                        "ref": p.reference,
+                        "schemaId": schema_id,
+                        "name": p.name,
+                        "version": p.version
+                    });
+
+                    let outcome: Result<()> = async {
+                        match ledger_schema(&pool, &schema_id).await? {
+                            Some((seq, attrs)) if same_attr_set(&attrs, &p.final_attrs) => {
+                                item["status"] = "skipped".into();
+                                item["reason"] = "already_on_ledger".into();
+                                item["seqNo"] = seq.into();
+                                available
+                                    .insert(p.reference.clone(), (schema_id.clone(), Some(seq)));
+                            }
+                            Some((seq, attrs)) => {
+                                item["status"] = "conflict".into();
+                                item["reason"] = "ledger_attrs_differ".into();
+                                item["seqNo"] = seq.into();
+                                item["ledgerAttrNames"] = attrs.into();
+                            }
+                            None if dry_run => {
+                                item["status"] = "planned".into();
+                                available.insert(p.reference.clone(), (schema_id.clone(), None));
+                            }
+                            None => {
+                                register_schema_with_pool(
+                                    &store,
+                                    &pool,
+                                    issuer_did.clone(),
+                                    p.name.clone(),
+                                    p.version.clone(),
+                                    p.final_attrs.clone(),
+                                )
+                                .await?;
+                                let seq =
+                                    ledger_schema(&pool, &schema_id).await?.map(|(s, _)| s);
+                                item["status"] = "published".into();
+                                item["seqNo"] = seq.into();
+                                available.insert(p.reference.clone(), (schema_id.clone(), seq));
+                            }
+                        }
+                        Ok(())
+                    }
+                    .await;
+
+                    if let Err(e) = outcome {
+                        item["status"] = "failed".into();
+                        item["error"] = e.reason.into();
+                    }
+                    schema_report.push(item);
+                }
+
+                // 2) CRED DEFS (dependem dos schemas)
+                let mut cred_def_report: Vec<serde_json::Value> = Vec::new();
+                for c in manifest.cred_defs.iter() {
+                    let mut item = serde_json::json!({
+                        "schemaRef": c.schema_ref,
+                        "tag": c.tag,
+                        "supportRevocation": c.support_revocation
+                    });
+
+                    let outcome: Result<()> = async {
+                        let (schema_id, seq) = match (&c.schema_ref, &c.schema_id) {
+                            (Some(r), _) => match available.get(r) {
+                                Some(v) => v.clone(),
+                                None => {
+                                    item["status"] = "skipped".into();
+                                    item["reason"] = "schema_unavailable".into();
+                                    return Ok(());
+                                }
+                            },
+                            (None, Some(sid)) => match ledger_schema(&pool, sid).await? {
+                                Some((s, _)) => (sid.clone(), Some(s)),
+                                None => {
+                                    item["status"] = "skipped".into();
+                                    item["reason"] = "schema_unavailable".into();
+                                    item["schemaId"] = sid.clone().into();
+                                    return Ok(());
+                                }
+                            },
+                            (None, None) => unreachable!("validado em plan_schemas"),
+                        };
+                        item["schemaId"] = schema_id.clone().into();
+
+                        let seq = match seq {
+                            Some(s) => s,
+                            None => {
+                                // dryRun com schema ainda não publicado
+                                item["status"] = "planned".into();
+                                return Ok(());
+                            }
+                        };
+                        let cred_def_id = format!("{}:3:CL:{}:{}", issuer_did, seq, c.tag);
+                        item["credDefId"] = cred_def_id.clone().into();
+
+                        let mut session = store
+                            .session(None)
+                            .await
+                            .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+                        let in_wallet = session
+                            .fetch("cred_def_private", &cred_def_id, false)
+                            .await
+                            .map_err(|e| {
+                                napi::Error::from_reason(format!("Erro fetch cred_def: {}", e))
+                            })?
+                            .is_some();
+                        drop(session);
+
+                        let on_ledger = ledger_has_cred_def(&pool, &cred_def_id).await?;
+                        if in_wallet && on_ledger {
+                            item["status"] = "skipped".into();
+                            item["reason"] = "already_in_wallet".into();
+                        } else if in_wallet {
+                            // Chaves gravadas mas a escrita no ledger falhou: republica
+                            if dry_run {
+                                item["status"] = "planned".into();
+                            } else {
+                                republish_cred_def_with_pool(
+                                    &store,
+                                    &pool,
+                                    &issuer_did,
+                                    &cred_def_id,
+                                )
+                                .await?;
+                                item["status"] = "published".into();
+                            }
+                            item["reason"] = "local_only".into();
+                        } else if on_ledger {
+                            // Publicada por outra wallet: sem a chave privada não há emissão
+                            item["status"] = "conflict".into();
+                            item["reason"] = "on_ledger_without_private_key".into();
+                        } else if dry_run {
+                            item["status"] = "planned".into();
+                        } else {
+                            register_cred_def_with_pool(
+                                &store,
+                                &pool,
+                                issuer_did.clone(),
+                                schema_id,
+                                c.tag.clone(),
+                                c.support_revocation,
+                            )
+                            .await?;
+                            item["status"] = "published".into();
+                        }
+
+                        // seqNo da CLAIM_DEF (tag gravada no registro local)
+                        let mut session = store
+                            .session(None)
+                            .await
+                            .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+                        let entry = session
+                            .fetch("cred_def", &cred_def_id, false)
+                            .await
+                            .map_err(|e| {
+                                napi::Error::from_reason(format!("Erro fetch cred_def: {}", e))
+                            })?;
+                        if let Some(t) = entry
+                            .as_ref()
+                            .and_then(|e| e.tags.iter().find(|t| t.name() == "ledger_seq_no"))
+                        {
+                            item["ledgerSeqNo"] = t.value().parse::<u64>().ok().into();
+                        }
+                        Ok(())
+                    }
+                    .await;
+
+                    if let Err(e) = outcome {
+                        item["status"] = "failed".into();
+                        item["error"] = e.reason.into();
+                    }
+                    cred_def_report.push(item);
+                }
+
+                let count = |st: &str| {
+                    schema_report
+                        .iter()
+                        .chain(cred_def_report.iter())
+                        .filter(|i| i["status"] == st)
+                        .count()
+                };
+                let summary = serde_json::json!({
+                    "published": count("published"),
+                    "skipped": count("skipped"),
+                    "planned": count("planned"),
+                    "conflict": count("conflict"),
+                    "failed": count("failed")
+                });
+                let ok = summary["conflict"] == 0 && summary["failed"] == 0;
+
+                Ok(serde_json::json!({
+                    "ok": ok,
+                    "issuerDid": issuer_did,
+                    "dryRun": dry_run,
+                    "summary": summary,
+                    "schemas": schema_report,
+                    "credDefs": cred_def_report
+                })
+                .to_string())
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
+}
@@ -0,0 +1,422 @@
+// src/modules/link_secrets.rs
+//
+// Manutenção de link secrets do holder: relatório/migração dos link secrets
+// legados (u128 usado direto como segredo, ~128 bits) e backup cifrado para
+// troca de dispositivo.
+//
+// Credenciais ficam presas ao link secret com que foram solicitadas: migrar não
+// converte credenciais antigas, só cria um link secret novo (default daqui em
+// diante) e lista quais credenciais precisam ser reemitidas pelo emissor.
+
+use crate::modules::common::{
+    napi_err, now_ts, open_with_pass, remove_if_exists, seal_with_pass, tag_value, CONFIG_CATEGORY,
+    KEY_DEFAULT_LINK_SECRET_ID,
+};
+use crate::modules::creddefs::rewrite_entry_tags;
+use crate::modules::credentials::{
+    default_link_secret_id, generate_link_secret, insert_link_secret, link_secret_format,
+    LINK_SECRET_CATEGORY, LINK_SECRET_FORMAT_U128,
+};
+use crate::IndyAgent;
+use anoncreds::types::LinkSecret;
+use aries_askar::entry::{Entry, EntryTag};
+use napi::{Error, Result};
+use napi_derive::napi;
+use std::collections::HashMap;
+
+const LINK_SECRET_EXPORT_TYPE: &str = "ssi-link-secret-export";
+
+async fn fetch_link_secret_entry(session: &mut aries_askar::Session, id: &str) -> Result<Entry> {
+    session
+        .fetch(LINK_SECRET_CATEGORY, id, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro DB LS: {}", e)))?
+        .ok_or_else(|| {
+            napi_err(
+                "LinkSecretNotFound",
+                format!("Link secret não encontrado: {}", id),
+            )
+        })
+}
+
+// Credenciais do holder agrupadas pelo link secret usado no request.
+// Credenciais anteriores à tag link_secret_id foram processadas com o legado
+// "default" (ou, sem ele, com o default atual da wallet).
+async fn credentials_by_link_secret(
+    session: &mut aries_askar::Session,
+) -> Result<HashMap<String, Vec<serde_json::Value>>> {
+    let legacy_id = match session
+        .fetch(LINK_SECRET_CATEGORY, "default", false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro DB LS: {}", e)))?
+    {
+        Some(_) => Some("default".to_string()),
+        None => default_link_secret_id(session).await?,
+    };
+
+    let entries = session
+        .fetch_all(Some("credential"), None, None, None, false, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch credential: {}", e)))?;
+
+    let mut out: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+    for e in entries {
+        let ls_id = match tag_value(&e.tags, "link_secret_id").or_else(|| legacy_id.clone()) {
+            Some(id) => id,
+            None => continue,
+        };
+        let v: serde_json::Value = serde_json::from_slice(&e.value).unwrap_or_default();
+        let cred = v.get("credential").unwrap_or(&v);
+        let cred_def_id = tag_value(&e.tags, "cred_def_id")
+            .or_else(|| cred["cred_def_id"].as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+        let schema_id = tag_value(&e.tags, "schema_id")
+            .or_else(|| cred["schema_id"].as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+        let issuer_did = cred_def_id
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        out.entry(ls_id).or_default().push(serde_json::json!({
+            "id": e.name,
+            "schemaId": schema_id,
+            "credDefId": cred_def_id,
+            "issuerDid": issuer_did
+        }));
+    }
+    Ok(out)
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  MIGRAÇÃO DE LINK SECRETS LEGADOS
+    // =========================================================================
+
+    // Relatório por link secret: formato, se é fraco (u128) e as credenciais
+    // presas a ele. "reissue" lista as credenciais presas a link secrets fracos.
+    #[napi]
+    pub async fn link_secret_migration_report(&self) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let default_id = default_link_secret_id(&mut session).await?;
+        let mut by_ls = credentials_by_link_secret(&mut session).await?;
+        let entries = session
+            .fetch_all(Some(LINK_SECRET_CATEGORY), None, None, None, false, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch link secrets: {}", e)))?;
+
+        let mut items = Vec::new();
+        let mut reissue = Vec::new();
+        for e in entries {
+            let format = link_secret_format(&e.tags, &e.value);
+            let weak = format == LINK_SECRET_FORMAT_U128;
+            let creds = by_ls.remove(&e.name).unwrap_or_default();
+            if weak {
+                for c in &creds {
+                    let mut c = c.clone();
+                    c["linkSecretId"] = e.name.clone().into();
+                    reissue.push(c);
+                }
+            }
+            items.push(serde_json::json!({
+                "id": &e.name,
+                "format": format,
+                "weak": weak,
+                "isDefault": default_id.as_deref() == Some(e.name.as_str()),
+                "status": tag_value(&e.tags, "status").unwrap_or_else(|| "active".to_string()),
+                "supersededBy": tag_value(&e.tags, "superseded_by"),
+                "credentials": creds
+            }));
+        }
+        items.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+        // Credenciais cujo link secret não está mais na wallet: não apresentáveis
+        let mut orphaned: Vec<serde_json::Value> = Vec::new();
+        for (ls_id, creds) in by_ls {
+            for mut c in creds {
+                c["linkSecretId"] = ls_id.clone().into();
+                orphaned.push(c);
+            }
+        }
+
+        Ok(serde_json::json!({
+            "ok": true,
+            "defaultLinkSecretId": default_id,
+            "weakCount": items.iter().filter(|i| i["weak"] == true).count(),
+            "linkSecrets": items,
+            "reissue": reissue,
+            "orphaned": orphaned
+        })
+        .to_string())
+    }
+
+    // Cria um link secret nativo substituindo `link_secret_id` (novo id default
+    // "<id>_v2"). O antigo fica com status=superseded e continua utilizável,
+    // por id explícito, para as credenciais já emitidas; se era o default, o novo
+    // assume. Devolve as credenciais que devem ser reemitidas.
+    #[napi]
+    pub async fn migrate_link_secret(
+        &self,
+        link_secret_id: String,
+        new_link_secret_id: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let old = fetch_link_secret_entry(&mut session, &link_secret_id).await?;
+        if let Some(by) = tag_value(&old.tags, "superseded_by") {
+            return Err(napi_err(
+                "LinkSecretAlreadyMigrated",
+                format!("Link secret {} já migrado para {}", link_secret_id, by),
+            ));
+        }
+        let old_format = link_secret_format(&old.tags, &old.value);
+
+        let new_id = new_link_secret_id
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| format!("{}_v2", link_secret_id));
+        if session
+            .fetch(LINK_SECRET_CATEGORY, &new_id, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro DB LS: {}", e)))?
+            .is_some()
+        {
+            return Err(napi_err(
+                "LinkSecretAlreadyExists",
+                format!("Link secret já existe: {}", new_id),
+            ));
+        }
+
+        let was_default =
+            default_link_secret_id(&mut session).await?.as_deref() == Some(link_secret_id.as_str());
+
+        let (value, _) = generate_link_secret()?;
+        insert_link_secret(
+            &mut session,
+            &new_id,
+            &value,
+            vec![EntryTag::Encrypted(
+                "supersedes".to_string(),
+                link_secret_id.clone(),
+            )],
+        )
+        .await?;
+
+        let now = now_ts().to_string();
+        rewrite_entry_tags(
+            &mut session,
+            LINK_SECRET_CATEGORY,
+            &link_secret_id,
+            &[
+                ("status", "superseded".to_string()),
+                ("superseded_by", new_id.clone()),
+                ("superseded_at", now),
+            ],
+        )
+        .await?;
+
+        if was_default {
+            remove_if_exists(&mut session, CONFIG_CATEGORY, KEY_DEFAULT_LINK_SECRET_ID).await?;
+            session
+                .insert(
+                    CONFIG_CATEGORY,
+                    KEY_DEFAULT_LINK_SECRET_ID,
+                    new_id.as_bytes(),
+                    None,
+                    None,
+                )
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro salvar config: {}", e)))?;
+        }
+
+        let reissue = credentials_by_link_secret(&mut session)
+            .await?
+            .remove(&link_secret_id)
+            .unwrap_or_default();
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(serde_json::json!({
+            "ok": true,
+            "oldLinkSecretId": link_secret_id,
+            "oldFormat": old_format,
+            "newLinkSecretId": new_id,
+            "isDefault": was_default,
+            "reissue": reissue
+        })
+        .to_string())
+    }
+
+    // =========================================================================
+    //  BACKUP DE LINK SECRET (troca de dispositivo)
+    // =========================================================================
+
+    // Pacote { type, version, linkSecretId, format, exportedAt, sealed } com o
+    // valor canônico cifrado por senha (Argon2id + AES-256-GCM).
+    #[napi]
+    pub async fn export_link_secret(
+        &self,
+        link_secret_id: String,
+        export_pass: String,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entry = fetch_link_secret_entry(&mut session, &link_secret_id).await?;
+        let format = link_secret_format(&entry.tags, &entry.value);
+        let value = String::from_utf8(entry.value.to_vec()).unwrap_or_default();
+
+        let inner = serde_json::json!({
+            "linkSecretId": link_secret_id,
+            "format": format,
+            "value": value,
+            "createdAt": tag_value(&entry.tags, "created_at")
+        });
+        let sealed = seal_with_pass(&export_pass, inner.to_string().as_bytes())?;
+
+        Ok(serde_json::json!({
+            "type": LINK_SECRET_EXPORT_TYPE,
+            "version": 1,
+            "linkSecretId": link_secret_id,
+            "format": format,
+            "exportedAt": now_ts(),
+            "sealed": sealed
+        })
+        .to_string())
+    }
+
+    // Importa um pacote de exportLinkSecret (id opcionalmente renomeado).
+    // Se o id já existir, recusa (LinkSecretAlreadyExists) salvo overwrite=true;
+    // mesmo com overwrite, recusa se houver credenciais com ele (LinkSecretInUse).
+    #[napi]
+    pub async fn import_link_secret(
+        &self,
+        package_json: String,
+        export_pass: String,
+        overwrite: Option<bool>,
+        link_secret_id: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let overwrite = overwrite.unwrap_or(false);
+
+        let package: serde_json::Value = serde_json::from_str(&package_json)
+            .map_err(|e| napi_err("LinkSecretPackageInvalid", e.to_string()))?;
+        if package["type"].as_str() != Some(LINK_SECRET_EXPORT_TYPE) {
+            return Err(napi_err(
+                "LinkSecretPackageInvalid",
+                format!("pacote não é {}", LINK_SECRET_EXPORT_TYPE),
+            ));
+        }
+
+        let plain = open_with_pass(&export_pass, &package["sealed"])?;
+        let inner: serde_json::Value = serde_json::from_slice(&plain)
+            .map_err(|e| napi_err("LinkSecretPackageInvalid", e.to_string()))?;
+        let original_id = inner["linkSecretId"]
+            .as_str()
+            .ok_or_else(|| napi_err("LinkSecretPackageInvalid", "linkSecretId ausente"))?;
+        if package["linkSecretId"].as_str() != Some(original_id) {
+            return Err(napi_err(
+                "LinkSecretPackageInvalid",
+                "linkSecretId do envelope difere do conteúdo cifrado",
+            ));
+        }
+        let value = inner["value"]
+            .as_str()
+            .ok_or_else(|| napi_err("LinkSecretPackageInvalid", "value ausente"))?;
+        LinkSecret::try_from(value)
+            .map_err(|e| napi_err("LinkSecretPackageInvalid", format!("{:?}", e)))?;
+
+        let id = link_secret_id
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| original_id.to_string());
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let existing = session
+            .fetch(LINK_SECRET_CATEGORY, &id, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro DB LS: {}", e)))?;
+        if existing.is_some() {
+            if !overwrite {
+                return Err(napi_err(
+                    "LinkSecretAlreadyExists",
+                    format!("Link secret já existe na wallet: {}", id),
+                ));
+            }
+            // Trocar o valor invalidaria as credenciais processadas com ele.
+            let in_use = credentials_by_link_secret(&mut session)
+                .await?
+                .get(&id)
+                .map_or(0, |creds| creds.len());
+            if in_use > 0 {
+                return Err(napi_err(
+                    "LinkSecretInUse",
+                    format!("Link secret {} é usado por {} credencial(is)", id, in_use),
+                ));
+            }
+            session
+                .remove(LINK_SECRET_CATEGORY, &id)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro remove LS: {}", e)))?;
+        }
+
+        insert_link_secret(
+            &mut session,
+            &id,
+            value,
+            vec![EntryTag::Encrypted(
+                "imported_at".to_string(),
+                now_ts().to_string(),
+            )],
+        )
+        .await?;
+        let is_default =
+            default_link_secret_id(&mut session).await?.as_deref() == Some(id.as_str());
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        // Valor em cache (se havia) é o anterior ao overwrite
+        self.link_secrets.lock().unwrap().remove(&id);
+
+        Ok(serde_json::json!({
+            "ok": true,
+            "linkSecretId": id,
+            "format": link_secret_format(&[], value.as_bytes()),
+            "isDefault": is_default
+        })
+        .to_string())
+    }
+}
@@ -0,0 +1,295 @@
+// src/modules/offer_expiry.rs
+//
+// Expiração de ofertas de credencial, dos dois lados.
+//
+//   issuer: "cred_offer" (offer_id_local), criada por create_credential_offer
+//   holder: "received_offer" (received-offer-<nonce>), por store_received_offer
+//
+// O prazo fica na tag expires_at da oferta e em expires_at da troca
+// (exchanges.rs); create_credential_request e create_credential recusam oferta
+// vencida (OfferExpired) ou já consumida (OfferAlreadyUsed). Sem prazo
+// explícito vale o padrão da wallet (setOfferExpiryConfig, 7 dias; 0 = nunca).
+// purgeExpiredOffers apaga as vencidas e abandona as trocas ainda pendentes;
+// pode ser chamado periodicamente pela aplicação. Ofertas gravadas antes da
+// tag expires_at vencem por created_at / received_at + padrão atual.
+
+use crate::modules::common::{
+    napi_err, now_ts, remove_if_exists, tag_value, CONFIG_CATEGORY, KEY_OFFER_EXPIRY,
+};
+use crate::modules::exchanges::{
+    exchange_transition_to, load_exchange, ExchangePatch, ROLE_HOLDER, ROLE_ISSUER,
+};
+use crate::IndyAgent;
+use aries_askar::entry::{EntryTag, TagFilter};
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+pub(crate) const ISSUER_OFFER_CATEGORY: &str = "cred_offer";
+pub(crate) const HOLDER_OFFER_CATEGORY: &str = "received_offer";
+
+const DEFAULT_OFFER_TTL_SECS: u64 = 7 * 86_400;
+const PURGE_REASON: &str = "offer-expired";
+
+// Estados em que a oferta ainda não virou credencial: a troca é abandonada
+// junto com a oferta vencida.
+const ISSUER_PENDING: &[&str] = &["offer-sent", "request-received"];
+const HOLDER_PENDING: &[&str] = &["offer-received", "request-sent"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct OfferExpiryConfig {
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+fn default_ttl_secs() -> u64 {
+    DEFAULT_OFFER_TTL_SECS
+}
+
+impl Default for OfferExpiryConfig {
+    fn default() -> Self {
+        OfferExpiryConfig {
+            ttl_secs: DEFAULT_OFFER_TTL_SECS,
+        }
+    }
+}
+
+async fn load_offer_expiry_config(session: &mut aries_askar::Session) -> Result<OfferExpiryConfig> {
+    let entry = session
+        .fetch(CONFIG_CATEGORY, KEY_OFFER_EXPIRY, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch config: {}", e)))?;
+    Ok(entry
+        .and_then(|e| serde_json::from_slice(&e.value).ok())
+        .unwrap_or_default())
+}
+
+fn expiry_from(from: i64, ttl_secs: u64) -> Option<i64> {
+    (ttl_secs > 0).then(|| from.saturating_add(ttl_secs.min(i64::MAX as u64) as i64))
+}
+
+// Prazo de uma oferta criada/recebida agora. ttl_secs None = padrão da
+// wallet; 0 = sem expiração.
+pub(crate) async fn offer_expires_at(
+    session: &mut aries_askar::Session,
+    ttl_secs: Option<u32>,
+) -> Result<Option<i64>> {
+    let ttl = match ttl_secs {
+        Some(t) => t as u64,
+        None => load_offer_expiry_config(session).await?.ttl_secs,
+    };
+    Ok(expiry_from(now_ts(), ttl))
+}
+
+// Prazo gravado na oferta ou, sem a tag, o derivado da data de criação.
+fn entry_expires_at(tags: &[EntryTag], created_tag: &str, ttl_secs: u64) -> Option<i64> {
+    if let Some(exp) = tag_value(tags, "expires_at") {
+        return exp.parse().ok();
+    }
+    let created = tag_value(tags, created_tag)?.parse().ok()?;
+    expiry_from(created, ttl_secs)
+}
+
+// Prazo da oferta gravada deste lado, para quando não há registro de troca
+// (check_offer_usable). Oferta não encontrada: None.
+pub(crate) async fn stored_offer_expires_at(
+    session: &mut aries_askar::Session,
+    role: &str,
+    nonce: &str,
+) -> Result<Option<i64>> {
+    let ttl = load_offer_expiry_config(session).await?.ttl_secs;
+    if role == ROLE_HOLDER {
+        let entry = session
+            .fetch(
+                HOLDER_OFFER_CATEGORY,
+                &format!("received-offer-{}", nonce),
+                false,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch oferta: {}", e)))?;
+        return Ok(entry.and_then(|e| entry_expires_at(&e.tags, "received_at", ttl)));
+    }
+    let tagged = session
+        .fetch_all(
+            Some(ISSUER_OFFER_CATEGORY),
+            Some(TagFilter::is_eq("nonce", nonce)),
+            Some(1),
+            None,
+            false,
+            false,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch ofertas: {}", e)))?;
+    if let Some(e) = tagged.first() {
+        return Ok(entry_expires_at(&e.tags, "created_at", ttl));
+    }
+    // Ofertas antigas, sem tag de nonce: procura pelo valor.
+    let untagged = TagFilter::negate(TagFilter::exist(vec!["nonce".to_string()]));
+    let legacy = session
+        .fetch_all(
+            Some(ISSUER_OFFER_CATEGORY),
+            Some(untagged),
+            None,
+            None,
+            false,
+            false,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch ofertas: {}", e)))?;
+    Ok(legacy
+        .iter()
+        .find(|e| {
+            serde_json::from_slice::<serde_json::Value>(&e.value)
+                .map(|v| v["nonce"].as_str() == Some(nonce))
+                .unwrap_or(false)
+        })
+        .and_then(|e| entry_expires_at(&e.tags, "created_at", ttl)))
+}
+
+// Abandona a troca se a oferta vencida ainda não virou credencial.
+async fn close_pending_exchange(
+    session: &mut aries_askar::Session,
+    role: &str,
+    thread_id: &str,
+) -> Result<bool> {
+    let pending = if role == ROLE_ISSUER {
+        ISSUER_PENDING
+    } else {
+        HOLDER_PENDING
+    };
+    let rec = match load_exchange(session, role, thread_id).await? {
+        Some(rec) if pending.contains(&rec.state.as_str()) => rec,
+        _ => return Ok(false),
+    };
+    exchange_transition_to(
+        session,
+        role,
+        &rec.thread_id,
+        "abandoned",
+        ExchangePatch {
+            reason: Some(PURGE_REASON.to_string()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    Ok(true)
+}
+
+#[napi]
+impl IndyAgent {
+    // config_json: { ttlSecs?: prazo padrão das ofertas em segundos (0 = sem expiração) }
+    #[napi]
+    pub async fn set_offer_expiry_config(&self, config_json: String) -> Result<String> {
+        let cfg: OfferExpiryConfig = serde_json::from_str(&config_json)
+            .map_err(|e| napi_err("OfferExpiryConfigInvalid", e.to_string()))?;
+        let json = serde_json::to_string(&cfg)
+            .map_err(|e| Error::from_reason(format!("Erro serializar config: {}", e)))?;
+
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        remove_if_exists(&mut session, CONFIG_CATEGORY, KEY_OFFER_EXPIRY).await?;
+        session
+            .insert(
+                CONFIG_CATEGORY,
+                KEY_OFFER_EXPIRY,
+                json.as_bytes(),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar config: {}", e)))?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+        Ok(json)
+    }
+
+    #[napi]
+    pub async fn get_offer_expiry_config(&self) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let cfg = load_offer_expiry_config(&mut session).await?;
+        serde_json::to_string(&cfg)
+            .map_err(|e| Error::from_reason(format!("Erro serializar config: {}", e)))
+    }
+
+    // Apaga ofertas vencidas (issuer e holder) e abandona as trocas pendentes.
+    // Retorna { issuerOffers, receivedOffers, exchangesAbandoned }.
+    #[napi]
+    pub async fn purge_expired_offers(&self) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let ttl = load_offer_expiry_config(&mut session).await?.ttl_secs;
+        let now = now_ts();
+        let mut counts = [0u32; 2];
+        let mut abandoned = 0u32;
+
+        let sides = [
+            (ISSUER_OFFER_CATEGORY, ROLE_ISSUER, "created_at"),
+            (HOLDER_OFFER_CATEGORY, ROLE_HOLDER, "received_at"),
+        ];
+        for (i, (category, role, created_tag)) in sides.into_iter().enumerate() {
+            let entries = session
+                .fetch_all(Some(category), None, None, None, false, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch ofertas: {}", e)))?;
+
+            for entry in entries {
+                // Já emitida (issuer): a oferta é o registro da emissão, não vence.
+                if tag_value(&entry.tags, "issued_at").is_some() {
+                    continue;
+                }
+                match entry_expires_at(&entry.tags, created_tag, ttl) {
+                    Some(exp) if exp <= now => {}
+                    _ => continue,
+                }
+                let nonce = serde_json::from_slice::<serde_json::Value>(&entry.value)
+                    .ok()
+                    .and_then(|v| v["nonce"].as_str().map(|s| s.to_string()));
+                if let Some(nonce) = nonce {
+                    if close_pending_exchange(&mut session, role, &nonce).await? {
+                        abandoned += 1;
+                    }
+                }
+                session
+                    .remove(category, &entry.name)
+                    .await
+                    .map_err(|e| Error::from_reason(format!("Erro remover oferta: {}", e)))?;
+                counts[i] += 1;
+            }
+        }
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(serde_json::json!({
+            "issuerOffers": counts[0],
+            "receivedOffers": counts[1],
+            "exchangesAbandoned": abandoned,
+        })
+        .to_string())
+    }
+}
@@ -0,0 +1,697 @@
+// src/modules/overlays.rs
+//
+// Overlays de exibição (inspirados em OCA) para schemas e cred defs:
+// rótulos por idioma (pt-BR/en no mínimo), formatos, ordem, atributos ocultos
+// (CONTROL_ATTRS sempre ocultos) e identidade visual do emissor.
+// O emissor publica o overlay como ATTRIB no próprio DID; o holder busca no
+// ledger e o list_credentials_view("full") passa a devolver o campo "display".
+
+use crate::modules::common::{
+    is_reserved_control_attr, napi_err, now_ts, remove_if_exists, SchemaRecord, CONTROL_ATTRS,
+};
+use crate::IndyAgent;
+use crate::{read_attrib_with_pool, write_attrib_with_pool};
+use aries_askar::entry::{EntryTag, TagFilter};
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+pub const OVERLAY_CATEGORY: &str = "display_overlay";
+pub const OVERLAY_TYPE: &str = "ssi-display-overlay";
+pub const OVERLAY_ATTRIB_PREFIX: &str = "ssi_overlay:";
+pub const OVERLAY_REQUIRED_LANGUAGES: [&str; 2] = ["pt-BR", "en"];
+// ATTRIB vai inteiro numa transação: logos devem ser URLs, não imagens embutidas.
+pub const OVERLAY_MAX_BYTES: usize = 32 * 1024;
+
+const OVERLAY_FORMATS: &[&str] = &[
+    "text", "integer", "decimal", "date", "datetime", "boolean", "enum", "url", "email",
+    "phone",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OverlayMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OverlayBranding {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secondary_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_image_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DisplayOverlay {
+    #[serde(default = "default_overlay_language")]
+    pub default_language: String,
+    #[serde(default)]
+    pub meta: BTreeMap<String, OverlayMeta>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(default)]
+    pub formats: BTreeMap<String, String>,
+    #[serde(default)]
+    pub order: Vec<String>,
+    #[serde(default)]
+    pub hidden: Vec<String>,
+    #[serde(default)]
+    pub branding: OverlayBranding,
+}
+
+fn default_overlay_language() -> String {
+    "pt-BR".to_string()
+}
+
+// Registro guardado na wallet e publicado (idêntico) no ATTRIB.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayRecord {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub version: u32,
+    pub target_id: String,
+    pub target_type: String, // "schema" | "cred_def"
+    pub issuer_did: String,
+    pub overlay: DisplayOverlay,
+    pub updated_at: i64,
+}
+
+// "{did}:2:{name}:{ver}" -> ("schema", did); "{did}:3:CL:{seq}:{tag}" -> ("cred_def", did)
+fn overlay_target(target_id: &str) -> Result<(&'static str, String)> {
+    let parts: Vec<&str> = target_id.split(':').collect();
+    let kind = if parts.len() == 4 && parts[1] == "2" {
+        "schema"
+    } else if parts.len() >= 5 && parts[1] == "3" && parts[2] == "CL" {
+        "cred_def"
+    } else {
+        return Err(napi_err(
+            "OverlayTargetInvalid",
+            format!("Alvo deve ser schema_id ou cred_def_id do ledger: {}", target_id),
+        ));
+    };
+    if parts[0].trim().is_empty() {
+        return Err(napi_err(
+            "OverlayTargetInvalid",
+            format!("Alvo sem DID do emissor: {}", target_id),
+        ));
+    }
+    Ok((kind, parts[0].to_string()))
+}
+
+fn is_hex_color(c: &str) -> bool {
+    let h = match c.strip_prefix('#') {
+        Some(h) => h,
+        None => return false,
+    };
+    (h.len() == 6 || h.len() == 3) && h.chars().all(|x| x.is_ascii_hexdigit())
+}
+
+fn is_image_ref(u: &str) -> bool {
+    u.starts_with("https://") || u.starts_with("http://") || u.starts_with("data:image/")
+}
+
+// Atributos do schema alvo, quando o schema é conhecido localmente.
+async fn known_attr_names(
+    session: &mut aries_askar::Session,
+    target_type: &str,
+    target_id: &str,
+) -> Result<Option<Vec<String>>> {
+    let schema_id = if target_type == "schema" {
+        target_id.to_string()
+    } else {
+        match session
+            .fetch("cred_def", target_id, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+            .and_then(|e| {
+                e.tags
+                    .iter()
+                    .find(|t| t.name() == "schema_id")
+                    .map(|t| t.value().to_string())
+            }) {
+            Some(s) => s,
+            None => return Ok(None),
+        }
+    };
+
+    let entries = session
+        .fetch_all(
+            Some("schema"),
+            Some(TagFilter::is_eq("schema_id", &schema_id)),
+            Some(1),
+            None,
+            false,
+            false,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch_all schema: {}", e)))?;
+
+    if let Some(r) = entries
+        .first()
+        .and_then(|e| serde_json::from_slice::<SchemaRecord>(&e.value).ok())
+    {
+        return Ok(Some(r.final_attr_names));
+    }
+
+    // Registro gravado por create_and_register_schema (JSON do ledger, nome = schema_id)
+    Ok(session
+        .fetch("schema", &schema_id, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch schema: {}", e)))?
+        .and_then(|e| serde_json::from_slice::<serde_json::Value>(&e.value).ok())
+        .and_then(|v| {
+            v["attrNames"].as_array().map(|a| {
+                a.iter()
+                    .filter_map(|x| x.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+        }))
+}
+
+// Valida e normaliza: CONTROL_ATTRS entram sempre em "hidden".
+// Erro OverlayInvalid com todos os problemas encontrados.
+fn normalize_overlay(mut o: DisplayOverlay, known: Option<&[String]>) -> Result<DisplayOverlay> {
+    let mut problems: Vec<String> = Vec::new();
+
+    for lang in OVERLAY_REQUIRED_LANGUAGES {
+        if !o.labels.contains_key(lang) {
+            problems.push(format!("labels sem o idioma obrigatório {}", lang));
+        }
+    }
+    if !o.labels.contains_key(&o.default_language) {
+        problems.push(format!(
+            "defaultLanguage {} sem rótulos em labels",
+            o.default_language
+        ));
+    }
+
+    let mut referenced: Vec<(&str, &String)> = Vec::new();
+    for (lang, map) in &o.labels {
+        for (attr, label) in map {
+            if label.trim().is_empty() {
+                problems.push(format!("labels.{}.{} vazio", lang, attr));
+            }
+            referenced.push(("labels", attr));
+        }
+    }
+    for (attr, fmt) in &o.formats {
+        if !OVERLAY_FORMATS.contains(&fmt.as_str()) {
+            problems.push(format!(
+                "formats.{}: formato desconhecido {} (use {})",
+                attr,
+                fmt,
+                OVERLAY_FORMATS.join(", ")
+            ));
+        }
+        referenced.push(("formats", attr));
+    }
+    for (i, attr) in o.order.iter().enumerate() {
+        if o.order[..i].contains(attr) {
+            problems.push(format!("order: atributo duplicado {}", attr));
+        }
+        referenced.push(("order", attr));
+    }
+    for attr in o.hidden.iter().filter(|a| !is_reserved_control_attr(a)) {
+        referenced.push(("hidden", attr));
+    }
+
+    if let Some(attrs) = known {
+        for (field, attr) in referenced {
+            if !attrs.iter().any(|a| a == attr) {
+                problems.push(format!("{}: atributo {} não existe no schema", field, attr));
+            }
+        }
+    }
+
+    let b = &o.branding;
+    for (field, color) in [
+        ("primaryColor", &b.primary_color),
+        ("secondaryColor", &b.secondary_color),
+        ("backgroundColor", &b.background_color),
+    ] {
+        if let Some(c) = color {
+            if !is_hex_color(c) {
+                problems.push(format!("branding.{}: cor inválida {} (use #RRGGBB)", field, c));
+            }
+        }
+    }
+    for (field, url) in [
+        ("logoUrl", &b.logo_url),
+        ("backgroundImageUrl", &b.background_image_url),
+    ] {
+        if let Some(u) = url {
+            if !is_image_ref(u) {
+                problems.push(format!("branding.{}: use URL http(s) ou data:image/", field));
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(napi_err("OverlayInvalid", problems.join("; ")));
+    }
+
+    for c in CONTROL_ATTRS {
+        if !o.hidden.iter().any(|h| h.as_str() == c) {
+            o.hidden.push(c.to_string());
+        }
+    }
+    Ok(o)
+}
+
+fn overlay_record_tags(rec: &OverlayRecord, source: &str) -> Vec<EntryTag> {
+    vec![
+        EntryTag::Encrypted("target_type".to_string(), rec.target_type.clone()),
+        EntryTag::Encrypted("issuer_did".to_string(), rec.issuer_did.clone()),
+        EntryTag::Encrypted("source".to_string(), source.to_string()),
+        EntryTag::Encrypted("updated_at".to_string(), rec.updated_at.to_string()),
+    ]
+}
+
+async fn save_overlay_record(
+    session: &mut aries_askar::Session,
+    rec: &OverlayRecord,
+    source: &str,
+) -> Result<String> {
+    let json = serde_json::to_string(rec)
+        .map_err(|e| Error::from_reason(format!("Erro serializar overlay: {}", e)))?;
+
+    remove_if_exists(session, OVERLAY_CATEGORY, &rec.target_id).await?;
+    session
+        .insert(
+            OVERLAY_CATEGORY,
+            &rec.target_id,
+            json.as_bytes(),
+            Some(&overlay_record_tags(rec, source)),
+            None,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro salvar overlay: {}", e)))?;
+    Ok(json)
+}
+
+// Todos os overlays da wallet, indexados pelo alvo (schema_id / cred_def_id).
+pub(crate) async fn load_display_overlays(
+    session: &mut aries_askar::Session,
+) -> Result<HashMap<String, DisplayOverlay>> {
+    let entries = session
+        .fetch_all(Some(OVERLAY_CATEGORY), None, None, None, false, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch_all overlay: {}", e)))?;
+
+    Ok(entries
+        .iter()
+        .filter_map(|e| serde_json::from_slice::<OverlayRecord>(&e.value).ok())
+        .map(|r| (r.target_id, r.overlay))
+        .collect())
+}
+
+// Overlay do schema como base; o da cred def (mais específico) sobrescreve.
+fn merge_overlays(base: &DisplayOverlay, top: &DisplayOverlay) -> DisplayOverlay {
+    let mut out = base.clone();
+    out.default_language = top.default_language.clone();
+    for (lang, m) in &top.meta {
+        out.meta.insert(lang.clone(), m.clone());
+    }
+    for (lang, map) in &top.labels {
+        let dst = out.labels.entry(lang.clone()).or_default();
+        for (attr, label) in map {
+            dst.insert(attr.clone(), label.clone());
+        }
+    }
+    for (attr, fmt) in &top.formats {
+        out.formats.insert(attr.clone(), fmt.clone());
+    }
+    if !top.order.is_empty() {
+        out.order = top.order.clone();
+    }
+    for h in &top.hidden {
+        if !out.hidden.contains(h) {
+            out.hidden.push(h.clone());
+        }
+    }
+    let (tb, ob) = (&top.branding, &mut out.branding);
+    if tb.primary_color.is_some() {
+        ob.primary_color = tb.primary_color.clone();
+    }
+    if tb.secondary_color.is_some() {
+        ob.secondary_color = tb.secondary_color.clone();
+    }
+    if tb.background_color.is_some() {
+        ob.background_color = tb.background_color.clone();
+    }
+    if tb.logo_url.is_some() {
+        ob.logo_url = tb.logo_url.clone();
+    }
+    if tb.background_image_url.is_some() {
+        ob.background_image_url = tb.background_image_url.clone();
+    }
+    out
+}
+
+// Campo "display" de uma credencial (None se não houver overlay para ela).
+// Atributos: primeiro os de "order", depois os demais; ocultos vão ao final.
+pub(crate) fn credential_display(
+    overlays: &HashMap<String, DisplayOverlay>,
+    schema_id: &str,
+    cred_def_id: &str,
+    values_raw: &serde_json::Map<String, serde_json::Value>,
+) -> Option<serde_json::Value> {
+    let (o, sources) = match (overlays.get(schema_id), overlays.get(cred_def_id)) {
+        (Some(s), Some(c)) => (merge_overlays(s, c), vec!["schema", "cred_def"]),
+        (Some(s), None) => (s.clone(), vec!["schema"]),
+        (None, Some(c)) => (c.clone(), vec!["cred_def"]),
+        (None, None) => return None,
+    };
+
+    let mut names: Vec<&String> = o.order.iter().filter(|a| values_raw.contains_key(*a)).collect();
+    for k in values_raw.keys() {
+        if !names.contains(&k) {
+            names.push(k);
+        }
+    }
+    names.sort_by_key(|a| o.hidden.contains(*a));
+
+    let attributes: Vec<serde_json::Value> = names
+        .into_iter()
+        .map(|attr| {
+            let labels: serde_json::Map<String, serde_json::Value> = o
+                .labels
+                .iter()
+                .filter_map(|(lang, m)| m.get(attr).map(|l| (lang.clone(), l.clone().into())))
+                .collect();
+            let label = o
+                .labels
+                .get(&o.default_language)
+                .and_then(|m| m.get(attr))
+                .cloned()
+                .unwrap_or_else(|| attr.clone());
+            serde_json::json!({
+                "name": attr,
+                "label": label,
+                "labels": labels,
+                "format": o.formats.get(attr).map(|f| f.as_str()).unwrap_or("text"),
+                "value": values_raw.get(attr).cloned().unwrap_or(serde_json::Value::Null),
+                "hidden": o.hidden.contains(attr)
+            })
+        })
+        .collect();
+
+    Some(serde_json::json!({
+        "sources": sources,
+        "defaultLanguage": o.default_language,
+        "meta": o.meta,
+        "branding": o.branding,
+        "attributes": attributes
+    }))
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  OVERLAYS DE EXIBIÇÃO (OCA-style)
+    // =========================================================================
+
+    // Define (ou substitui) o overlay local de um schema_id / cred_def_id.
+    // overlay_json:
+    //   { "defaultLanguage"?: "pt-BR",
+    //     "meta":   { "pt-BR": { "name": "..", "description": ".." }, "en": {..} },
+    //     "labels": { "pt-BR": { "attr": "Rótulo" }, "en": { "attr": "Label" } },
+    //     "formats": { "attr": "date" }, "order": ["attr", ..], "hidden": ["attr"],
+    //     "branding": { "primaryColor": "#0B3D91", "logoUrl": "https://..." } }
+    #[napi]
+    pub async fn overlay_set(&self, target_id: String, overlay_json: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let (target_type, issuer_did) = overlay_target(&target_id)?;
+        let overlay: DisplayOverlay = serde_json::from_str(&overlay_json)
+            .map_err(|e| napi_err("OverlayInvalid", format!("JSON de overlay inválido: {}", e)))?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let known = known_attr_names(&mut session, target_type, &target_id).await?;
+        let overlay = normalize_overlay(overlay, known.as_deref())?;
+
+        let rec = OverlayRecord {
+            kind: OVERLAY_TYPE.to_string(),
+            version: 1,
+            target_id,
+            target_type: target_type.to_string(),
+            issuer_did,
+            overlay,
+            updated_at: now_ts(),
+        };
+        let json = save_overlay_record(&mut session, &rec, "local").await?;
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(json)
+    }
+
+    #[napi]
+    pub async fn overlay_get(&self, target_id: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entry = session
+            .fetch(OVERLAY_CATEGORY, &target_id, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch overlay: {}", e)))?
+            .ok_or_else(|| {
+                napi_err(
+                    "OverlayNotFound",
+                    format!("Nenhum overlay para {}", target_id),
+                )
+            })?;
+
+        Ok(String::from_utf8(entry.value.to_vec()).unwrap_or_default())
+    }
+
+    // Lista os overlays: [{ targetId, targetType, issuerDid, source, updatedAt }]
+    #[napi]
+    pub async fn overlay_list(&self) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entries = session
+            .fetch_all(Some(OVERLAY_CATEGORY), None, None, None, false, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch_all overlay: {}", e)))?;
+
+        let list: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|e| {
+                let tag = |n: &str| {
+                    e.tags
+                        .iter()
+                        .find(|t| t.name() == n)
+                        .map(|t| t.value().to_string())
+                        .unwrap_or_default()
+                };
+                serde_json::json!({
+                    "targetId": e.name,
+                    "targetType": tag("target_type"),
+                    "issuerDid": tag("issuer_did"),
+                    "source": tag("source"),
+                    "updatedAt": tag("updated_at").parse::<i64>().unwrap_or(0)
+                })
+            })
+            .collect();
+
+        Ok(serde_json::Value::Array(list).to_string())
+    }
+
+    #[napi]
+    pub async fn overlay_delete(&self, target_id: String) -> Result<bool> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        // Só "não encontrado" vira false; demais erros do banco sobem.
+        let removed = remove_if_exists(&mut session, OVERLAY_CATEGORY, &target_id).await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+        Ok(removed)
+    }
+
+    // Emissor: publica o overlay local como ATTRIB no DID do emissor do alvo
+    // (chave "ssi_overlay:<targetId>"). Requer connect_network e o DID na wallet.
+    #[napi]
+    pub async fn overlay_publish(&self, target_id: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let pool = self.pool.clone().ok_or_else(|| {
+            Error::from_reason("Não conectado à rede. Execute connect_network antes.")
+        })?;
+
+        let (_, issuer_did) = overlay_target(&target_id)?;
+
+        let rec: OverlayRecord = {
+            let mut session = store
+                .session(None)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+            let has_did = session
+                .fetch("did", &issuer_did, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch DID: {}", e)))?
+                .is_some();
+            if !has_did {
+                return Err(napi_err(
+                    "OverlayIssuerNotInWallet",
+                    format!("DID do emissor {} não está nesta wallet", issuer_did),
+                ));
+            }
+
+            let entry = session
+                .fetch(OVERLAY_CATEGORY, &target_id, false)
+                .await
+                .map_err(|e| Error::from_reason(format!("Erro fetch overlay: {}", e)))?
+                .ok_or_else(|| {
+                    napi_err(
+                        "OverlayNotFound",
+                        format!("Nenhum overlay para {}", target_id),
+                    )
+                })?;
+            serde_json::from_slice(&entry.value)
+                .map_err(|e| Error::from_reason(format!("Overlay corrompido: {}", e)))?
+        };
+
+        let payload = serde_json::to_value(&rec)
+            .map_err(|e| Error::from_reason(format!("Erro serializar overlay: {}", e)))?;
+        let bytes = payload.to_string().len();
+        if bytes > OVERLAY_MAX_BYTES {
+            return Err(napi_err(
+                "OverlayTooLarge",
+                format!(
+                    "Overlay com {} bytes excede {} (use logoUrl em vez de imagem embutida)",
+                    bytes, OVERLAY_MAX_BYTES
+                ),
+            ));
+        }
+
+        let attrib_key = format!("{}{}", OVERLAY_ATTRIB_PREFIX, target_id);
+        let mut raw = serde_json::Map::new();
+        raw.insert(attrib_key.clone(), payload);
+        let raw = serde_json::Value::Object(raw);
+        let response = write_attrib_with_pool(&store, &pool, &issuer_did, &raw).await?;
+
+        let resp_json: serde_json::Value = serde_json::from_str(&response).unwrap_or_default();
+        if resp_json["op"].as_str() != Some("REPLY") {
+            let reason = resp_json["reason"].as_str().unwrap_or("resposta inesperada");
+            return Err(napi_err("OverlayPublishFailed", reason.to_string()));
+        }
+
+        Ok(serde_json::json!({
+            "ok": true,
+            "targetId": target_id,
+            "issuerDid": issuer_did,
+            "attribKey": attrib_key,
+            "bytes": bytes,
+            "seqNo": resp_json["result"]["txnMetadata"]["seqNo"]
+        })
+        .to_string())
+    }
+
+    // Holder: busca no ledger o overlay publicado pelo emissor do alvo e o
+    // guarda localmente (source "ledger"), substituindo o anterior.
+    #[napi]
+    pub async fn overlay_fetch_from_ledger(&self, target_id: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let pool = self.pool.clone().ok_or_else(|| {
+            Error::from_reason("Não conectado à rede. Execute connect_network antes.")
+        })?;
+
+        let (target_type, issuer_did) = overlay_target(&target_id)?;
+        let attrib_key = format!("{}{}", OVERLAY_ATTRIB_PREFIX, target_id);
+
+        let raw = read_attrib_with_pool(&pool, &issuer_did, &attrib_key)
+            .await?
+            .ok_or_else(|| {
+                napi_err(
+                    "OverlayNotOnLedger",
+                    format!("Emissor {} não publicou overlay para {}", issuer_did, target_id),
+                )
+            })?;
+        let raw = match raw.as_str() {
+            Some(s) => serde_json::from_str(s).unwrap_or(serde_json::Value::Null),
+            None => raw,
+        };
+
+        let mut rec: OverlayRecord = serde_json::from_value(raw).map_err(|e| {
+            napi_err("OverlayInvalid", format!("Overlay do ledger inválido: {}", e))
+        })?;
+        if rec.kind != OVERLAY_TYPE || rec.target_id != target_id {
+            return Err(napi_err(
+                "OverlayInvalid",
+                format!("Overlay do ledger não corresponde a {}", target_id),
+            ));
+        }
+        // Emissor/tipo sempre derivados do alvo, nunca do payload.
+        rec.target_type = target_type.to_string();
+        rec.issuer_did = issuer_did;
+        rec.overlay = normalize_overlay(rec.overlay, None)?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let json = save_overlay_record(&mut session, &rec, "ledger").await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(json)
+    }
+}
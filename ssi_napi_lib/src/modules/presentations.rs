@@ -1,8 +1,9 @@
 // src/modules/presentations.rs
-// use crate::modules::common::napi_err;
+use crate::modules::common::napi_err;
+use crate::modules::encoding::check_predicate_attr_encoding;
 use crate::IndyAgent;
-// Importamos o cache compartilhado do módulo de credenciais
-use crate::modules::credentials::LINK_SECRET_CACHE;
+// Link secrets (cache por wallet) vêm do módulo de credenciais
+use crate::modules::credentials::{is_superseded, load_link_secret};
 
 use napi::{Env, Error, JsObject, Result};
 use napi_derive::napi;
@@ -235,12 +236,13 @@ impl IndyAgent {
         use anoncreds::data_types::schema::{Schema, SchemaId};
 
         // Tipos de Alto Nível
-        use anoncreds::types::{LinkSecret, PresentCredentials, PresentationRequest};
+        use anoncreds::types::{PresentCredentials, PresentationRequest};
 
         let store = match &self.store {
             Some(s) => s.clone(),
             None => return Err(Error::from_reason("Wallet fechada!")),
         };
+        let cache = self.link_secrets.clone();
 
         env.execute_tokio_future(
             async move {
@@ -249,30 +251,8 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-                // 1. LINK SECRET
-                let link_secret = {
-                    let cached_ls = { LINK_SECRET_CACHE.lock().unwrap().clone() };
-                    if let Some(ls) = cached_ls {
-                        ls
-                    } else {
-                        let entry = session
-                            .fetch("link_secret", "default", false)
-                            .await
-                            .map_err(|e| napi::Error::from_reason(format!("Erro DB LS: {}", e)))?
-                            .ok_or_else(|| {
-                                napi::Error::from_reason("Link Secret 'default' não encontrado")
-                            })?;
-                        let seed_str = String::from_utf8(entry.value.to_vec()).unwrap_or_default();
-                        let ls_obj = LinkSecret::try_from(seed_str.as_str()).map_err(|e| {
-                            napi::Error::from_reason(format!("Erro LS math: {:?}", e))
-                        })?;
-                        let arc_ls = std::sync::Arc::new(ls_obj);
-                        {
-                            *LINK_SECRET_CACHE.lock().unwrap() = Some(arc_ls.clone());
-                        }
-                        arc_ls
-                    }
-                };
+                // 1. LINK SECRET (default da wallet)
+                let (_, link_secret) = load_link_secret(&mut session, &cache, None).await?;
 
                 // 2. PARSE REQUEST
                 let request: PresentationRequest = serde_json::from_str(&presentation_request_json)
@@ -500,6 +480,12 @@ impl IndyAgent {
                         .ok_or_else(|| {
                             napi::Error::from_reason(format!("Cred {} nao achada", cred_id))
                         })?;
+                    if is_superseded(&cred_entry.tags) {
+                        return Err(napi_err(
+                            "CredentialSuperseded",
+                            format!("Cred {} foi substituída por uma renovação", cred_id),
+                        ));
+                    }
 
                     let cred_str = String::from_utf8(cred_entry.value.to_vec()).unwrap_or_default();
                     let cred_json: serde_json::Value = serde_json::from_str(&cred_str).unwrap();
@@ -513,7 +499,23 @@ impl IndyAgent {
                     credential_keeper.insert(cred_id.clone(), credential);
                 }
 
-                // 7. MONTAR A PROVA (Usando referências do Keeper)
+                // 7. PREDICADOS SÓ SOBRE ATRIBUTOS CODIFICADOS COMO INTEIRO
+                let pres_req_val: serde_json::Value =
+                    serde_json::from_str(&presentation_request_json).unwrap_or_default();
+                for (cred_id, actions) in &cred_actions {
+                    if let Some(cred) = credential_keeper.get(cred_id) {
+                        for a in actions.iter().filter(|a| a.is_predicate) {
+                            check_predicate_attr_encoding(
+                                &pres_req_val,
+                                &a.referent,
+                                cred_id,
+                                cred,
+                            )?;
+                        }
+                    }
+                }
+
+                // 8. MONTAR A PROVA (Usando referências do Keeper)
                 let mut present_credentials = PresentCredentials::default();
 
                 for (cred_id, actions) in cred_actions {
@@ -535,7 +537,7 @@ impl IndyAgent {
                     }
                 }
 
-                // 8. GERAR
+                // 9. GERAR
                 let presentation = anoncreds::prover::create_presentation(
                     &request,
                     present_credentials,
@@ -724,6 +726,7 @@ impl IndyAgent {
     /// - selection_json: formato UI-friendly (RequestedCredsSpecV1)
     /// - schemas_json: map schemaId -> payload ledger/local
     /// - cred_defs_json: map credDefId -> payload ledger/local
+    /// - link_secret_id: opcional; sem ele usa o link secret default da wallet
     #[napi]
     pub fn create_presentation_v2(
         &self,
@@ -732,18 +735,20 @@ impl IndyAgent {
         selection_json: String,
         schemas_json: String,
         cred_defs_json: String,
+        link_secret_id: Option<String>,
     ) -> Result<JsObject> {
         // Imports (iguais ao seu create_presentation atual)
         use anoncreds::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
         use anoncreds::data_types::credential::Credential;
         use anoncreds::data_types::schema::{Schema, SchemaId};
-        use anoncreds::types::{LinkSecret, PresentCredentials, PresentationRequest};
+        use anoncreds::types::{PresentCredentials, PresentationRequest};
         use std::collections::HashMap;
 
         let store = match &self.store {
             Some(s) => s.clone(),
             None => return Err(Error::from_reason("Wallet fechada!")),
         };
+        let cache = self.link_secrets.clone();
 
         env.execute_tokio_future(
             async move {
@@ -752,28 +757,9 @@ impl IndyAgent {
                     .await
                     .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
 
-                // 1) Link secret (igual você já faz)
-                let link_secret = {
-                    let cached_ls = { LINK_SECRET_CACHE.lock().unwrap().clone() };
-                    if let Some(ls) = cached_ls {
-                        ls
-                    } else {
-                        let entry = session
-                            .fetch("link_secret", "default", false)
-                            .await
-                            .map_err(|e| napi::Error::from_reason(format!("Erro DB LS: {}", e)))?
-                            .ok_or_else(|| {
-                                napi::Error::from_reason("Link Secret 'default' não encontrado")
-                            })?;
-                        let seed_str = String::from_utf8(entry.value.to_vec()).unwrap_or_default();
-                        let ls_obj = LinkSecret::try_from(seed_str.as_str()).map_err(|e| {
-                            napi::Error::from_reason(format!("Erro LS math: {:?}", e))
-                        })?;
-                        let arc_ls = std::sync::Arc::new(ls_obj);
-                        *LINK_SECRET_CACHE.lock().unwrap() = Some(arc_ls.clone());
-                        arc_ls
-                    }
-                };
+                // 1) Link secret: o informado ou o default da wallet
+                let (_, link_secret) =
+                    load_link_secret(&mut session, &cache, link_secret_id.as_deref()).await?;
 
                 // 2) Parse PresentationRequest
                 let request: PresentationRequest = serde_json::from_str(&presentation_request_json)
@@ -1041,6 +1027,13 @@ impl IndyAgent {
                         .ok_or_else(|| {
                             napi::Error::from_reason(format!("Cred {} nao achada", cred_id))
                         })?;
+                    // Substituída por renovação: apresentar a credencial nova
+                    if is_superseded(&cred_entry.tags) {
+                        return Err(napi_err(
+                            "CredentialSuperseded",
+                            format!("Cred {} foi substituída por uma renovação", cred_id),
+                        ));
+                    }
 
                     let cred_str = String::from_utf8(cred_entry.value.to_vec()).unwrap_or_default();
                     let cred_json: serde_json::Value =
@@ -1055,6 +1048,20 @@ impl IndyAgent {
                     credential_keeper.insert(cred_id.clone(), credential);
                 }
 
+                // Predicados só sobre atributos codificados como inteiro
+                for (cred_id, actions) in &cred_actions {
+                    if let Some(cred) = credential_keeper.get(cred_id) {
+                        for a in actions.iter().filter(|a| a.is_predicate) {
+                            check_predicate_attr_encoding(
+                                &pres_req_json_val,
+                                &a.referent,
+                                cred_id,
+                                cred,
+                            )?;
+                        }
+                    }
+                }
+
                 // Montar PresentCredentials
                 let mut present_credentials = PresentCredentials::default();
                 for (cred_id, actions) in cred_actions {
@@ -0,0 +1,272 @@
+// src/modules/schema_templates.rs
+//
+// Catálogo embutido de schemas para Mobilidade Aérea Urbana (UAM).
+// Todos os emissores do consórcio instanciam os mesmos nomes/versões/atributos,
+// garantindo interoperabilidade entre credenciais de emissores diferentes.
+// Mudou um template? Crie uma NOVA entrada com version incrementada; nunca
+// altere uma entrada publicada (schemas no ledger são imutáveis).
+
+use crate::modules::common::{
+    build_final_attr_names, make_schema_local_id, napi_err, now_ts, SchemaRecord,
+};
+use crate::modules::schemas::schema_record_tags;
+use crate::IndyAgent;
+use aries_askar::entry::TagFilter;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+pub const SCHEMA_TEMPLATE_CATALOG_VERSION: &str = "2026.1";
+
+pub struct SchemaTemplate {
+    pub template_id: &'static str,
+    pub name: &'static str,
+    pub version: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub attr_names: &'static [&'static str],
+    pub revocable: bool,
+}
+
+pub static SCHEMA_TEMPLATES: &[SchemaTemplate] = &[
+    SchemaTemplate {
+        template_id: "uam.pilot_license.v1",
+        name: "uam_pilot_license",
+        version: "1.0",
+        title: "Licença de Piloto (eVTOL / powered-lift)",
+        description: "Licença de piloto com categoria, habilitações de tipo e validade médica.",
+        attr_names: &[
+            "license_number",
+            "holder_name",
+            "holder_birth_date",
+            "nationality",
+            "license_category",
+            "type_ratings",
+            "instrument_rating",
+            "medical_class",
+            "medical_expiry_date",
+            "issuing_authority",
+            "issue_date",
+            "expiry_date",
+        ],
+        revocable: true,
+    },
+    SchemaTemplate {
+        template_id: "uam.evtol_airworthiness.v1",
+        name: "uam_evtol_airworthiness_certificate",
+        version: "1.0",
+        title: "Certificado de Aeronavegabilidade eVTOL",
+        description: "Certificado de aeronavegabilidade de uma aeronave eVTOL específica.",
+        attr_names: &[
+            "certificate_number",
+            "registration_mark",
+            "manufacturer",
+            "model",
+            "serial_number",
+            "type_certificate_ref",
+            "airworthiness_category",
+            "max_takeoff_mass_kg",
+            "operating_limitations",
+            "issuing_authority",
+            "issue_date",
+            "expiry_date",
+        ],
+        revocable: true,
+    },
+    SchemaTemplate {
+        template_id: "uam.operator_certificate.v1",
+        name: "uam_operator_certificate",
+        version: "1.0",
+        title: "Certificado de Operador UAM",
+        description: "Certificado do operador aéreo com tipos de operação e área autorizada.",
+        attr_names: &[
+            "certificate_number",
+            "operator_name",
+            "operator_tax_id",
+            "operator_did",
+            "operation_types",
+            "authorized_area",
+            "fleet_size",
+            "accountable_manager",
+            "issuing_authority",
+            "issue_date",
+            "expiry_date",
+        ],
+        revocable: true,
+    },
+    SchemaTemplate {
+        template_id: "uam.vertiport_slot.v1",
+        name: "uam_vertiport_slot_authorization",
+        version: "1.0",
+        title: "Autorização de Slot em Vertiporto",
+        description: "Autorização de pouso/decolagem em janela de horário de um vertiporto.",
+        attr_names: &[
+            "authorization_id",
+            "vertiport_code",
+            "operator_did",
+            "registration_mark",
+            "slot_start",
+            "slot_end",
+            "pad_id",
+            "operation_type",
+            "issuing_authority",
+            "issue_date",
+        ],
+        revocable: false,
+    },
+    SchemaTemplate {
+        template_id: "uam.maintenance_release.v1",
+        name: "uam_maintenance_release",
+        version: "1.0",
+        title: "Certificado de Liberação de Manutenção",
+        description: "Liberação para serviço após manutenção (CRS) de uma aeronave.",
+        attr_names: &[
+            "release_number",
+            "registration_mark",
+            "work_order_ref",
+            "work_performed",
+            "maintenance_org_approval",
+            "certifying_staff_name",
+            "certifying_staff_license",
+            "release_date",
+            "next_inspection_due",
+        ],
+        revocable: false,
+    },
+];
+
+pub fn find_schema_template(template_id: &str) -> Option<&'static SchemaTemplate> {
+    SCHEMA_TEMPLATES.iter().find(|t| t.template_id == template_id)
+}
+
+fn template_json(t: &SchemaTemplate) -> serde_json::Value {
+    serde_json::json!({
+        "templateId": t.template_id,
+        "name": t.name,
+        "version": t.version,
+        "title": t.title,
+        "description": t.description,
+        "attrNames": t.attr_names,
+        "revocable": t.revocable,
+        "catalogVersion": SCHEMA_TEMPLATE_CATALOG_VERSION
+    })
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  CATÁLOGO DE TEMPLATES UAM
+    // =========================================================================
+    #[napi]
+    pub fn schema_templates_list(&self) -> Result<String> {
+        let list: Vec<serde_json::Value> = SCHEMA_TEMPLATES.iter().map(template_json).collect();
+        Ok(serde_json::json!({
+            "catalogVersion": SCHEMA_TEMPLATE_CATALOG_VERSION,
+            "templates": list
+        })
+        .to_string())
+    }
+
+    // Cria um schema local a partir do template (env default "template").
+    // Idempotente: se já existir o mesmo templateId no mesmo env, devolve o existente.
+    #[napi]
+    pub async fn schema_instantiate_template(
+        &self,
+        template_id: String,
+        env_label: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let tpl = find_schema_template(&template_id).ok_or_else(|| {
+            napi_err(
+                "SchemaTemplateNotFound",
+                format!("Template desconhecido: {}", template_id),
+            )
+        })?;
+        let envv = env_label
+            .filter(|e| !e.trim().is_empty())
+            .unwrap_or_else(|| "template".to_string());
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let existing = session
+            .fetch_all(
+                Some("schema"),
+                Some(TagFilter::all_of(vec![
+                    TagFilter::is_eq("template_id", tpl.template_id),
+                    TagFilter::is_eq("env", &envv),
+                ])),
+                Some(1),
+                None,
+                false,
+                false,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch_all schema: {}", e)))?;
+
+        if let Some(e) = existing.first() {
+            let json = String::from_utf8(e.value.to_vec()).unwrap_or_default();
+            return Ok(serde_json::json!({
+                "ok": true,
+                "created": false,
+                "templateId": tpl.template_id,
+                "json": json
+            })
+            .to_string());
+        }
+
+        let attr_names: Vec<String> = tpl.attr_names.iter().map(|a| a.to_string()).collect();
+        let final_attrs = build_final_attr_names(attr_names.clone(), tpl.revocable)?;
+
+        let now = now_ts();
+        let rec = SchemaRecord {
+            id_local: make_schema_local_id(),
+            name: tpl.name.to_string(),
+            version: tpl.version.to_string(),
+            attr_names,
+            revocable: tpl.revocable,
+            final_attr_names: final_attrs,
+            on_ledger: false,
+            schema_id: None,
+            issuer_did: None,
+            env: envv,
+            created_at: now,
+            updated_at: now,
+            supersedes: None,
+            template_id: Some(tpl.template_id.to_string()),
+            attr_types: None,
+        };
+
+        let json = serde_json::to_string(&rec)
+            .map_err(|e| Error::from_reason(format!("Erro serializar schema local: {}", e)))?;
+
+        session
+            .insert(
+                "schema",
+                &rec.id_local,
+                json.as_bytes(),
+                Some(&schema_record_tags(&rec)),
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar schema local: {}", e)))?;
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(serde_json::json!({
+            "ok": true,
+            "created": true,
+            "templateId": tpl.template_id,
+            "json": json
+        })
+        .to_string())
+    }
+}
@@ -3,9 +3,11 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::modules::common::{
-    build_final_attr_names, make_schema_local_id, now_ts, SchemaRecord, CONFIG_CATEGORY,
-    KEY_DEFAULT_SCHEMA_ISSUER_DID,
+    build_final_attr_names, bump_schema_version, check_attr_type_def, compare_schema_versions,
+    is_reserved_control_attr, make_schema_local_id, napi_err, now_ts, remove_if_exists, AttrType,
+    SchemaRecord, CONFIG_CATEGORY, CONTROL_ATTRS, KEY_DEFAULT_SCHEMA_ISSUER_DID,
 };
+use std::collections::BTreeMap;
 use crate::IndyAgent;
 use aries_askar::entry::{EntryTag, TagFilter};
 use indy_data_types::did::DidValue;
@@ -20,7 +22,281 @@ use napi_derive::napi;
 // IMPORTANTE: Adicione LedgerSchemaId aqui
 use indy_data_types::SchemaId as LedgerSchemaId;
 // IMPORTANTE: Importe a função do common
-use crate::modules::common::{send_request_async};
+use crate::modules::common::{record_ledger_txn, send_request_async};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaNextVersionOpts {
+    pub add_attrs: Option<Vec<String>>,
+    pub remove_attrs: Option<Vec<String>>,
+    pub bump: Option<String>, // "minor" (default) | "major"
+}
+
+// Overlay de tipos do schema usado por uma cred def (via tag schema_id da
+// cred def). Busca por nome (tag) e confere o schema_id no valor, o que
+// também cobre registros antigos sem a tag schema_id.
+pub(crate) async fn load_attr_types_for_cred_def(
+    session: &mut aries_askar::Session,
+    cred_def_id: &str,
+) -> Result<Option<BTreeMap<String, AttrType>>> {
+    let schema_id = match session
+        .fetch("cred_def", cred_def_id, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch cred_def: {}", e)))?
+        .and_then(|e| {
+            e.tags
+                .iter()
+                .find(|t| t.name() == "schema_id")
+                .map(|t| t.value().to_string())
+        }) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+
+    let parts: Vec<&str> = schema_id.split(':').collect();
+    if parts.len() != 4 {
+        return Ok(None);
+    }
+
+    let entries = session
+        .fetch_all(
+            Some("schema"),
+            Some(TagFilter::is_eq("name", parts[2])),
+            None,
+            None,
+            false,
+            false,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch_all schema: {}", e)))?;
+
+    Ok(entries
+        .iter()
+        .filter_map(|e| serde_json::from_slice::<SchemaRecord>(&e.value).ok())
+        .find(|r| r.schema_id.as_deref() == Some(schema_id.as_str()))
+        .and_then(|r| r.attr_types))
+}
+
+pub(crate) fn schema_record_tags(rec: &SchemaRecord) -> Vec<EntryTag> {
+    let mut tags = vec![
+        EntryTag::Encrypted("on_ledger".to_string(), rec.on_ledger.to_string()),
+        EntryTag::Encrypted("env".to_string(), rec.env.clone()),
+        EntryTag::Encrypted("name".to_string(), rec.name.clone()),
+        EntryTag::Encrypted("version".to_string(), rec.version.clone()),
+        EntryTag::Encrypted("revocable".to_string(), rec.revocable.to_string()),
+    ];
+    if let Some(d) = &rec.issuer_did {
+        tags.push(EntryTag::Encrypted("issuer_did".to_string(), d.clone()));
+    }
+    if let Some(sid) = &rec.schema_id {
+        tags.push(EntryTag::Encrypted("schema_id".to_string(), sid.clone()));
+    }
+    if let Some(prev) = &rec.supersedes {
+        tags.push(EntryTag::Encrypted("supersedes".to_string(), prev.clone()));
+    }
+    if let Some(t) = &rec.template_id {
+        tags.push(EntryTag::Encrypted("template_id".to_string(), t.clone()));
+    }
+    tags
+}
+
+// Núcleo de create_and_register_schema (pool já conectado). Reutilizado
+// pela publicação em lote (publishIssuerManifest).
+pub(crate) async fn register_schema_with_pool(
+    store: &aries_askar::Store,
+    pool: &indy_vdr::pool::PoolRunner,
+    issuer_did: String,
+    name: String,
+    version: String,
+    attr_names: Vec<String>,
+) -> Result<String> {
+    let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
+
+    let mut session = store
+        .session(None)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+    // 3. Preparar Chaves
+    let did_entry = session
+        .fetch("did", &issuer_did, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro DB: {}", e)))?
+        .ok_or_else(|| napi::Error::from_reason("DID não achado"))?;
+
+    let did_json: serde_json::Value = serde_json::from_slice(&did_entry.value)
+        .map_err(|e| napi::Error::from_reason(format!("DID JSON inválido: {}", e)))?;
+
+    let verkey_ref = did_json["verkey"]
+        .as_str()
+        .ok_or_else(|| napi::Error::from_reason("Campo 'verkey' ausente"))?;
+
+    let key_entry = session
+        .fetch_key(verkey_ref, false)
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro fetch key: {}", e)))?
+        .ok_or_else(|| napi::Error::from_reason("Chave privada não achada"))?;
+
+    let my_key = key_entry
+        .load_local_key()
+        .map_err(|e| napi::Error::from_reason(format!("Erro load_local_key: {}", e)))?;
+
+    // 4. Build Request (CORREÇÃO DE TIPO)
+    let did_obj = DidValue(issuer_did.clone());
+    let schema_id = format!("{}:2:{}:{}", issuer_did, name, version);
+
+    let schema_json = serde_json::json!({
+        "id": schema_id,
+        "name": name,
+        "version": version,
+        "attrNames": attr_names,
+        "ver": "1.0",
+        "seqNo": null
+    });
+
+    // A. Convertemos JSON -> Struct SchemaV1
+    let schema_struct: indy_vdr::ledger::requests::schema::SchemaV1 =
+        serde_json::from_value(schema_json).map_err(|e| {
+            napi::Error::from_reason(format!("Erro parse SchemaV1: {}", e))
+        })?;
+
+    // B. Embrulhamos a Struct no Enum Schema::SchemaV1
+    // O compilador sugeriu explicitamente este caminho:
+    let schema_enum =
+        indy_vdr::ledger::requests::schema::Schema::SchemaV1(schema_struct);
+
+    // C. Passamos o Enum para o builder
+    let mut req = rb
+        .build_schema_request(&did_obj, schema_enum)
+        .map_err(|e| napi::Error::from_reason(format!("Build Req Err: {}", e)))?;
+
+    // 5. TAA
+    let taa_req = rb
+        .build_get_txn_author_agreement_request(None, None)
+        .map_err(|e| napi::Error::from_reason(format!("Erro build TAA req: {}", e)))?;
+
+    let taa_resp = send_request_async(pool, taa_req).await?;
+    let taa_val: serde_json::Value = serde_json::from_str(&taa_resp).map_err(|e| {
+        napi::Error::from_reason(format!("Erro parse TAA response: {}", e))
+    })?;
+
+    if let Some(result) = taa_val.get("result") {
+        if let Some(data) = result.get("data") {
+            if !data.is_null() {
+                let text = data.get("text").and_then(|t| t.as_str());
+                let version = data.get("version").and_then(|v| v.as_str());
+                let digest = data.get("digest").and_then(|d| d.as_str());
+
+                if let (Some(t), Some(v)) = (text, version) {
+                    let ts = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs();
+                    let ts_midnight_utc = (ts / 86400) * 86400;
+
+                    let taa_acceptance = rb
+                        .prepare_txn_author_agreement_acceptance_data(
+                            Some(t),
+                            Some(v),
+                            digest,
+                            "wallet_agreement",
+                            ts_midnight_utc,
+                        )
+                        .map_err(|e| {
+                            napi::Error::from_reason(format!("Erro prepare TAA: {}", e))
+                        })?;
+
+                    req.set_txn_author_agreement_acceptance(&taa_acceptance)
+                        .map_err(|e| {
+                            napi::Error::from_reason(format!("Erro set TAA: {}", e))
+                        })?;
+                }
+            }
+        }
+    }
+
+    // 6. Assinar
+    let signature_input = req
+        .get_signature_input()
+        .map_err(|e| napi::Error::from_reason(format!("Sig Input: {}", e)))?;
+    let signature = my_key
+        .sign_message(signature_input.as_bytes(), None)
+        .map_err(|e| napi::Error::from_reason(format!("Sign: {}", e)))?;
+    req.set_signature(&signature)
+        .map_err(|e| napi::Error::from_reason(format!("Set Sig: {}", e)))?;
+
+    // 7. Enviar
+    let response = send_request_async(pool, req).await?;
+    record_ledger_txn(store, &issuer_did, None, "SCHEMA", &response).await;
+
+    // 8. Verificar
+    let resp_json: serde_json::Value =
+        serde_json::from_str(&response).map_err(|e| {
+            napi::Error::from_reason(format!("Resposta JSON inválida: {}", e))
+        })?;
+    if let Some(op) = resp_json.get("op") {
+        if op == "REJECT" || op == "REQNACK" {
+            let reason = resp_json
+                .get("reason")
+                .and_then(|r| r.as_str())
+                .unwrap_or("Sem detalhes");
+            return Err(napi::Error::from_reason(format!(
+                "Ledger REJECT: {}",
+                reason
+            )));
+        }
+    }
+
+    // 9. Salvar
+    let seq_no = resp_json["result"]["seqNo"].as_u64();
+
+    let schema_json_str = serde_json::json!({
+        "id": schema_id,
+        "name": name,
+        "version": version,
+        "ver": "1.0",
+        "attrNames": attr_names,
+        "seqNo": seq_no
+    })
+    .to_string();
+
+    // session
+    //     .insert("schema", &schema_id, schema_json_str.as_bytes(), None, None)
+    //     .await
+    //     .map_err(|e| napi::Error::from_reason(format!("Erro salvar schema: {}", e)))?;
+    // session
+    //     .commit()
+    //     .await
+    //     .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
+
+    let tags = vec![
+        EntryTag::Encrypted("on_ledger".to_string(), "true".to_string()),
+        EntryTag::Encrypted("env".to_string(), "prod".to_string()),
+        EntryTag::Encrypted("name".to_string(), name.clone()),
+        EntryTag::Encrypted("version".to_string(), version.clone()),
+        EntryTag::Encrypted("issuer_did".to_string(), issuer_did.clone()),
+        EntryTag::Encrypted("revocable".to_string(), "false".to_string()), // por enquanto
+    ];
+
+    session
+        .insert(
+            "schema",
+            &schema_id,
+            schema_json_str.as_bytes(),
+            Some(&tags),
+            None,
+        )
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro salvar schema: {}", e)))?;
+
+    session
+        .commit()
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
+
+    Ok(schema_id)
+}
 
 #[napi]
 impl IndyAgent {
@@ -136,190 +412,9 @@ impl IndyAgent {
                 let pool = PoolBuilder::new(PoolConfig::default(), transactions)
                     .into_runner(None)
                     .map_err(|e| napi::Error::from_reason(format!("Erro Pool: {}", e)))?;
-                let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
 
-                let mut session = store
-                    .session(None)
+                register_schema_with_pool(&store, &pool, issuer_did, name, version, attr_names)
                     .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
-
-                // 3. Preparar Chaves
-                let did_entry = session
-                    .fetch("did", &issuer_did, false)
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro DB: {}", e)))?
-                    .ok_or_else(|| napi::Error::from_reason("DID não achado"))?;
-
-                let did_json: serde_json::Value = serde_json::from_slice(&did_entry.value)
-                    .map_err(|e| napi::Error::from_reason(format!("DID JSON inválido: {}", e)))?;
-
-                let verkey_ref = did_json["verkey"]
-                    .as_str()
-                    .ok_or_else(|| napi::Error::from_reason("Campo 'verkey' ausente"))?;
-
-                let key_entry = session
-                    .fetch_key(verkey_ref, false)
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro fetch key: {}", e)))?
-                    .ok_or_else(|| napi::Error::from_reason("Chave privada não achada"))?;
-
-                let my_key = key_entry
-                    .load_local_key()
-                    .map_err(|e| napi::Error::from_reason(format!("Erro load_local_key: {}", e)))?;
-
-                // 4. Build Request (CORREÇÃO DE TIPO)
-                let did_obj = DidValue(issuer_did.clone());
-                let schema_id = format!("{}:2:{}:{}", issuer_did, name, version);
-
-                let schema_json = serde_json::json!({
-                    "id": schema_id,
-                    "name": name,
-                    "version": version,
-                    "attrNames": attr_names,
-                    "ver": "1.0",
-                    "seqNo": null
-                });
-
-                // A. Convertemos JSON -> Struct SchemaV1
-                let schema_struct: indy_vdr::ledger::requests::schema::SchemaV1 =
-                    serde_json::from_value(schema_json).map_err(|e| {
-                        napi::Error::from_reason(format!("Erro parse SchemaV1: {}", e))
-                    })?;
-
-                // B. Embrulhamos a Struct no Enum Schema::SchemaV1
-                // O compilador sugeriu explicitamente este caminho:
-                let schema_enum =
-                    indy_vdr::ledger::requests::schema::Schema::SchemaV1(schema_struct);
-
-                // C. Passamos o Enum para o builder
-                let mut req = rb
-                    .build_schema_request(&did_obj, schema_enum)
-                    .map_err(|e| napi::Error::from_reason(format!("Build Req Err: {}", e)))?;
-
-                // 5. TAA
-                let taa_req = rb
-                    .build_get_txn_author_agreement_request(None, None)
-                    .map_err(|e| napi::Error::from_reason(format!("Erro build TAA req: {}", e)))?;
-
-                let taa_resp = send_request_async(&pool, taa_req).await?;
-                let taa_val: serde_json::Value = serde_json::from_str(&taa_resp).map_err(|e| {
-                    napi::Error::from_reason(format!("Erro parse TAA response: {}", e))
-                })?;
-
-                if let Some(result) = taa_val.get("result") {
-                    if let Some(data) = result.get("data") {
-                        if !data.is_null() {
-                            let text = data.get("text").and_then(|t| t.as_str());
-                            let version = data.get("version").and_then(|v| v.as_str());
-                            let digest = data.get("digest").and_then(|d| d.as_str());
-
-                            if let (Some(t), Some(v)) = (text, version) {
-                                let ts = SystemTime::now()
-                                    .duration_since(UNIX_EPOCH)
-                                    .unwrap_or_default()
-                                    .as_secs();
-                                let ts_midnight_utc = (ts / 86400) * 86400;
-
-                                let taa_acceptance = rb
-                                    .prepare_txn_author_agreement_acceptance_data(
-                                        Some(t),
-                                        Some(v),
-                                        digest,
-                                        "wallet_agreement",
-                                        ts_midnight_utc,
-                                    )
-                                    .map_err(|e| {
-                                        napi::Error::from_reason(format!("Erro prepare TAA: {}", e))
-                                    })?;
-
-                                req.set_txn_author_agreement_acceptance(&taa_acceptance)
-                                    .map_err(|e| {
-                                        napi::Error::from_reason(format!("Erro set TAA: {}", e))
-                                    })?;
-                            }
-                        }
-                    }
-                }
-
-                // 6. Assinar
-                let signature_input = req
-                    .get_signature_input()
-                    .map_err(|e| napi::Error::from_reason(format!("Sig Input: {}", e)))?;
-                let signature = my_key
-                    .sign_message(signature_input.as_bytes(), None)
-                    .map_err(|e| napi::Error::from_reason(format!("Sign: {}", e)))?;
-                req.set_signature(&signature)
-                    .map_err(|e| napi::Error::from_reason(format!("Set Sig: {}", e)))?;
-
-                // 7. Enviar
-                let response = send_request_async(&pool, req).await?;
-
-                // 8. Verificar
-                let resp_json: serde_json::Value =
-                    serde_json::from_str(&response).map_err(|e| {
-                        napi::Error::from_reason(format!("Resposta JSON inválida: {}", e))
-                    })?;
-                if let Some(op) = resp_json.get("op") {
-                    if op == "REJECT" || op == "REQNACK" {
-                        let reason = resp_json
-                            .get("reason")
-                            .and_then(|r| r.as_str())
-                            .unwrap_or("Sem detalhes");
-                        return Err(napi::Error::from_reason(format!(
-                            "Ledger REJECT: {}",
-                            reason
-                        )));
-                    }
-                }
-
-                // 9. Salvar
-                let seq_no = resp_json["result"]["seqNo"].as_u64();
-
-                let schema_json_str = serde_json::json!({
-                    "id": schema_id,
-                    "name": name,
-                    "version": version,
-                    "ver": "1.0",
-                    "attrNames": attr_names,
-                    "seqNo": seq_no
-                })
-                .to_string();
-
-                // session
-                //     .insert("schema", &schema_id, schema_json_str.as_bytes(), None, None)
-                //     .await
-                //     .map_err(|e| napi::Error::from_reason(format!("Erro salvar schema: {}", e)))?;
-                // session
-                //     .commit()
-                //     .await
-                //     .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
-
-                let tags = vec![
-                    EntryTag::Encrypted("on_ledger".to_string(), "true".to_string()),
-                    EntryTag::Encrypted("env".to_string(), "prod".to_string()),
-                    EntryTag::Encrypted("name".to_string(), name.clone()),
-                    EntryTag::Encrypted("version".to_string(), version.clone()),
-                    EntryTag::Encrypted("issuer_did".to_string(), issuer_did.clone()),
-                    EntryTag::Encrypted("revocable".to_string(), "false".to_string()), // por enquanto
-                ];
-
-                session
-                    .insert(
-                        "schema",
-                        &schema_id,
-                        schema_json_str.as_bytes(),
-                        Some(&tags),
-                        None,
-                    )
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro salvar schema: {}", e)))?;
-
-                session
-                    .commit()
-                    .await
-                    .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
-
-                Ok(schema_id)
             },
             |&mut env, data| env.create_string(&data),
         )
@@ -383,6 +478,9 @@ impl IndyAgent {
             env: envv.clone(),
             created_at: now,
             updated_at: now,
+            supersedes: None,
+            template_id: None,
+            attr_types: None,
         };
 
         let json = serde_json::to_string(&rec)
@@ -601,18 +699,10 @@ impl IndyAgent {
                     napi::Error::from_reason(format!("Erro serializar schema: {}", e))
                 })?;
 
-                let tags = vec![
-                    EntryTag::Encrypted("on_ledger".to_string(), "true".to_string()),
-                    EntryTag::Encrypted("env".to_string(), "prod".to_string()),
-                    EntryTag::Encrypted("name".to_string(), rec.name.clone()),
-                    EntryTag::Encrypted("version".to_string(), rec.version.clone()),
-                    EntryTag::Encrypted("issuer_did".to_string(), issuer_did),
-                    EntryTag::Encrypted(
-                        "revocable".to_string(),
-                        if rec.revocable { "true" } else { "false" }.to_string(),
-                    ),
-                ];
+                let tags = schema_record_tags(&rec);
 
+                // Registro já existe: insert direto falharia como duplicado (upsert)
+                remove_if_exists(&mut session, "schema", &id_local).await?;
                 session
                     .insert("schema", &id_local, json.as_bytes(), Some(&tags), None)
                     .await
@@ -690,4 +780,547 @@ impl IndyAgent {
 
         Ok(entry.map(|e| String::from_utf8(e.value.to_vec()).unwrap_or_default()))
     }
+
+    // =========================================================================
+    //  VERSIONAMENTO DE SCHEMAS
+    // =========================================================================
+    // Schemas no ledger são imutáveis: evoluir = criar nova versão local
+    // (clone + addAttrs/removeAttrs + bump) ligada à anterior via "supersedes".
+    #[napi]
+    pub async fn schema_create_next_version(
+        &self,
+        id_local: String,
+        opts_json: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let opts: SchemaNextVersionOpts = match opts_json {
+            Some(s) if !s.trim().is_empty() => serde_json::from_str(&s)
+                .map_err(|e| napi_err("SchemaNextVersionOptsInvalid", e.to_string()))?,
+            _ => SchemaNextVersionOpts::default(),
+        };
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entry = session
+            .fetch("schema", &id_local, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch schema: {}", e)))?
+            .ok_or_else(|| {
+                napi_err(
+                    "SchemaNotFound",
+                    format!("Schema local não encontrado: {}", id_local),
+                )
+            })?;
+        let prev: SchemaRecord = serde_json::from_slice(&entry.value)
+            .map_err(|e| Error::from_reason(format!("JSON schema local inválido: {}", e)))?;
+
+        // Mantém a linhagem linear: cada versão só pode ser sucedida uma vez
+        let same_name = session
+            .fetch_all(
+                Some("schema"),
+                Some(TagFilter::is_eq("name", &prev.name)),
+                None,
+                None,
+                false,
+                false,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch_all schema: {}", e)))?;
+        let siblings: Vec<SchemaRecord> = same_name
+            .iter()
+            .filter_map(|e| serde_json::from_slice::<SchemaRecord>(&e.value).ok())
+            .collect();
+        if let Some(next) = siblings
+            .iter()
+            .find(|r| r.supersedes.as_deref() == Some(id_local.as_str()))
+        {
+            return Err(napi_err(
+                "SchemaAlreadySuperseded",
+                format!(
+                    "Schema {} já foi sucedido por {} (versão {})",
+                    id_local, next.id_local, next.version
+                ),
+            ));
+        }
+
+        // Atributos: remove -> adiciona (na ordem original)
+        let mut attrs = prev.attr_names.clone();
+        for r in opts.remove_attrs.unwrap_or_default() {
+            let before = attrs.len();
+            attrs.retain(|a| !a.eq_ignore_ascii_case(r.trim()));
+            if attrs.len() == before {
+                return Err(napi_err(
+                    "SchemaAttrNotFound",
+                    format!("Atributo a remover não existe no schema: {}", r),
+                ));
+            }
+        }
+        for a in opts.add_attrs.unwrap_or_default() {
+            attrs.push(a.trim().to_string());
+        }
+        if attrs.is_empty() {
+            return Err(napi_err(
+                "SchemaAttrsEmpty",
+                "A nova versão precisa de pelo menos um atributo",
+            ));
+        }
+
+        let final_attrs = build_final_attr_names(attrs.clone(), prev.revocable)
+            .map_err(|e| napi_err("SchemaAttrsInvalid", e.reason))?;
+
+        let bump = opts.bump.unwrap_or_else(|| "minor".to_string());
+        let version = bump_schema_version(&prev.version, &bump)?;
+        if siblings.iter().any(|r| r.version == version) {
+            return Err(napi_err(
+                "SchemaVersionExists",
+                format!("Já existe {} versão {} na wallet", prev.name, version),
+            ));
+        }
+
+        // Overlay de tipos herdado apenas para atributos que continuam existindo
+        let attr_types = prev.attr_types.as_ref().map(|m| {
+            m.iter()
+                .filter(|(k, _)| attrs.contains(k))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<std::collections::BTreeMap<_, _>>()
+        });
+
+        let now = now_ts();
+        let rec = SchemaRecord {
+            id_local: make_schema_local_id(),
+            name: prev.name.clone(),
+            version,
+            attr_names: attrs,
+            revocable: prev.revocable,
+            final_attr_names: final_attrs,
+            on_ledger: false,
+            schema_id: None,
+            issuer_did: None,
+            env: prev.env.clone(),
+            created_at: now,
+            updated_at: now,
+            supersedes: Some(prev.id_local.clone()),
+            // Versão derivada não é instância do template (supersedes aponta a origem).
+            template_id: None,
+            attr_types: attr_types.filter(|m| !m.is_empty()),
+        };
+
+        let json = serde_json::to_string(&rec)
+            .map_err(|e| Error::from_reason(format!("Erro serializar schema local: {}", e)))?;
+
+        session
+            .insert(
+                "schema",
+                &rec.id_local,
+                json.as_bytes(),
+                Some(&schema_record_tags(&rec)),
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar schema local: {}", e)))?;
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(json)
+    }
+
+    // Todas as versões locais de um schema (por nome), em ordem de versão,
+    // com ids no ledger, diffs de atributos e cred defs que usam cada versão.
+    #[napi]
+    pub async fn schema_lineage(&self, name: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entries = session
+            .fetch_all(
+                Some("schema"),
+                Some(TagFilter::is_eq("name", &name)),
+                None,
+                None,
+                false,
+                false,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch_all schema: {}", e)))?;
+
+        let mut recs: Vec<SchemaRecord> = entries
+            .iter()
+            .filter_map(|e| serde_json::from_slice::<SchemaRecord>(&e.value).ok())
+            .collect();
+        recs.sort_by(|a, b| {
+            compare_schema_versions(&a.version, &b.version).then(a.created_at.cmp(&b.created_at))
+        });
+
+        let mut versions: Vec<serde_json::Value> = Vec::new();
+        for rec in recs.iter() {
+            let superseded_by = recs
+                .iter()
+                .find(|r| r.supersedes.as_deref() == Some(rec.id_local.as_str()))
+                .map(|r| r.id_local.clone());
+
+            let (added, removed) = match rec
+                .supersedes
+                .as_ref()
+                .and_then(|p| recs.iter().find(|r| &r.id_local == p))
+            {
+                Some(p) => (
+                    rec.attr_names
+                        .iter()
+                        .filter(|a| !p.attr_names.contains(a))
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    p.attr_names
+                        .iter()
+                        .filter(|a| !rec.attr_names.contains(a))
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                ),
+                None => (vec![], vec![]),
+            };
+
+            let mut cred_defs: Vec<String> = Vec::new();
+            if let Some(sid) = &rec.schema_id {
+                let cds = session
+                    .fetch_all(
+                        Some("cred_def"),
+                        Some(TagFilter::is_eq("schema_id", sid)),
+                        None,
+                        None,
+                        false,
+                        false,
+                    )
+                    .await
+                    .map_err(|e| Error::from_reason(format!("Erro fetch_all cred_def: {}", e)))?;
+                cred_defs = cds.iter().map(|e| e.name.clone()).collect();
+            }
+
+            versions.push(serde_json::json!({
+                "idLocal": rec.id_local,
+                "version": rec.version,
+                "env": rec.env,
+                "onLedger": rec.on_ledger,
+                "schemaId": rec.schema_id,
+                "issuerDid": rec.issuer_did,
+                "attrNames": rec.attr_names,
+                "revocable": rec.revocable,
+                "supersedes": rec.supersedes,
+                "supersededBy": superseded_by,
+                "addedAttrs": added,
+                "removedAttrs": removed,
+                "credDefs": cred_defs,
+                "createdAt": rec.created_at
+            }));
+        }
+
+        let latest = recs
+            .iter()
+            .rev()
+            .find(|r| !recs.iter().any(|x| x.supersedes.as_deref() == Some(r.id_local.as_str())))
+            .map(|r| r.id_local.clone());
+
+        Ok(serde_json::json!({
+            "name": name,
+            "count": versions.len(),
+            "latest": latest,
+            "versions": versions
+        })
+        .to_string())
+    }
+
+    // =========================================================================
+    //  TIPAGEM DE ATRIBUTOS (OVERLAY LOCAL)
+    // =========================================================================
+    // types_json: { "<attr>": { "type": "int"|"date"|"enum"|"string"|"boolean", ... } }
+    //   int:    { "min"?, "max"? }          date: ISO "YYYY-MM-DD" -> dias desde epoch
+    //   enum:   { "values": [..] }          string: { "pattern"? (regex), "maxLen"? }
+    // "{}" ou null remove o overlay. Atributos sem tipo seguem a regra antiga.
+    #[napi]
+    pub async fn schema_set_attr_types(
+        &self,
+        id_local: String,
+        types_json: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let types: BTreeMap<String, AttrType> = match types_json {
+            Some(s) if !s.trim().is_empty() && s.trim() != "null" => {
+                serde_json::from_str(&s).map_err(|e| {
+                    napi_err("AttrTypeInvalid", format!("JSON de tipos inválido: {}", e))
+                })?
+            }
+            _ => BTreeMap::new(),
+        };
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entry = session
+            .fetch("schema", &id_local, true)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch schema: {}", e)))?
+            .ok_or_else(|| {
+                napi_err(
+                    "SchemaNotFound",
+                    format!("Schema local não encontrado: {}", id_local),
+                )
+            })?;
+        let mut rec: SchemaRecord = serde_json::from_slice(&entry.value)
+            .map_err(|e| Error::from_reason(format!("JSON schema local inválido: {}", e)))?;
+
+        for (attr, t) in types.iter() {
+            if !rec.attr_names.contains(attr) {
+                return Err(napi_err(
+                    "AttrTypeInvalid",
+                    format!("Atributo não pertence ao schema (ou é de controle): {}", attr),
+                ));
+            }
+            check_attr_type_def(attr, t)?;
+        }
+
+        rec.attr_types = if types.is_empty() { None } else { Some(types) };
+        rec.updated_at = now_ts();
+
+        let json = serde_json::to_string(&rec)
+            .map_err(|e| Error::from_reason(format!("Erro serializar schema local: {}", e)))?;
+
+        session
+            .remove("schema", &id_local)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro remove schema: {}", e)))?;
+        session
+            .insert(
+                "schema",
+                &id_local,
+                json.as_bytes(),
+                Some(&schema_record_tags(&rec)),
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar schema local: {}", e)))?;
+
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(json)
+    }
+
+    // =========================================================================
+    //  IMPORTAR SCHEMA DO LEDGER PARA O REGISTRO LOCAL
+    // =========================================================================
+    // Adota um schema de outro emissor: GET_SCHEMA, normaliza attr_names/attrNames,
+    // detecta o layout revogável (CONTROL_ATTRS completos) e grava como
+    // SchemaRecord on_ledger=true com o DID do emissor estrangeiro.
+    // Idempotente por schema_id (devolve o registro existente com imported=false).
+    #[napi]
+    pub fn schema_import_from_ledger(
+        &self,
+        env: Env,
+        schema_id: String,
+        env_label: Option<String>,
+    ) -> Result<JsObject> {
+        let pool = match &self.pool {
+            Some(p) => p.clone(),
+            None => {
+                return Err(Error::from_reason(
+                    "Não conectado à rede. Execute connect_network antes.",
+                ))
+            }
+        };
+        let store = match &self.store {
+            Some(s) => s.clone(),
+            None => return Err(Error::from_reason("Wallet fechada!")),
+        };
+        let envv = env_label
+            .filter(|e| !e.trim().is_empty())
+            .unwrap_or_else(|| "prod".to_string());
+
+        env.execute_tokio_future(
+            async move {
+                let parts: Vec<&str> = schema_id.split(':').collect();
+                if parts.len() != 4 || parts[1] != "2" {
+                    return Err(napi_err(
+                        "SchemaIdInvalid",
+                        format!("schemaId inválido (esperado did:2:nome:versão): {}", schema_id),
+                    ));
+                }
+                let foreign_did = parts[0].to_string();
+
+                let mut session = store
+                    .session(None)
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+                let existing = session
+                    .fetch_all(
+                        Some("schema"),
+                        Some(TagFilter::is_eq("schema_id", &schema_id)),
+                        Some(1),
+                        None,
+                        false,
+                        false,
+                    )
+                    .await
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("Erro fetch_all schema: {}", e))
+                    })?;
+                if let Some(e) = existing.first() {
+                    let json = String::from_utf8(e.value.to_vec()).unwrap_or_default();
+                    return Ok(serde_json::json!({
+                        "ok": true,
+                        "imported": false,
+                        "schemaId": schema_id,
+                        "json": json
+                    })
+                    .to_string());
+                }
+
+                // 1) GET_SCHEMA
+                let rb = RequestBuilder::new(ProtocolVersion::Node1_4);
+                let req = rb
+                    .build_get_schema_request(None, &LedgerSchemaId(schema_id.clone()))
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("Erro build GET request: {}", e))
+                    })?;
+                let response_str = send_request_async(&pool, req).await?;
+                let json: serde_json::Value = serde_json::from_str(&response_str)
+                    .map_err(|_e| napi::Error::from_reason("Erro parse JSON resposta"))?;
+
+                let result = &json["result"];
+                let seq_no = result["seqNo"].as_u64().ok_or_else(|| {
+                    napi_err(
+                        "SchemaNotOnLedger",
+                        format!("Schema {} não encontrado (seqNo ausente).", schema_id),
+                    )
+                })?;
+
+                // 2) Normalização (data pode vir como string JSON ou objeto)
+                let data: serde_json::Value = match &result["data"] {
+                    serde_json::Value::String(s) => serde_json::from_str(s).unwrap_or_default(),
+                    other => other.clone(),
+                };
+                let ledger_attrs: Vec<String> = data
+                    .get("attr_names")
+                    .or_else(|| data.get("attrNames"))
+                    .and_then(|v| v.as_array())
+                    .map(|arr| {
+                        arr.iter()
+                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if ledger_attrs.is_empty() {
+                    return Err(napi_err(
+                        "SchemaNotOnLedger",
+                        format!("Schema {} sem atributos no ledger.", schema_id),
+                    ));
+                }
+
+                let name = data["name"]
+                    .as_str()
+                    .or_else(|| result["data"]["name"].as_str())
+                    .unwrap_or(parts[2])
+                    .to_string();
+                let version = data["version"]
+                    .as_str()
+                    .or_else(|| result["data"]["version"].as_str())
+                    .unwrap_or(parts[3])
+                    .to_string();
+
+                // 3) Layout revogável: todos os CONTROL_ATTRS presentes
+                let has_all_control = CONTROL_ATTRS
+                    .iter()
+                    .all(|c| ledger_attrs.iter().any(|a| a.eq_ignore_ascii_case(c)));
+                let user_attrs: Vec<String> = ledger_attrs
+                    .iter()
+                    .filter(|a| !is_reserved_control_attr(a))
+                    .cloned()
+                    .collect();
+                let partial_control = !has_all_control && user_attrs.len() != ledger_attrs.len();
+
+                let (attr_names, revocable, final_attr_names) = if has_all_control {
+                    let fin = build_final_attr_names(user_attrs.clone(), true)
+                        .map_err(|e| napi_err("SchemaAttrsInvalid", e.reason))?;
+                    (user_attrs, true, fin)
+                } else {
+                    // Layout parcial/estrangeiro: preserva a lista do ledger como está
+                    (ledger_attrs.clone(), false, ledger_attrs.clone())
+                };
+
+                let now = now_ts();
+                let rec = SchemaRecord {
+                    id_local: make_schema_local_id(),
+                    name,
+                    version,
+                    attr_names,
+                    revocable,
+                    final_attr_names,
+                    on_ledger: true,
+                    schema_id: Some(schema_id.clone()),
+                    issuer_did: Some(foreign_did),
+                    env: envv,
+                    created_at: now,
+                    updated_at: now,
+                    supersedes: None,
+                    template_id: None,
+                    attr_types: None,
+                };
+
+                let rec_json = serde_json::to_string(&rec).map_err(|e| {
+                    napi::Error::from_reason(format!("Erro serializar schema: {}", e))
+                })?;
+
+                session
+                    .insert(
+                        "schema",
+                        &rec.id_local,
+                        rec_json.as_bytes(),
+                        Some(&schema_record_tags(&rec)),
+                        None,
+                    )
+                    .await
+                    .map_err(|e| {
+                        napi::Error::from_reason(format!("Erro salvar schema local: {}", e))
+                    })?;
+                session
+                    .commit()
+                    .await
+                    .map_err(|e| napi::Error::from_reason(format!("Erro commit: {}", e)))?;
+
+                Ok(serde_json::json!({
+                    "ok": true,
+                    "imported": true,
+                    "schemaId": schema_id,
+                    "seqNo": seq_no,
+                    "revocable": revocable,
+                    "partialControlAttrs": partial_control,
+                    "json": rec_json
+                })
+                .to_string())
+            },
+            |&mut env, data| env.create_string(&data),
+        )
+    }
 }
@@ -0,0 +1,714 @@
+// src/modules/time_window.rs
+//
+// Revogação por janela de tempo controlada pelo holder (CONTROL_ATTRS dos
+// schemas revogáveis), sem registro de revogação no ledger.
+//
+// 1) Holder, na hora do request: timeWindowPrepare gera um seed secreto e
+//    monta uma árvore de Merkle sobre os slots da janela
+//    (slot i = [start_time + i*unit, start_time + (i+1)*unit), i < time_window).
+//      folha_i = sha256("ssi-time-window/leaf" || seed || i_u64be)
+//      nó folha = sha256(0x00 || folha_i); nó interno = sha256(0x01 || esq || dir)
+//    Nível com quantidade ímpar: o último nó sobe sem par. O seed fica na
+//    wallet; para o issuer vão só start_time, unit_of_time, time_window,
+//    root_merkle_L e seed = sha256("ssi-time-window/seed" || seed) (compromisso).
+// 2) Issuer: timeWindowEmbed valida esses valores e os junta aos valores da
+//    credencial antes do createCredential.
+// 3) Holder, na apresentação: timeWindowProof entrega a folha do slot atual e
+//    o caminho de autenticação; a apresentação AnonCreds revela start_time,
+//    unit_of_time, time_window e root_merkle_L.
+// 4) Verifier: timeWindowVerify confere folha + caminho contra o root revelado
+//    e o slot contra o relógio.
+//
+// Revogar = o holder destrói o seed (timeWindowRevoke): nenhuma folha de slot
+// futuro pode mais ser produzida. Observação: root_merkle_L e a folha revelados
+// são únicos por credencial, então apresentações com janela são correlacionáveis.
+
+use crate::modules::common::{napi_err, now_ts, remove_if_exists};
+use crate::modules::validity::{parse_time, unit_seconds, values_raw_of};
+use crate::IndyAgent;
+use aries_askar::entry::{EntryTag, TagFilter};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine as _;
+use napi::{Error, Result};
+use napi_derive::napi;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+const SEED_CATEGORY: &str = "time_window_seed";
+
+const LEAF_DOMAIN: &[u8] = b"ssi-time-window/leaf";
+const SEED_DOMAIN: &[u8] = b"ssi-time-window/seed";
+
+// 2^20 slots: ~12 dias em segundos, ~2800 anos em dias
+const MAX_SLOTS: i64 = 1 << 20;
+
+const STATUS_ACTIVE: &str = "active";
+const STATUS_REVOKED: &str = "revoked";
+
+type H256 = [u8; 32];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct PrepareParams {
+    #[serde(default)]
+    start_time: Option<Value>,
+    unit_of_time: String,
+    time_window: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SeedRecord {
+    // vazio depois de revogado
+    seed: String,
+    start_time: i64,
+    unit_of_time: String,
+    time_window: i64,
+    root: String,
+    offer_nonce: String,
+    created_at: i64,
+    #[serde(default)]
+    revoked_at: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ProofOptions {
+    #[serde(default)]
+    at_time: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct VerifyOptions {
+    #[serde(default)]
+    at_time: Option<i64>,
+    #[serde(default)]
+    tolerance_slots: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WindowProof {
+    slot: i64,
+    leaf: String,
+    path: Vec<String>,
+    root_merkle_l: String,
+}
+
+// Janela já validada (start, unidade em segundos, quantidade de slots).
+struct Window {
+    start: i64,
+    unit: i64,
+    slots: i64,
+}
+
+impl Window {
+    fn parse(start_time: &str, unit_of_time: &str, time_window: &str) -> Option<Self> {
+        let (start, _) = parse_time(start_time)?;
+        let unit = unit_seconds(unit_of_time)?;
+        let slots: i64 = time_window.trim().parse().ok()?;
+        if slots <= 0 || slots > MAX_SLOTS {
+            return None;
+        }
+        start.checked_add(slots.checked_mul(unit)?)?;
+        Some(Self { start, unit, slots })
+    }
+
+    // Slot que contém t (pode cair fora de [0, slots)). t vem do chamador
+    // (atTime): distância fora de i64 é recusada.
+    fn slot_at(&self, t: i64) -> Result<i64> {
+        t.checked_sub(self.start)
+            .map(|d| d.div_euclid(self.unit))
+            .ok_or_else(|| {
+                napi_err(
+                    "TimeWindowParamsInvalid",
+                    format!("atTime fora da faixa: {}", t),
+                )
+            })
+    }
+}
+
+fn to_hex(b: &[u8]) -> String {
+    b.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+fn from_hex32(s: &str) -> Option<H256> {
+    if s.len() != 64 || !s.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+fn seed_commitment(seed: &[u8]) -> String {
+    let mut h = Sha256::new();
+    h.update(SEED_DOMAIN);
+    h.update(seed);
+    to_hex(&h.finalize())
+}
+
+fn leaf_secret(seed: &[u8], slot: i64) -> H256 {
+    let mut h = Sha256::new();
+    h.update(LEAF_DOMAIN);
+    h.update(seed);
+    h.update((slot as u64).to_be_bytes());
+    h.finalize().into()
+}
+
+fn leaf_node(leaf: &H256) -> H256 {
+    let mut h = Sha256::new();
+    h.update([0u8]);
+    h.update(leaf);
+    h.finalize().into()
+}
+
+fn parent_node(left: &H256, right: &H256) -> H256 {
+    let mut h = Sha256::new();
+    h.update([1u8]);
+    h.update(left);
+    h.update(right);
+    h.finalize().into()
+}
+
+// Todos os níveis da árvore, das folhas (0) até o root.
+fn build_levels(seed: &[u8], slots: i64) -> Vec<Vec<H256>> {
+    let leaves: Vec<H256> = (0..slots)
+        .map(|i| leaf_node(&leaf_secret(seed, i)))
+        .collect();
+    let mut levels = vec![leaves];
+    while levels.last().map_or(0, |l| l.len()) > 1 {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|c| {
+                if c.len() == 2 {
+                    parent_node(&c[0], &c[1])
+                } else {
+                    c[0]
+                }
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+fn auth_path(levels: &[Vec<H256>], slot: i64) -> Vec<String> {
+    let mut idx = slot as usize;
+    let mut path = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        let sib = idx ^ 1;
+        if sib < level.len() {
+            path.push(to_hex(&level[sib]));
+        }
+        idx >>= 1;
+    }
+    path
+}
+
+// Recalcula o root a partir da folha e do caminho; None se o caminho não tem
+// o tamanho exato esperado para (slot, slots).
+fn root_from_path(leaf: &H256, slot: i64, slots: i64, path: &[H256]) -> Option<H256> {
+    let mut h = leaf_node(leaf);
+    let mut idx = slot as usize;
+    let mut n = slots as usize;
+    let mut it = path.iter();
+    while n > 1 {
+        if idx ^ 1 < n {
+            let sib = it.next()?;
+            h = if idx % 2 == 0 {
+                parent_node(&h, sib)
+            } else {
+                parent_node(sib, &h)
+            };
+        }
+        idx >>= 1;
+        n = n.div_ceil(2);
+    }
+    it.next().is_none().then_some(h)
+}
+
+fn control_str<'a>(values: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    values
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .and_then(|(_, v)| v.as_str())
+}
+
+// Valores revelados por sub_proof_index ({ atributo: raw }), usando os nomes
+// de requested_attributes (name ou names).
+fn revealed_by_sub_proof(
+    pres_req: &Value,
+    presentation: &Value,
+) -> BTreeMap<u64, Map<String, Value>> {
+    let mut out: BTreeMap<u64, Map<String, Value>> = BTreeMap::new();
+    let requested = &pres_req["requested_attributes"];
+    let proof = &presentation["requested_proof"];
+
+    if let Some(attrs) = proof["revealed_attrs"].as_object() {
+        for (referent, info) in attrs {
+            let (Some(name), Some(idx), Some(raw)) = (
+                requested[referent]["name"].as_str(),
+                info["sub_proof_index"].as_u64(),
+                info.get("raw"),
+            ) else {
+                continue;
+            };
+            out.entry(idx)
+                .or_default()
+                .insert(name.to_string(), raw.clone());
+        }
+    }
+    if let Some(groups) = proof["revealed_attr_groups"].as_object() {
+        for info in groups.values() {
+            let (Some(idx), Some(values)) =
+                (info["sub_proof_index"].as_u64(), info["values"].as_object())
+            else {
+                continue;
+            };
+            let entry = out.entry(idx).or_default();
+            for (name, v) in values {
+                if let Some(raw) = v.get("raw") {
+                    entry.insert(name.clone(), raw.clone());
+                }
+            }
+        }
+    }
+    out
+}
+
+async fn find_seed_record(
+    session: &mut aries_askar::Session,
+    root: &str,
+) -> Result<Option<SeedRecord>> {
+    let entries = session
+        .fetch_all(
+            Some(SEED_CATEGORY),
+            Some(TagFilter::is_eq("root", root)),
+            Some(1),
+            None,
+            false,
+            false,
+        )
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch seed: {}", e)))?;
+    Ok(entries
+        .first()
+        .and_then(|e| serde_json::from_slice(&e.value).ok()))
+}
+
+async fn save_seed_record(session: &mut aries_askar::Session, rec: &SeedRecord) -> Result<()> {
+    let status = if rec.revoked_at.is_some() {
+        STATUS_REVOKED
+    } else {
+        STATUS_ACTIVE
+    };
+    let tags = vec![
+        EntryTag::Encrypted("root".to_string(), rec.root.clone()),
+        EntryTag::Encrypted("offer_nonce".to_string(), rec.offer_nonce.clone()),
+        EntryTag::Encrypted("status".to_string(), status.to_string()),
+    ];
+    let bytes = serde_json::to_vec(rec)
+        .map_err(|e| Error::from_reason(format!("Erro serializar seed: {}", e)))?;
+    remove_if_exists(session, SEED_CATEGORY, &rec.offer_nonce).await?;
+    session
+        .insert(SEED_CATEGORY, &rec.offer_nonce, &bytes, Some(&tags), None)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro salvar seed: {}", e)))
+}
+
+async fn load_credential_values(
+    session: &mut aries_askar::Session,
+    credential_id: &str,
+) -> Result<Map<String, Value>> {
+    let entry = session
+        .fetch("credential", credential_id, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch credencial: {}", e)))?
+        .ok_or_else(|| {
+            napi_err(
+                "CredentialNotFound",
+                format!("Credencial não encontrada: {}", credential_id),
+            )
+        })?;
+    let cred: Value = serde_json::from_slice(&entry.value).unwrap_or_default();
+    Ok(values_raw_of(&cred))
+}
+
+fn value_as_string(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  JANELA DE TEMPO (MERKLE) — REVOGAÇÃO PELO HOLDER
+    // =========================================================================
+
+    // Holder, junto do createCredentialRequest. params_json:
+    // { startTime?: epoch|ISO (padrão agora), unitOfTime, timeWindow }.
+    // Retorna os valores de controle para o issuer:
+    // { seed, start_time, unit_of_time, time_window, root_merkle_L }.
+    // Chamar de novo para a mesma oferta troca o seed.
+    #[napi]
+    pub async fn time_window_prepare(
+        &self,
+        offer_json: String,
+        params_json: String,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+
+        let offer: Value = serde_json::from_str(&offer_json)
+            .map_err(|e| Error::from_reason(format!("JSON inválido offer_json: {}", e)))?;
+        let offer_nonce = offer["nonce"]
+            .as_str()
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| Error::from_reason("offer_json sem nonce"))?
+            .to_string();
+
+        let params: PrepareParams = serde_json::from_str(&params_json)
+            .map_err(|e| napi_err("TimeWindowParamsInvalid", e.to_string()))?;
+        let start_time = match &params.start_time {
+            None | Some(Value::Null) => now_ts().to_string(),
+            Some(v) => value_as_string(v)
+                .and_then(|s| parse_time(&s))
+                .map(|(t, _)| t.to_string())
+                .ok_or_else(|| napi_err("TimeWindowParamsInvalid", "startTime inválido"))?,
+        };
+        let time_window = value_as_string(&params.time_window).unwrap_or_default();
+        let unit_of_time = params.unit_of_time.trim().to_string();
+        let window = Window::parse(&start_time, &unit_of_time, &time_window).ok_or_else(|| {
+            napi_err(
+                "TimeWindowParamsInvalid",
+                format!(
+                    "unitOfTime/timeWindow inválidos (timeWindow entre 1 e {})",
+                    MAX_SLOTS
+                ),
+            )
+        })?;
+
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        let slots = window.slots;
+        let root = tokio::task::spawn_blocking(move || {
+            let levels = build_levels(&seed, slots);
+            to_hex(&levels.last().unwrap()[0])
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro montar árvore: {}", e)))?;
+
+        let rec = SeedRecord {
+            seed: B64.encode(seed),
+            start_time: window.start,
+            unit_of_time: unit_of_time.clone(),
+            time_window: slots,
+            root: root.clone(),
+            offer_nonce,
+            created_at: now_ts(),
+            revoked_at: None,
+        };
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        save_seed_record(&mut session, &rec).await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+
+        Ok(json!({
+            "seed": seed_commitment(&seed),
+            "start_time": window.start.to_string(),
+            "unit_of_time": unit_of_time,
+            "time_window": slots.to_string(),
+            "root_merkle_L": root,
+        })
+        .to_string())
+    }
+
+    // Issuer: valida os valores de controle vindos do holder e os junta aos
+    // valores da credencial. Atributo de controle já presente com outro valor
+    // falha com TimeWindowConflict. Retorna o values_json final.
+    #[napi]
+    pub fn time_window_embed(&self, values_json: String, control_json: String) -> Result<String> {
+        let mut values: Map<String, Value> = serde_json::from_str(&values_json)
+            .map_err(|e| Error::from_reason(format!("JSON inválido values_json: {}", e)))?;
+        let control: Map<String, Value> = serde_json::from_str(&control_json)
+            .map_err(|e| napi_err("TimeWindowControlInvalid", e.to_string()))?;
+
+        let mut parsed: Vec<(&str, String)> = Vec::new();
+        for name in [
+            "seed",
+            "start_time",
+            "unit_of_time",
+            "time_window",
+            "root_merkle_L",
+        ] {
+            let v = control
+                .get(name)
+                .and_then(value_as_string)
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| napi_err("TimeWindowControlInvalid", format!("{} ausente", name)))?;
+            parsed.push((name, v));
+        }
+        let get = |n: &str| parsed.iter().find(|(k, _)| *k == n).unwrap().1.as_str();
+        if from_hex32(get("seed")).is_none() || from_hex32(get("root_merkle_L")).is_none() {
+            return Err(napi_err(
+                "TimeWindowControlInvalid",
+                "seed/root_merkle_L devem ser sha256 em hex",
+            ));
+        }
+        if Window::parse(get("start_time"), get("unit_of_time"), get("time_window")).is_none() {
+            return Err(napi_err(
+                "TimeWindowControlInvalid",
+                "start_time/unit_of_time/time_window inválidos",
+            ));
+        }
+
+        for (name, v) in &parsed {
+            let existing = values
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(k, ev)| (k.clone(), value_as_string(ev).unwrap_or_default()));
+            match existing {
+                Some((_, ev)) if !ev.is_empty() && ev != *v => {
+                    return Err(napi_err(
+                        "TimeWindowConflict",
+                        format!("{} já definido com outro valor", name),
+                    ));
+                }
+                Some((k, _)) => {
+                    values.insert(k, json!(v));
+                }
+                None => {
+                    values.insert(name.to_string(), json!(v));
+                }
+            }
+        }
+        serde_json::to_string(&values)
+            .map_err(|e| Error::from_reason(format!("Erro serializar valores: {}", e)))
+    }
+
+    // Holder: prova do slot atual para uma credencial armazenada.
+    // opts_json: { atTime?: epoch s }.
+    // Retorna { slot, leaf, path, rootMerkleL, validFrom, validUntil }.
+    #[napi]
+    pub async fn time_window_proof(
+        &self,
+        credential_id: String,
+        opts_json: Option<String>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let opts: ProofOptions = match opts_json.as_deref() {
+            Some(s) if !s.trim().is_empty() => serde_json::from_str(s)
+                .map_err(|e| napi_err("TimeWindowParamsInvalid", e.to_string()))?,
+            _ => ProofOptions::default(),
+        };
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let values = load_credential_values(&mut session, &credential_id).await?;
+        let root = control_str(&values, "root_merkle_L").unwrap_or_default();
+        let rec = find_seed_record(&mut session, root).await?.ok_or_else(|| {
+            napi_err(
+                "TimeWindowSeedNotFound",
+                format!(
+                    "Credencial {} sem seed de janela nesta wallet",
+                    credential_id
+                ),
+            )
+        })?;
+        if rec.revoked_at.is_some() {
+            return Err(napi_err(
+                "TimeWindowRevoked",
+                format!(
+                    "Janela da credencial {} revogada pelo holder",
+                    credential_id
+                ),
+            ));
+        }
+        let seed = B64
+            .decode(&rec.seed)
+            .map_err(|e| Error::from_reason(format!("Seed corrompido: {}", e)))?;
+        if control_str(&values, "seed") != Some(seed_commitment(&seed).as_str()) {
+            return Err(napi_err(
+                "TimeWindowSeedNotFound",
+                "compromisso do seed não confere com a credencial",
+            ));
+        }
+        let window = Window {
+            start: rec.start_time,
+            unit: unit_seconds(&rec.unit_of_time)
+                .ok_or_else(|| Error::from_reason("unit_of_time corrompido"))?,
+            slots: rec.time_window,
+        };
+
+        let slot = window.slot_at(opts.at_time.unwrap_or_else(now_ts))?;
+        if slot < 0 {
+            return Err(napi_err("TimeWindowNotStarted", "janela ainda não começou"));
+        }
+        if slot >= window.slots {
+            return Err(napi_err("TimeWindowExpired", "janela encerrada"));
+        }
+
+        let slots = window.slots;
+        let (leaf, path) = tokio::task::spawn_blocking(move || {
+            let levels = build_levels(&seed, slots);
+            (to_hex(&leaf_secret(&seed, slot)), auth_path(&levels, slot))
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro montar árvore: {}", e)))?;
+
+        Ok(json!({
+            "slot": slot,
+            "leaf": leaf,
+            "path": path,
+            "rootMerkleL": rec.root,
+            "validFrom": window.start + slot * window.unit,
+            "validUntil": window.start + (slot + 1) * window.unit,
+        })
+        .to_string())
+    }
+
+    // Verifier: confere a prova de janela contra os valores revelados na
+    // apresentação (start_time, unit_of_time, time_window e root_merkle_L da
+    // mesma credencial). Não substitui verifyPresentation, que continua
+    // necessária para a prova AnonCreds.
+    // opts_json: { atTime?: epoch s, toleranceSlots?: n (padrão 0) }.
+    // Retorna { valid, slot, expectedSlot, reason? }.
+    #[napi]
+    pub fn time_window_verify(
+        &self,
+        presentation_request_json: String,
+        presentation_json: String,
+        window_proof_json: String,
+        opts_json: Option<String>,
+    ) -> Result<String> {
+        let pres_req: Value = serde_json::from_str(&presentation_request_json)
+            .map_err(|e| Error::from_reason(format!("Erro Request JSON: {}", e)))?;
+        let presentation: Value = serde_json::from_str(&presentation_json)
+            .map_err(|e| Error::from_reason(format!("Erro Presentation JSON: {}", e)))?;
+        let proof: WindowProof = serde_json::from_str(&window_proof_json)
+            .map_err(|e| napi_err("TimeWindowProofInvalid", e.to_string()))?;
+        let opts: VerifyOptions = match opts_json.as_deref() {
+            Some(s) if !s.trim().is_empty() => serde_json::from_str(s)
+                .map_err(|e| napi_err("TimeWindowParamsInvalid", e.to_string()))?,
+            _ => VerifyOptions::default(),
+        };
+
+        let fail = |reason: &str, expected: Option<i64>| {
+            json!({
+                "valid": false,
+                "slot": proof.slot,
+                "expectedSlot": expected,
+                "reason": reason,
+            })
+            .to_string()
+        };
+
+        let revealed = revealed_by_sub_proof(&pres_req, &presentation);
+        let Some(values) = revealed
+            .values()
+            .find(|v| control_str(v, "root_merkle_L") == Some(proof.root_merkle_l.as_str()))
+        else {
+            return Ok(fail("RootNotRevealed", None));
+        };
+        let window = match (
+            control_str(values, "start_time"),
+            control_str(values, "unit_of_time"),
+            control_str(values, "time_window"),
+        ) {
+            (Some(s), Some(u), Some(w)) => Window::parse(s, u, w),
+            _ => return Ok(fail("WindowNotRevealed", None)),
+        };
+        let Some(window) = window else {
+            return Ok(fail("WindowInvalid", None));
+        };
+
+        let expected = window.slot_at(opts.at_time.unwrap_or_else(now_ts))?;
+        if proof.slot < 0 || proof.slot >= window.slots {
+            return Ok(fail("SlotOutOfWindow", Some(expected)));
+        }
+        if proof.slot.abs_diff(expected) > opts.tolerance_slots.unwrap_or(0) as u64 {
+            return Ok(fail("SlotMismatch", Some(expected)));
+        }
+
+        let root = from_hex32(&proof.root_merkle_l);
+        let leaf = from_hex32(&proof.leaf);
+        let path: Option<Vec<H256>> = proof.path.iter().map(|p| from_hex32(p)).collect();
+        let (Some(root), Some(leaf), Some(path)) = (root, leaf, path) else {
+            return Err(napi_err(
+                "TimeWindowProofInvalid",
+                "leaf/path/rootMerkleL devem ser sha256 em hex",
+            ));
+        };
+        if root_from_path(&leaf, proof.slot, window.slots, &path) != Some(root) {
+            return Ok(fail("MerklePathInvalid", Some(expected)));
+        }
+
+        Ok(json!({
+            "valid": true,
+            "slot": proof.slot,
+            "expectedSlot": expected,
+        })
+        .to_string())
+    }
+
+    // Holder: destrói o seed da janela de uma credencial. Depois disso nenhuma
+    // prova de slot pode ser gerada. Retorna false se já estava revogada.
+    #[napi]
+    pub async fn time_window_revoke(&self, credential_id: String) -> Result<bool> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let values = load_credential_values(&mut session, &credential_id).await?;
+        let root = control_str(&values, "root_merkle_L").unwrap_or_default();
+        let mut rec = find_seed_record(&mut session, root).await?.ok_or_else(|| {
+            napi_err(
+                "TimeWindowSeedNotFound",
+                format!(
+                    "Credencial {} sem seed de janela nesta wallet",
+                    credential_id
+                ),
+            )
+        })?;
+        if rec.revoked_at.is_some() {
+            return Ok(false);
+        }
+        rec.seed.clear();
+        rec.revoked_at = Some(now_ts());
+        save_seed_record(&mut session, &rec).await?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+        Ok(true)
+    }
+}
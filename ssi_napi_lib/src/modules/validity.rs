@@ -0,0 +1,384 @@
+// src/modules/validity.rs
+//
+// Janela de validade de credenciais no lado do holder. AnonCreds não tem
+// expiração: a janela é derivada dos valores da credencial.
+//
+// 1) CONTROL_ATTRS (schemas revogáveis): start_time + time_window * unit_of_time
+// 2) Atributos de data configuráveis (config "credential_validity_attrs"):
+//    validFrom (padrão valid_from, issue_date, issuance_date) e
+//    validUntil (padrão expiry_date, expiration_date, valid_until)
+//
+// Se as duas fontes existirem vale a janela mais restritiva. Datas aceitas:
+// epoch (s ou ms), YYYYMMDD, YYYY-MM-DD e YYYY-MM-DDTHH:MM:SS[Z|±HH:MM] (UTC).
+// Data sem hora em validUntil vale até o fim do dia. Unidades de tempo têm
+// tamanho fixo (mês = 30 dias, ano = 365 dias), como os slots do time window.
+
+use crate::modules::common::{
+    iso_date_to_epoch_days, napi_err, now_ts, remove_if_exists, tag_value, CONFIG_CATEGORY,
+    KEY_VALIDITY_ATTRS,
+};
+use crate::modules::credentials::is_superseded;
+use crate::IndyAgent;
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+pub(crate) const STATUS_VALID: &str = "valid";
+pub(crate) const STATUS_EXPIRED: &str = "expired";
+pub(crate) const STATUS_NOT_YET_VALID: &str = "not_yet_valid";
+
+const DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct ValidityAttrs {
+    #[serde(default = "default_valid_from")]
+    pub valid_from: Vec<String>,
+    #[serde(default = "default_valid_until")]
+    pub valid_until: Vec<String>,
+}
+
+fn default_valid_from() -> Vec<String> {
+    vec![
+        "valid_from".into(),
+        "issue_date".into(),
+        "issuance_date".into(),
+    ]
+}
+
+fn default_valid_until() -> Vec<String> {
+    vec![
+        "expiry_date".into(),
+        "expiration_date".into(),
+        "valid_until".into(),
+    ]
+}
+
+impl Default for ValidityAttrs {
+    fn default() -> Self {
+        Self {
+            valid_from: default_valid_from(),
+            valid_until: default_valid_until(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Validity {
+    pub status: &'static str,
+    pub valid_from: Option<i64>,
+    pub valid_until: Option<i64>,
+    // "control_attrs" | "attrs" | "control_attrs+attrs" | None (sem janela)
+    pub source: Option<String>,
+}
+
+pub(crate) async fn load_validity_attrs(
+    session: &mut aries_askar::Session,
+) -> Result<ValidityAttrs> {
+    let entry = session
+        .fetch(CONFIG_CATEGORY, KEY_VALIDITY_ATTRS, false)
+        .await
+        .map_err(|e| Error::from_reason(format!("Erro fetch config: {}", e)))?;
+    Ok(entry
+        .and_then(|e| serde_json::from_slice(&e.value).ok())
+        .unwrap_or_default())
+}
+
+// (epoch s, só data?)
+pub(crate) fn parse_time(s: &str) -> Option<(i64, bool)> {
+    let s = s.trim();
+    if s.is_empty() || !s.is_ascii() {
+        return None;
+    }
+    if s.bytes().all(|b| b.is_ascii_digit()) {
+        if s.len() == 8 {
+            let iso = format!("{}-{}-{}", &s[0..4], &s[4..6], &s[6..8]);
+            return iso_date_to_epoch_days(&iso).map(|d| (d * DAY, true));
+        }
+        let n: i64 = s.parse().ok()?;
+        // 13 dígitos ou mais: milissegundos
+        return Some((if n > 99_999_999_999 { n / 1000 } else { n }, false));
+    }
+    if s.len() < 10 {
+        return None;
+    }
+    let day = iso_date_to_epoch_days(&s[0..10])? * DAY;
+    if s.len() == 10 {
+        return Some((day, true));
+    }
+    if s.len() < 19 || !matches!(&s[10..11], "T" | " ") {
+        return None;
+    }
+    let hh: i64 = s[11..13].parse().ok()?;
+    let mm: i64 = s[14..16].parse().ok()?;
+    let ss: i64 = s[17..19].parse().ok()?;
+    let mut t = day + hh * 3600 + mm * 60 + ss;
+
+    // fração de segundo ignorada; fuso ±HH:MM convertido para UTC
+    let rest = s[19..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    if rest.len() == 6 && (rest.starts_with('+') || rest.starts_with('-')) {
+        let oh: i64 = rest[1..3].parse().ok()?;
+        let om: i64 = rest[4..6].parse().ok()?;
+        let off = oh * 3600 + om * 60;
+        t += if rest.starts_with('+') { -off } else { off };
+    } else if !rest.is_empty() && rest != "Z" {
+        return None;
+    }
+    Some((t, false))
+}
+
+// Tamanho da unidade em segundos (en/pt ou número de segundos).
+pub(crate) fn unit_seconds(unit: &str) -> Option<i64> {
+    let u = unit.trim().to_lowercase();
+    if let Ok(n) = u.parse::<i64>() {
+        return (n > 0).then_some(n);
+    }
+    let secs = match u.as_str() {
+        "s" | "sec" | "second" | "seconds" | "segundo" | "segundos" => 1,
+        "min" | "minute" | "minutes" | "minuto" | "minutos" => 60,
+        "h" | "hour" | "hours" | "hora" | "horas" => 3600,
+        "d" | "day" | "days" | "dia" | "dias" => DAY,
+        "week" | "weeks" | "semana" | "semanas" => 7 * DAY,
+        "month" | "months" | "mes" | "mês" | "meses" => 30 * DAY,
+        "y" | "year" | "years" | "ano" | "anos" => 365 * DAY,
+        _ => return None,
+    };
+    Some(secs)
+}
+
+fn raw_str<'a>(values_raw: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    values_raw
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .and_then(|(_, v)| v.as_str())
+}
+
+// Janela dos CONTROL_ATTRS; None se faltar algum ou for inválido.
+pub(crate) fn control_window(values_raw: &Map<String, Value>) -> Option<(i64, i64)> {
+    let (start, _) = parse_time(raw_str(values_raw, "start_time")?)?;
+    let unit = unit_seconds(raw_str(values_raw, "unit_of_time")?)?;
+    let window: i64 = raw_str(values_raw, "time_window")?.trim().parse().ok()?;
+    if window <= 0 {
+        return None;
+    }
+    Some((start, start.checked_add(window.checked_mul(unit)?)?))
+}
+
+pub(crate) fn credential_validity(
+    values_raw: &Map<String, Value>,
+    attrs: &ValidityAttrs,
+    now: i64,
+) -> Validity {
+    let mut from: Option<i64> = None;
+    let mut until: Option<i64> = None;
+    let mut sources = Vec::new();
+
+    if let Some((f, u)) = control_window(values_raw) {
+        from = Some(f);
+        until = Some(u);
+        sources.push("control_attrs");
+    }
+
+    let attr_from = attrs
+        .valid_from
+        .iter()
+        .find_map(|a| parse_time(raw_str(values_raw, a)?))
+        .map(|(t, _)| t);
+    let attr_until = attrs
+        .valid_until
+        .iter()
+        .find_map(|a| parse_time(raw_str(values_raw, a)?))
+        .map(|(t, date_only)| if date_only { t + DAY - 1 } else { t });
+    if attr_from.is_some() || attr_until.is_some() {
+        sources.push("attrs");
+    }
+    if let Some(f) = attr_from {
+        from = Some(from.map_or(f, |x| x.max(f)));
+    }
+    if let Some(u) = attr_until {
+        until = Some(until.map_or(u, |x| x.min(u)));
+    }
+
+    let status = match (from, until) {
+        (Some(f), _) if now < f => STATUS_NOT_YET_VALID,
+        (_, Some(u)) if now > u => STATUS_EXPIRED,
+        _ => STATUS_VALID,
+    };
+    Validity {
+        status,
+        valid_from: from,
+        valid_until: until,
+        source: (!sources.is_empty()).then(|| sources.join("+")),
+    }
+}
+
+// values_raw ({ atributo: raw }) a partir do JSON da credencial armazenada.
+pub(crate) fn values_raw_of(credential: &Value) -> Map<String, Value> {
+    credential["values"]
+        .as_object()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.get("raw")?.clone())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[napi]
+impl IndyAgent {
+    // =========================================================================
+    //  VALIDADE DE CREDENCIAIS (HOLDER)
+    // =========================================================================
+
+    // config_json: { validFrom?: string[], validUntil?: string[] } (nomes de
+    // atributos, em ordem de preferência). Campo omitido volta ao padrão.
+    #[napi]
+    pub async fn set_validity_attributes(&self, config_json: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let attrs: ValidityAttrs = serde_json::from_str(&config_json)
+            .map_err(|e| napi_err("ValidityConfigInvalid", e.to_string()))?;
+        if attrs
+            .valid_from
+            .iter()
+            .chain(attrs.valid_until.iter())
+            .any(|a| a.trim().is_empty())
+        {
+            return Err(napi_err("ValidityConfigInvalid", "nome de atributo vazio"));
+        }
+        let json = serde_json::to_string(&attrs)
+            .map_err(|e| Error::from_reason(format!("Erro serializar config: {}", e)))?;
+
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        remove_if_exists(&mut session, CONFIG_CATEGORY, KEY_VALIDITY_ATTRS).await?;
+        session
+            .insert(
+                CONFIG_CATEGORY,
+                KEY_VALIDITY_ATTRS,
+                json.as_bytes(),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro salvar config: {}", e)))?;
+        session
+            .commit()
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro commit: {}", e)))?;
+        Ok(json)
+    }
+
+    #[napi]
+    pub async fn get_validity_attributes(&self) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+        let attrs = load_validity_attrs(&mut session).await?;
+        serde_json::to_string(&attrs)
+            .map_err(|e| Error::from_reason(format!("Erro serializar config: {}", e)))
+    }
+
+    // { status, valid_from, valid_until, source } de uma credencial armazenada.
+    #[napi]
+    pub async fn get_credential_validity(&self, credential_id: String) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let entry = session
+            .fetch("credential", &credential_id, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch credencial: {}", e)))?
+            .ok_or_else(|| {
+                napi_err(
+                    "CredentialNotFound",
+                    format!("Credencial não encontrada: {}", credential_id),
+                )
+            })?;
+        let attrs = load_validity_attrs(&mut session).await?;
+        let cred: Value = serde_json::from_slice(&entry.value).unwrap_or_default();
+        let validity = credential_validity(&values_raw_of(&cred), &attrs, now_ts());
+        serde_json::to_string(&validity)
+            .map_err(|e| Error::from_reason(format!("Erro serializar validade: {}", e)))
+    }
+
+    // Credenciais que expiram nos próximos within_days dias (lembrete de
+    // renovação), ordenadas por valid_until. include_expired inclui as já
+    // expiradas. Item: { id_local, alias?, schema_id, cred_def_id, valid_until,
+    // days_left, status }.
+    #[napi]
+    pub async fn list_expiring_credentials(
+        &self,
+        within_days: u32,
+        include_expired: Option<bool>,
+    ) -> Result<String> {
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| Error::from_reason("Wallet fechada!"))?;
+        let mut session = store
+            .session(None)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro sessão: {}", e)))?;
+
+        let attrs = load_validity_attrs(&mut session).await?;
+        let entries = session
+            .fetch_all(Some("credential"), None, None, None, false, false)
+            .await
+            .map_err(|e| Error::from_reason(format!("Erro fetch: {}", e)))?;
+
+        let now = now_ts();
+        let horizon = now + within_days as i64 * DAY;
+        let include_expired = include_expired.unwrap_or(false);
+
+        let mut out: Vec<(i64, Value)> = Vec::new();
+        for entry in entries.iter().filter(|e| !is_superseded(&e.tags)) {
+            let cred: Value = match serde_json::from_slice(&entry.value) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            let v = credential_validity(&values_raw_of(&cred), &attrs, now);
+            let until = match v.valid_until {
+                Some(u) if u <= horizon => u,
+                _ => continue,
+            };
+            if v.status == STATUS_EXPIRED && !include_expired {
+                continue;
+            }
+            let mut item = json!({
+                "id_local": entry.name,
+                "schema_id": tag_value(&entry.tags, "schema_id").unwrap_or_default(),
+                "cred_def_id": tag_value(&entry.tags, "cred_def_id").unwrap_or_default(),
+                "valid_until": until,
+                "days_left": (until - now).div_euclid(DAY),
+                "status": v.status,
+            });
+            if let Some(a) = tag_value(&entry.tags, "alias") {
+                item["alias"] = json!(a);
+            }
+            out.push((until, item));
+        }
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let items: Vec<Value> = out.into_iter().map(|(_, v)| v).collect();
+        serde_json::to_string(&items)
+            .map_err(|e| Error::from_reason(format!("Erro serializar lista: {}", e)))
+    }
+}
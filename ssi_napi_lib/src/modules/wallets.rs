@@ -98,6 +98,7 @@ impl IndyAgent {
 
         let config_uri = format!("sqlite://{}", path);
         self.connection_uri = config_uri.clone();
+        self.link_secrets.lock().unwrap().clear();
 
         let sidecar_path = sidecar_path_for(&path);
         let sc = if Path::new(&sidecar_path).exists() {
@@ -185,6 +186,9 @@ impl IndyAgent {
         // Libera o Pool de conexão com o Ledger
         self.pool = None;
 
+        // Link secrets pertencem à wallet fechada
+        self.link_secrets.lock().unwrap().clear();
+
         Ok(true)
     }
 
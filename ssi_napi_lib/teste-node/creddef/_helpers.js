@@ -0,0 +1,2 @@
+// teste-node/creddef/_helpers.js
+module.exports = require("../credentials/_helpers");
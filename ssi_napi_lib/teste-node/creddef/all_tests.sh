@@ -9,6 +9,10 @@ node teste-node/creddef/test_creddef_01_ledger_smoke.js
 node teste-node/creddef/test_creddef_02_ledger_fetch_negative.js
 node teste-node/creddef/test_creddef_03_idempotent_same_tag.js
 node teste-node/creddef/test_creddef_04_id_format_seqno.js
+node teste-node/creddef/test_creddef_05_registry.js
+node teste-node/creddef/test_creddef_06_private_export.js
+node teste-node/creddef/test_creddef_07_issuer_manifest.js
+node teste-node/creddef/test_creddef_08_rotate.js
 
 echo "✅ SUÍTE CREDDEF completa."
 
@@ -0,0 +1,121 @@
+// teste-node/creddef/test_creddef_05_registry.js
+
+const fs = require("fs");
+const path = require("path");
+const { errCode } = require("./_helpers");
+
+let IndyAgent;
+try { IndyAgent = require(path.join(process.cwd(), "index.js")).IndyAgent; }
+catch { IndyAgent = require(path.join(process.cwd(), "index.node")).IndyAgent; }
+
+const NETWORK_CONFIG = {
+  genesisFile: "./von_genesis.txn",
+  trusteeSeed: "000000000000000000000000Trustee1",
+  trusteeDid: "V4SGRU86Z58d6TV7PBUe6f",
+};
+
+function assert(c, m) { if (!c) throw new Error(m); }
+
+function fn(agent, camel, snake) {
+  const f = agent[camel] || agent[snake];
+  if (!f) throw new Error(`Método não encontrado: ${camel}/${snake}`);
+  return f.bind(agent);
+}
+
+(async () => {
+  const agent = new IndyAgent();
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const dbPath = process.env.WALLET_PATH || path.join(__dirname, "..", "wallets", "test_wallet_creddef_01.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CREDDEF 05: registro local (list/get/export/delete)");
+  console.log("Config:", { dbPath, genesisAbs });
+
+  assert(fs.existsSync(genesisAbs), "Genesis ausente.");
+  assert(fs.existsSync(dbPath), "Wallet ausente.");
+
+  await agent.walletOpen(dbPath, pass);
+  await agent.connectNetwork(genesisAbs);
+
+  try {
+    const importDidFromSeed = fn(agent, "importDidFromSeed", "import_did_from_seed");
+    const [issuerDid] = await importDidFromSeed(NETWORK_CONFIG.trusteeSeed);
+    assert(issuerDid === NETWORK_CONFIG.trusteeDid, `Trustee DID inesperado: ${issuerDid}`);
+
+    const schemaName = `SchemaRegistry_${Date.now()}`;
+    const schemaVersion = `1.${Math.floor(Date.now() / 1000)}`;
+    const schemaId = await fn(agent, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, schemaName, schemaVersion, ["nome", "cpf"]
+    );
+
+    const tag = `TAG_REG_${Math.floor(Date.now() / 1000)}`;
+    const credDefId = await fn(agent, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, tag
+    );
+    console.log("credDefId:", credDefId);
+
+    // 1) list
+    console.log("1) listCredDefs...");
+    const list = JSON.parse(await fn(agent, "listCredDefs", "list_cred_defs")());
+    const item = list.find((x) => x.credDefId === credDefId);
+    assert(item, "credDef não aparece em listCredDefs");
+    assert(item.schemaName === schemaName, `schemaName inesperado: ${item.schemaName}`);
+    assert(item.schemaVersion === schemaVersion, `schemaVersion inesperado: ${item.schemaVersion}`);
+    assert(item.tag === tag, `tag inesperada: ${item.tag}`);
+    assert(item.supportRevocation === false, "supportRevocation deveria ser false");
+    assert(item.hasPrivate === true, "hasPrivate deveria ser true");
+    assert(item.issuedCount === 0, "issuedCount deveria ser 0");
+    assert(Number(item.ledgerSeqNo) > 0, `ledgerSeqNo ausente: ${item.ledgerSeqNo}`);
+
+    // 2) get + export
+    console.log("2) getCredDef / exportCredDefPublic...");
+    const got = JSON.parse(await fn(agent, "getCredDef", "get_cred_def")(credDefId));
+    assert(got.credDef && got.credDef.value, "getCredDef sem credDef pública");
+
+    const exported = JSON.parse(await fn(agent, "exportCredDefPublic", "export_cred_def_public")(credDefId));
+    assert(exported.id === credDefId, "export sem id");
+    assert(exported.value && exported.value.primary, "export sem value.primary");
+    assert(!JSON.stringify(exported).includes("\"p_key\""), "export vazou material privado");
+
+    // 3) delete bloqueado por oferta pendente
+    console.log("3) deleteCredDef com oferta pendente...");
+    const offerId = `offer-reg-${Date.now()}`;
+    await fn(agent, "createCredentialOffer", "create_credential_offer")(credDefId, offerId);
+
+    const after = JSON.parse(await fn(agent, "listCredDefs", "list_cred_defs")());
+    const item2 = after.find((x) => x.credDefId === credDefId);
+    assert(item2.offersPending === 1, `offersPending esperado 1, veio ${item2.offersPending}`);
+
+    let blocked = false;
+    try {
+      await fn(agent, "deleteCredDef", "delete_cred_def")(credDefId);
+    } catch (e) {
+      blocked = errCode(e) === "CredDefInUse";
+    }
+    assert(blocked, "deleteCredDef deveria falhar com CredDefInUse");
+
+    // 4) remove oferta e deleta
+    console.log("4) remove oferta e deleta credDef...");
+    await fn(agent, "deleteCredentialOffer", "delete_credential_offer")(offerId);
+    const del = JSON.parse(await fn(agent, "deleteCredDef", "delete_cred_def")(credDefId));
+    assert(del.ok === true && del.removedPrivate === true, "deleteCredDef retorno inesperado");
+
+    const finalList = JSON.parse(await fn(agent, "listCredDefs", "list_cred_defs")());
+    assert(!finalList.some((x) => x.credDefId === credDefId), "credDef ainda listada após delete");
+
+    let notFound = false;
+    try {
+      await fn(agent, "getCredDef", "get_cred_def")(credDefId);
+    } catch (e) {
+      notFound = errCode(e) === "CredDefNotFound";
+    }
+    assert(notFound, "getCredDef deveria falhar com CredDefNotFound");
+
+    console.log("✅ OK: registro local de cred defs validado.");
+  } finally {
+    await agent.walletClose();
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CREDDEF 05:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,121 @@
+// teste-node/creddef/test_creddef_06_private_export.js
+
+const fs = require("fs");
+const path = require("path");
+const { errCode } = require("./_helpers");
+
+let IndyAgent;
+try { IndyAgent = require(path.join(process.cwd(), "index.js")).IndyAgent; }
+catch { IndyAgent = require(path.join(process.cwd(), "index.node")).IndyAgent; }
+
+const NETWORK_CONFIG = {
+  genesisFile: "./von_genesis.txn",
+  trusteeSeed: "000000000000000000000000Trustee1",
+  trusteeDid: "V4SGRU86Z58d6TV7PBUe6f",
+};
+
+function assert(c, m) { if (!c) throw new Error(m); }
+
+function fn(agent, camel, snake) {
+  const f = agent[camel] || agent[snake];
+  if (!f) throw new Error(`Método não encontrado: ${camel}/${snake}`);
+  return f.bind(agent);
+}
+
+(async () => {
+  const agent = new IndyAgent();
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const dbPath = process.env.WALLET_PATH || path.join(__dirname, "..", "wallets", "test_wallet_creddef_01.db");
+  const standbyPath = path.join(__dirname, "..", "wallets", "test_wallet_creddef_standby.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+  const exportPass = "senha-export-creddef";
+
+  console.log("🚀 TESTE CREDDEF 06: export/import da cred def privada (hot-standby)");
+  console.log("Config:", { dbPath, standbyPath, genesisAbs });
+
+  assert(fs.existsSync(genesisAbs), "Genesis ausente.");
+  assert(fs.existsSync(dbPath), "Wallet ausente.");
+
+  // 1) Emissor primário: cria e exporta
+  await agent.walletOpen(dbPath, pass);
+  await agent.connectNetwork(genesisAbs);
+
+  let credDefId;
+  let pkg;
+  try {
+    const [issuerDid] = await fn(agent, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaId = await fn(agent, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, `SchemaBackup_${Date.now()}`, `1.${Math.floor(Date.now() / 1000)}`, ["nome", "cpf"]
+    );
+    credDefId = await fn(agent, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_BKP_${Math.floor(Date.now() / 1000)}`
+    );
+    console.log("credDefId:", credDefId);
+
+    console.log("1) exportCredDefPrivate...");
+    pkg = await fn(agent, "exportCredDefPrivate", "export_cred_def_private")(credDefId, exportPass);
+    const p = JSON.parse(pkg);
+    assert(p.type === "ssi-creddef-private-export", "tipo de pacote inesperado");
+    assert(p.credDefId === credDefId, "credDefId do pacote divergente");
+    assert(!pkg.includes("\"p_key\""), "pacote vazou chave privada em claro");
+  } finally {
+    await agent.walletClose();
+  }
+
+  // 2) Emissor standby: wallet nova, importa
+  for (const f of [standbyPath, `${standbyPath}.kdf.json`, `${standbyPath}-shm`, `${standbyPath}-wal`]) {
+    try { fs.unlinkSync(f); } catch (_) {}
+  }
+  await agent.walletCreate(standbyPath, pass);
+  await agent.walletOpen(standbyPath, pass);
+
+  try {
+    const importFn = fn(agent, "importCredDefPrivate", "import_cred_def_private");
+
+    console.log("2) import com senha errada...");
+    let wrongPass = false;
+    try { await importFn(pkg, "senha-errada"); } catch (e) { wrongPass = errCode(e) === "ExportDecryptFailed"; }
+    assert(wrongPass, "import com senha errada deveria falhar com ExportDecryptFailed");
+
+    console.log("2.1) parâmetros de KDF fora dos limites...");
+    for (const patch of [{ m_cost_kib: 64 * 1024 * 1024 }, { t_cost: 0 }, { kdf: "scrypt" }, { version: 2 }]) {
+      const p2 = JSON.parse(pkg);
+      Object.assign(p2.sealed, patch);
+      let code = null;
+      try { await importFn(JSON.stringify(p2), exportPass); } catch (e) { code = errCode(e); }
+      assert(code === "ExportFormatInvalid", `${JSON.stringify(patch)}: esperado ExportFormatInvalid, veio ${code}`);
+    }
+
+    console.log("3) import válido...");
+    const r = JSON.parse(await importFn(pkg, exportPass));
+    assert(r.ok === true && r.credDefId === credDefId, "import retorno inesperado");
+    assert(r.replaced === false, "primeiro import não deveria substituir");
+
+    console.log("4) import repetido sem overwrite...");
+    let dup = false;
+    try { await importFn(pkg, exportPass); } catch (e) { dup = errCode(e) === "CredDefAlreadyExists"; }
+    assert(dup, "import repetido deveria falhar com CredDefAlreadyExists");
+
+    console.log("5) pacote adulterado...");
+    const tampered = JSON.parse(pkg);
+    tampered.credDefId = `${credDefId}_x`;
+    let bad = false;
+    try { await importFn(JSON.stringify(tampered), exportPass, true); } catch (e) { bad = errCode(e) === "CredDefPackageInvalid"; }
+    assert(bad, "pacote adulterado deveria falhar com CredDefPackageInvalid");
+
+    console.log("6) standby emite oferta com a cred def importada...");
+    const list = JSON.parse(await fn(agent, "listCredDefs", "list_cred_defs")());
+    assert(list.some((x) => x.credDefId === credDefId && x.hasPrivate), "cred def importada não listada");
+    const offer = JSON.parse(
+      await fn(agent, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-standby-${Date.now()}`)
+    );
+    assert(offer.cred_def_id === credDefId, "oferta com cred_def_id divergente");
+
+    console.log("✅ OK: export/import da cred def privada validado.");
+  } finally {
+    await agent.walletClose();
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CREDDEF 06:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,101 @@
+// teste-node/creddef/test_creddef_07_issuer_manifest.js
+
+const fs = require("fs");
+const path = require("path");
+const { errCode } = require("./_helpers");
+
+let IndyAgent;
+try { IndyAgent = require(path.join(process.cwd(), "index.js")).IndyAgent; }
+catch { IndyAgent = require(path.join(process.cwd(), "index.node")).IndyAgent; }
+
+const NETWORK_CONFIG = {
+  genesisFile: "./von_genesis.txn",
+  trusteeSeed: "000000000000000000000000Trustee1",
+  trusteeDid: "V4SGRU86Z58d6TV7PBUe6f",
+};
+
+function assert(c, m) { if (!c) throw new Error(m); }
+
+function fn(agent, camel, snake) {
+  const f = agent[camel] || agent[snake];
+  if (!f) throw new Error(`Método não encontrado: ${camel}/${snake}`);
+  return f.bind(agent);
+}
+
+(async () => {
+  const agent = new IndyAgent();
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const dbPath = process.env.WALLET_PATH || path.join(__dirname, "..", "wallets", "test_wallet_creddef_01.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CREDDEF 07: publicação em lote via manifesto");
+  console.log("Config:", { dbPath, genesisAbs });
+
+  assert(fs.existsSync(genesisAbs), "Genesis ausente.");
+  assert(fs.existsSync(dbPath), "Wallet ausente.");
+
+  await agent.walletOpen(dbPath, pass);
+  await agent.connectNetwork(genesisAbs);
+
+  try {
+    const [issuerDid] = await fn(agent, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const publish = fn(agent, "publishIssuerManifest", "publish_issuer_manifest");
+
+    const stamp = Math.floor(Date.now() / 1000);
+    const manifest = {
+      issuerDid,
+      schemas: [
+        { ref: "regiao", name: `RegiaoAerea_${stamp}`, version: "1.0", attrNames: ["regiao", "setor"] },
+        { ref: "slot", templateId: "uam.vertiport_slot.v1" },
+      ],
+      credDefs: [
+        { schemaRef: "regiao", tag: `REG_${stamp}` },
+        { schemaRef: "slot", tag: `SLOT_${stamp}` },
+      ],
+    };
+
+    // 1) manifesto inválido não escreve nada
+    console.log("1) manifesto inválido...");
+    let invalid = false;
+    try {
+      await publish(JSON.stringify({ issuerDid, schemas: [], credDefs: [{ schemaRef: "nao_existe" }] }));
+    } catch (e) { invalid = errCode(e) === "ManifestInvalid"; }
+    assert(invalid, "manifesto inválido deveria falhar com ManifestInvalid");
+
+    // 2) dryRun
+    console.log("2) dryRun...");
+    const dry = JSON.parse(await publish(JSON.stringify({ ...manifest, dryRun: true })));
+    assert(dry.dryRun === true, "dryRun não refletido");
+    assert(dry.schemas[0].status === "planned", `schema novo deveria estar planned: ${dry.schemas[0].status}`);
+    assert(dry.summary.published === 0, "dryRun não pode publicar");
+
+    // 3) publicação real
+    console.log("3) publicação...");
+    const r1 = JSON.parse(await publish(JSON.stringify(manifest)));
+    console.log("summary:", r1.summary);
+    assert(r1.ok === true, `publicação com erros: ${JSON.stringify(r1)}`);
+    assert(r1.schemas[0].status === "published" && r1.schemas[0].seqNo > 0, "schema regiao não publicado");
+    assert(["published", "skipped"].includes(r1.schemas[1].status), "schema de template com status inesperado");
+    for (const cd of r1.credDefs) {
+      assert(cd.status === "published", `cred def não publicada: ${JSON.stringify(cd)}`);
+      assert(String(cd.credDefId).includes(":3:CL:"), "credDefId ausente no relatório");
+      assert(cd.ledgerSeqNo > 0, "ledgerSeqNo ausente no relatório");
+    }
+
+    // 4) idempotência
+    console.log("4) reexecução idempotente...");
+    const r2 = JSON.parse(await publish(JSON.stringify(manifest)));
+    assert(r2.ok === true, "reexecução com erros");
+    assert(r2.summary.published === 0, "reexecução não deveria publicar nada");
+    assert(r2.schemas.every((s) => s.status === "skipped" && s.reason === "already_on_ledger"), "schemas deveriam ser skipped");
+    assert(r2.credDefs.every((c) => c.status === "skipped" && c.reason === "already_in_wallet"), "cred defs deveriam ser skipped");
+    assert(r2.credDefs[0].credDefId === r1.credDefs[0].credDefId, "credDefId mudou entre execuções");
+
+    console.log("✅ OK: manifesto do emissor validado.");
+  } finally {
+    await agent.walletClose();
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CREDDEF 07:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,112 @@
+// teste-node/creddef/test_creddef_08_rotate.js
+
+const fs = require("fs");
+const path = require("path");
+const { errCode } = require("./_helpers");
+
+let IndyAgent;
+try { IndyAgent = require(path.join(process.cwd(), "index.js")).IndyAgent; }
+catch { IndyAgent = require(path.join(process.cwd(), "index.node")).IndyAgent; }
+
+const NETWORK_CONFIG = {
+  genesisFile: "./von_genesis.txn",
+  trusteeSeed: "000000000000000000000000Trustee1",
+  trusteeDid: "V4SGRU86Z58d6TV7PBUe6f",
+};
+
+function assert(c, m) { if (!c) throw new Error(m); }
+
+function fn(agent, camel, snake) {
+  const f = agent[camel] || agent[snake];
+  if (!f) throw new Error(`Método não encontrado: ${camel}/${snake}`);
+  return f.bind(agent);
+}
+
+(async () => {
+  const agent = new IndyAgent();
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const dbPath = process.env.WALLET_PATH || path.join(__dirname, "..", "wallets", "test_wallet_creddef_01.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CREDDEF 08: rotação de cred def (tag versionada + ofertas movidas)");
+  console.log("Config:", { dbPath, genesisAbs });
+
+  assert(fs.existsSync(genesisAbs), "Genesis ausente.");
+  assert(fs.existsSync(dbPath), "Wallet ausente.");
+
+  await agent.walletOpen(dbPath, pass);
+  await agent.connectNetwork(genesisAbs);
+
+  try {
+    const [issuerDid] = await fn(agent, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+
+    const schemaName = `SchemaRotate_${Date.now()}`;
+    const schemaVersion = `1.${Math.floor(Date.now() / 1000)}`;
+    const schemaId = await fn(agent, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, schemaName, schemaVersion, ["nome", "licenca"]
+    );
+    const tag = `TAG_ROT_${Math.floor(Date.now() / 1000)}`;
+    const oldId = await fn(agent, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, tag
+    );
+
+    const createOffer = fn(agent, "createCredentialOffer", "create_credential_offer");
+    const pendingId = `offer-rot-${Date.now()}`;
+    await createOffer(oldId, pendingId);
+
+    // 1) rotação
+    console.log("1) rotateCredDef...");
+    const rot = JSON.parse(await fn(agent, "rotateCredDef", "rotate_cred_def")(oldId));
+    assert(rot.ok === true, "rotação falhou");
+    assert(rot.tag === `${tag}_v2`, `tag versionada inesperada: ${rot.tag}`);
+    assert(rot.newCredDefId.endsWith(`:${tag}_v2`), `novo id inesperado: ${rot.newCredDefId}`);
+    const movedOffer = rot.movedOffers.find((m) => m.offerId === pendingId);
+    assert(movedOffer, "oferta pendente não foi movida");
+    assert(movedOffer.threadId !== movedOffer.oldThreadId && JSON.parse(movedOffer.offerJson).nonce === movedOffer.threadId,
+      "oferta movida deveria ter nonce novo");
+    const oldEx = JSON.parse(await agent.exchangeGet("issuer", movedOffer.oldThreadId));
+    assert(oldEx.state === "abandoned", `troca antiga deveria ser abandonada: ${oldEx.state}`);
+    const newEx = JSON.parse(await agent.exchangeGet("issuer", movedOffer.threadId));
+    assert(newEx.state === "offer-sent" && newEx.credDefId === rot.newCredDefId && newEx.offerId === pendingId,
+      `troca nova inesperada: ${JSON.stringify(newEx)}`);
+    const listedMoved = JSON.parse(await fn(agent, "listCredentialOffers", "list_credential_offers")())
+      .find((o) => o.id_local === pendingId);
+    assert(listedMoved.expires_at && listedMoved.expires_at === String(newEx.expiresAt), "expires_at da oferta não foi mantido");
+
+    // 2) status no registro
+    console.log("2) status deprecated/active...");
+    const list = JSON.parse(await fn(agent, "listCredDefs", "list_cred_defs")());
+    const oldItem = list.find((x) => x.credDefId === oldId);
+    const newItem = list.find((x) => x.credDefId === rot.newCredDefId);
+    assert(oldItem.status === "deprecated" && oldItem.supersededBy === rot.newCredDefId, "antiga não deprecated");
+    assert(newItem.status === "active" && newItem.supersedes === oldId, "nova não encadeada");
+    assert(newItem.offersPending >= 1, "oferta movida não conta como pendente na nova");
+    assert(oldItem.offersPending === 0, "antiga não deveria manter ofertas pendentes");
+
+    // 3) novas ofertas sempre na cred def ativa
+    console.log("3) createCredentialOffer redireciona para a ativa...");
+    const viaOld = JSON.parse(await createOffer(oldId, `offer-rot-old-${Date.now()}`));
+    assert(viaOld.cred_def_id === rot.newCredDefId, `oferta via id antigo usou ${viaOld.cred_def_id}`);
+    const viaSchema = JSON.parse(await createOffer(schemaId, `offer-rot-schema-${Date.now()}`));
+    assert(viaSchema.cred_def_id === rot.newCredDefId, `oferta via schemaId usou ${viaSchema.cred_def_id}`);
+    const active = await fn(agent, "getActiveCredDef", "get_active_cred_def")(oldId);
+    assert(active === rot.newCredDefId, "getActiveCredDef inesperado");
+
+    // 4) segunda rotação da mesma antiga é recusada; rotação da nova gera _v3
+    console.log("4) rotações encadeadas...");
+    let code = null;
+    try { await fn(agent, "rotateCredDef", "rotate_cred_def")(oldId); } catch (e) { code = errCode(e); }
+    assert(code === "CredDefDeprecated", `esperado CredDefDeprecated, veio ${code}`);
+    const rot2 = JSON.parse(await fn(agent, "rotateCredDef", "rotate_cred_def")(rot.newCredDefId));
+    assert(rot2.tag === `${tag}_v3`, `tag da segunda rotação inesperada: ${rot2.tag}`);
+    const active2 = await fn(agent, "getActiveCredDef", "get_active_cred_def")(oldId);
+    assert(active2 === rot2.newCredDefId, "cadeia superseded_by não seguida até a ativa");
+
+    console.log("✅ OK: rotação de cred def validada.");
+  } finally {
+    await agent.walletClose();
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CREDDEF 08:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
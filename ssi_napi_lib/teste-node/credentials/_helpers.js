@@ -114,6 +114,11 @@ function extractNonce(offerJson) {
   return nonce;
 }
 
+// Código de erro estruturado (napi_err: { ok:false, code, message }); null se ausente.
+function errCode(e) {
+  try { return JSON.parse(e.message).code; } catch { return null; }
+}
+
 module.exports = {
   NETWORK_CONFIG,
   assert,
@@ -126,4 +131,5 @@ module.exports = {
   parseJsonSafe,
   walletCreateOpenIdempotent,
   extractNonce,
+  errCode,
 };
@@ -12,5 +12,17 @@ node teste-node/credentials/test_cred_04_link_secret_idempotent.js
 node teste-node/credentials/test_cred_01_issue_store_e2e.js
 node teste-node/credentials/test_cred_02_store_negative_missing_metadata.js
 node teste-node/credentials/test_cred_05_offers_range.js
+node teste-node/credentials/test_cred_06_typed_values.js
+node teste-node/credentials/test_cred_07_display_overlay.js
+node teste-node/credentials/test_cred_08_link_secrets.js
+node teste-node/credentials/test_cred_09_link_secret_backup.js
+node teste-node/credentials/test_cred_10_exchanges.js
+node teste-node/credentials/test_cred_11_issue_v2.js
+node teste-node/credentials/test_cred_12_batch.js
+node teste-node/credentials/test_cred_13_issued_registry.js
+node teste-node/credentials/test_cred_14_validity.js
+node teste-node/credentials/test_cred_15_time_window.js
+node teste-node/credentials/test_cred_16_renewal.js
+node teste-node/credentials/test_cred_17_offer_expiry.js
 
 echo "✅ SUÍTE CREDENTIALS completa."
@@ -0,0 +1,136 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  errCode,
+} = require("./_helpers");
+
+function epochDays(iso) {
+  return Math.floor(Date.parse(`${iso}T00:00:00Z`) / 86400000);
+}
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_cred_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 06: tipagem de atributos (overlay) + codificação canônica");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+    await holder.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+
+    const schemaName = `SchemaTyped_${Date.now()}`;
+    const schemaVersion = `1.${Math.floor(Date.now() / 1000)}`;
+    const attrs = ["nome", "idade", "validade", "categoria", "ifr", "licenca"];
+
+    console.log("1) Schema no ledger + registro local com overlay de tipos...");
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, schemaName, schemaVersion, attrs
+    );
+    const local = parseJsonSafe(await issuer.schemaSaveLocal(schemaName, schemaVersion, attrs, false, "test"), "local");
+    const reg = await issuer.schemaRegisterFromLocal(genesisAbs, local.id_local, issuerDid);
+    assert(reg.schemaId === schemaId, `schemaId local divergente: ${reg.schemaId} vs ${schemaId}`);
+
+    let badDef = false;
+    try {
+      await issuer.schemaSetAttrTypes(local.id_local, JSON.stringify({ licenca: { type: "string", pattern: "([" } }));
+    } catch (e) { badDef = errCode(e) === "AttrTypeInvalid"; }
+    assert(badDef, "regex inválida deveria falhar com AttrTypeInvalid");
+
+    const typed = parseJsonSafe(await issuer.schemaSetAttrTypes(local.id_local, JSON.stringify({
+      idade: { type: "int", min: 0, max: 130 },
+      validade: { type: "date" },
+      categoria: { type: "enum", values: ["A", "B", "C"] },
+      ifr: { type: "boolean" },
+      licenca: { type: "string", pattern: "^[A-Z]{3}-[0-9]{4}$" },
+    })), "typed");
+    assert(typed.attr_types && typed.attr_types.validade.type === "date", "overlay não persistido");
+
+    console.log("2) CredDef + offer + request...");
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_TYPED_${Math.floor(Date.now() / 1000)}`
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+
+    const createOffer = fn(issuer, "createCredentialOffer", "create_credential_offer");
+    const createRequest = fn(holder, "createCredentialRequest", "create_credential_request");
+    const createCredential = fn(issuer, "createCredential", "create_credential");
+
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    const good = {
+      nome: "Alice",
+      idade: "29",
+      validade: "2030-06-15",
+      categoria: "B",
+      ifr: true,
+      licenca: "BRA-1234",
+    };
+
+    console.log("3) Valores inválidos são recusados...");
+    const bads = [
+      { idade: "vinte" },
+      { idade: "200" },
+      { validade: "15/06/2030" },
+      { validade: "2030-02-30" },
+      { categoria: "Z" },
+      { ifr: "talvez" },
+      { licenca: "bra-12" },
+    ];
+    for (const patch of bads) {
+      const offerJson = await createOffer(credDefId, `offer-typed-bad-${Date.now()}`);
+      const requestJson = await createRequest("default", holderDid, credDefJson, offerJson);
+      let refused = false;
+      try {
+        await createCredential(credDefId, offerJson, requestJson, JSON.stringify({ ...good, ...patch }));
+      } catch (e) { refused = errCode(e) === "AttrValueInvalid"; }
+      assert(refused, `valor inválido aceito: ${JSON.stringify(patch)}`);
+    }
+
+    console.log("4) Valores válidos com codificação canônica...");
+    const offerJson = await createOffer(credDefId, `offer-typed-ok-${Date.now()}`);
+    const requestJson = await createRequest("default", holderDid, credDefJson, offerJson);
+    const cred = parseJsonSafe(
+      await createCredential(credDefId, offerJson, requestJson, JSON.stringify(good)),
+      "credential"
+    );
+    const v = cred.values;
+    assert(v.idade.encoded === "29", `idade.encoded inesperado: ${v.idade.encoded}`);
+    assert(v.validade.raw === "2030-06-15", "validade.raw deveria manter a data ISO");
+    assert(v.validade.encoded === String(epochDays("2030-06-15")), `validade.encoded inesperado: ${v.validade.encoded}`);
+    assert(v.ifr.raw === "true" && v.ifr.encoded === "1", "booleano não canônico");
+    assert(/^[0-9]+$/.test(v.categoria.encoded), "enum não codificado como inteiro");
+
+    console.log("✅ OK: TESTE CRED 06 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 06:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,144 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  errCode,
+} = require("./_helpers");
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_cred_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 07: overlay de exibição (OCA-style) publicado como ATTRIB");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+    await holder.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+
+    const schemaName = `SchemaOverlay_${Date.now()}`;
+    const schemaVersion = `1.${Math.floor(Date.now() / 1000)}`;
+    const attrs = ["nome", "licenca", "validade"];
+
+    console.log("1) Schema + cred def no ledger...");
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, schemaName, schemaVersion, attrs
+    );
+    const local = parseJsonSafe(await issuer.schemaSaveLocal(schemaName, schemaVersion, attrs, false, "test"), "local");
+    await issuer.schemaRegisterFromLocal(genesisAbs, local.id_local, issuerDid);
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_OVL_${Math.floor(Date.now() / 1000)}`
+    );
+
+    console.log("2) Overlays inválidos são recusados...");
+    const bads = [
+      { labels: { "pt-BR": { nome: "Nome" } } },
+      { labels: { "pt-BR": { nome: "Nome" }, en: { nome: "Name" } }, formats: { nome: "rich" } },
+      { labels: { "pt-BR": { naoexiste: "X" }, en: {} } },
+      { labels: { "pt-BR": {}, en: {} }, branding: { primaryColor: "azul" } },
+      { labels: { "pt-BR": {}, en: {} }, extra: true },
+    ];
+    for (const bad of bads) {
+      let refused = false;
+      try { await issuer.overlaySet(schemaId, JSON.stringify(bad)); } catch (e) { refused = errCode(e) === "OverlayInvalid"; }
+      assert(refused, `overlay inválido aceito: ${JSON.stringify(bad)}`);
+    }
+    let badTarget = false;
+    try { await issuer.overlaySet("nao-e-um-id", "{}"); } catch (e) { badTarget = errCode(e) === "OverlayTargetInvalid"; }
+    assert(badTarget, "alvo inválido deveria falhar com OverlayTargetInvalid");
+
+    console.log("3) Overlay do schema + overlay da cred def (branding)...");
+    const schemaOverlay = parseJsonSafe(await issuer.overlaySet(schemaId, JSON.stringify({
+      meta: { "pt-BR": { name: "Licença de Piloto" }, en: { name: "Pilot License" } },
+      labels: {
+        "pt-BR": { nome: "Nome do titular", licenca: "Número da licença", validade: "Válida até" },
+        en: { nome: "Holder name", licenca: "License number", validade: "Valid until" },
+      },
+      formats: { validade: "date" },
+      order: ["licenca", "nome", "validade"],
+    })), "schemaOverlay");
+    for (const c of ["seed", "start_time", "unit_of_time", "time_window", "root_merkle_L"]) {
+      assert(schemaOverlay.overlay.hidden.includes(c), `CONTROL_ATTR ${c} deveria estar oculto`);
+    }
+    await issuer.overlaySet(credDefId, JSON.stringify({
+      labels: { "pt-BR": { nome: "Piloto" }, en: { nome: "Pilot" } },
+      branding: { primaryColor: "#0B3D91", logoUrl: "https://example.org/logo.png" },
+    }));
+
+    console.log("4) Publicação como ATTRIB...");
+    for (const target of [schemaId, credDefId]) {
+      const pub = parseJsonSafe(await issuer.overlayPublish(target), "publish");
+      assert(pub.ok === true && pub.issuerDid === issuerDid, `publicação falhou: ${JSON.stringify(pub)}`);
+    }
+
+    console.log("5) Holder busca os overlays no ledger...");
+    for (const target of [schemaId, credDefId]) {
+      const fetched = parseJsonSafe(await holder.overlayFetchFromLedger(target), "fetch");
+      assert(fetched.targetId === target, "overlay buscado com alvo divergente");
+    }
+    const listed = parseJsonSafe(await holder.overlayList(), "overlayList");
+    const fromLedger = listed.filter((o) => [schemaId, credDefId].includes(o.targetId));
+    assert(fromLedger.length === 2 && fromLedger.every((o) => o.source === "ledger"), "overlays do ledger não listados");
+
+    console.log("6) Emissão + list_credentials_view full com display...");
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    const offerJson = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-ovl-${Date.now()}`);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+    const requestJson = await fn(holder, "createCredentialRequest", "create_credential_request")(
+      "default", holderDid, credDefJson, offerJson
+    );
+    const credentialJson = await fn(issuer, "createCredential", "create_credential")(
+      credDefId, offerJson, requestJson, JSON.stringify({ nome: "Alice", licenca: "BRA-1234", validade: "2030-06-15" })
+    );
+    const nonce = JSON.parse(offerJson).nonce;
+    const credentialId = `cred-ovl-${Date.now()}`;
+    await fn(holder, "storeCredential", "store_credential")(credentialId, credentialJson, nonce, credDefJson, null);
+
+    const view = parseJsonSafe(await fn(holder, "listCredentialsView", "list_credentials_view")("full"), "view");
+    const item = view.find((c) => c.id_local === credentialId);
+    assert(item && item.display, "display ausente no modo full");
+    const d = item.display;
+    assert(JSON.stringify(d.sources) === JSON.stringify(["schema", "cred_def"]), `sources inesperado: ${d.sources}`);
+    assert(d.attributes.map((a) => a.name).join(",") === "licenca,nome,validade", "ordem não aplicada");
+    const nome = d.attributes.find((a) => a.name === "nome");
+    assert(nome.label === "Piloto" && nome.labels.en === "Pilot", "rótulo da cred def deveria sobrescrever o do schema");
+    assert(nome.value === "Alice", "valor raw ausente no display");
+    assert(d.attributes.find((a) => a.name === "validade").format === "date", "formato não aplicado");
+    assert(d.branding.primaryColor === "#0B3D91", "branding ausente");
+    assert(d.meta.en.name === "Pilot License", "meta do schema ausente");
+
+    const compact = parseJsonSafe(await fn(holder, "listCredentialsView", "list_credentials_view")("compact"), "compact");
+    assert(compact.every((c) => c.display === undefined), "compact não deveria trazer display");
+
+    console.log("✅ OK: TESTE CRED 07 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 07:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,156 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  rmIfExists,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+function freshWallet(p) {
+  rmIfExists(p);
+  rmIfExists(p + ".sidecar");
+  rmIfExists(p + ".kdf.json");
+}
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  // Wallets do holder sempre novas: o teste depende de qual link secret é o default.
+  const holderDbA = path.join(walletDir, "test_wallet_cred_ls_a.db");
+  const holderDbB = path.join(walletDir, "test_wallet_cred_ls_b.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 08: link secrets nomeados por wallet (default + seleção explícita)");
+  console.log("Config:", { issuerDb, holderDbA, holderDbB, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+  freshWallet(holderDbA);
+  freshWallet(holderDbB);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDbA, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaName = `SchemaLS_${Date.now()}`;
+    const schemaVersion = `1.${Math.floor(Date.now() / 1000)}`;
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, schemaName, schemaVersion, ["nome", "idade"]
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_LS_${Math.floor(Date.now() / 1000)}`
+    );
+    const schemaObj = parseJsonSafe(
+      await fn(issuer, "fetchSchemaFromLedger", "fetch_schema_from_ledger")(genesisAbs, schemaId), "schema"
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+
+    console.log("1) Dois link secrets: o primeiro vira default...");
+    const createLinkSecret = fn(holder, "createLinkSecret", "create_link_secret");
+    await createLinkSecret("ls-pessoal");
+    await createLinkSecret("ls-trabalho");
+    let list = parseJsonSafe(await holder.listLinkSecrets(), "listLinkSecrets");
+    assert(list.map((l) => l.id).join(",") === "ls-pessoal,ls-trabalho", `lista inesperada: ${JSON.stringify(list)}`);
+    assert(list.find((l) => l.isDefault).id === "ls-pessoal", "primeiro link secret deveria ser o default");
+    assert(list.every((l) => typeof l.createdAt === "number"), "createdAt ausente");
+
+    let notFound = false;
+    try { await holder.setDefaultLinkSecret("nao-existe"); } catch (e) { notFound = errCode(e) === "LinkSecretNotFound"; }
+    assert(notFound, "default inexistente deveria falhar com LinkSecretNotFound");
+
+    await holder.setDefaultLinkSecret("ls-trabalho");
+    assert((await holder.getDefaultLinkSecret()) === "ls-trabalho", "default não atualizado");
+
+    console.log("2) Request com link secret explícito (não default) + store...");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+    const offerJson = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-ls-${Date.now()}`);
+    const requestJson = await fn(holder, "createCredentialRequest", "create_credential_request")(
+      "ls-pessoal", holderDid, credDefJson, offerJson
+    );
+    const credentialJson = await fn(issuer, "createCredential", "create_credential")(
+      credDefId, offerJson, requestJson, JSON.stringify({ nome: "Alice", idade: "30" })
+    );
+    const credentialId = `cred-ls-${Date.now()}`;
+    // store_credential usa o link secret do request, não o default (ls-trabalho)
+    await fn(holder, "storeCredential", "store_credential")(
+      credentialId, credentialJson, extractNonce(offerJson), credDefJson, null
+    );
+
+    console.log("3) Apresentação v2 com o link secret correto verifica...");
+    const presReq = {
+      nonce: String(Date.now()),
+      name: "LS",
+      version: "0.1",
+      requested_attributes: { a_nome: { name: "nome" } },
+      requested_predicates: {},
+    };
+    const selection = { selection: [{ cred_id: credentialId, attributes: [{ referent: "a_nome", revealed: true }] }] };
+    const schemasMap = JSON.stringify({ [schemaId]: schemaObj });
+    const credDefsMap = JSON.stringify({ [credDefId]: parseJsonSafe(credDefJson, "credDef") });
+    const createPresentationV2 = fn(holder, "createPresentationV2", "create_presentation_v2");
+    const verify = fn(issuer, "verifyPresentation", "verify_presentation");
+
+    const presOk = await createPresentationV2(
+      JSON.stringify(presReq), JSON.stringify(selection), schemasMap, credDefsMap, "ls-pessoal"
+    );
+    assert((await verify(JSON.stringify(presReq), presOk, schemasMap, credDefsMap)) === true,
+      "apresentação com o link secret do request deveria verificar");
+
+    // Sem id explícito usa o default (ls-trabalho), que não é o da credencial.
+    let wrongVerified = true;
+    try {
+      const presWrong = await createPresentationV2(
+        JSON.stringify(presReq), JSON.stringify(selection), schemasMap, credDefsMap
+      );
+      wrongVerified = (await verify(JSON.stringify(presReq), presWrong, schemasMap, credDefsMap)) === true;
+    } catch { wrongVerified = false; }
+    assert(!wrongVerified, "apresentação com outro link secret não deveria verificar");
+
+    let unknownLs = false;
+    try {
+      await createPresentationV2(JSON.stringify(presReq), JSON.stringify(selection), schemasMap, credDefsMap, "nao-existe");
+    } catch (e) { unknownLs = errCode(e) === "LinkSecretNotFound"; }
+    assert(unknownLs, "link secret inexistente deveria falhar com LinkSecretNotFound");
+
+    console.log("4) Outra wallet no mesmo agente não herda link secrets em cache...");
+    await holder.walletClose();
+    await walletCreateOpenIdempotent(holder, holderDbB, pass);
+    list = parseJsonSafe(await holder.listLinkSecrets(), "listLinkSecrets B");
+    assert(list.length === 0, "wallet nova não deveria ter link secrets");
+    let noDefault = false;
+    try {
+      await fn(holder, "createCredentialRequest", "create_credential_request")("", holderDid, credDefJson, offerJson);
+    } catch (e) { noDefault = errCode(e) === "LinkSecretNotFound"; }
+    assert(noDefault, "request sem link secret na wallet B deveria falhar com LinkSecretNotFound");
+    let cachedLeak = false;
+    try {
+      await fn(holder, "createCredentialRequest", "create_credential_request")("ls-pessoal", holderDid, credDefJson, offerJson);
+      cachedLeak = true;
+    } catch (e) { cachedLeak = errCode(e) !== "LinkSecretNotFound"; }
+    assert(!cachedLeak, "link secret da wallet A vazou para a wallet B");
+
+    console.log("✅ OK: TESTE CRED 08 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 08:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,154 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  rmIfExists,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+function freshWallet(p) {
+  rmIfExists(p);
+  rmIfExists(p + ".sidecar");
+  rmIfExists(p + ".kdf.json");
+}
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const exportPass = "senha-do-backup-ls";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const deviceA = path.join(walletDir, "test_wallet_cred_ls_device_a.db");
+  const deviceB = path.join(walletDir, "test_wallet_cred_ls_device_b.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 09: link secret nativo, backup cifrado e migração");
+  console.log("Config:", { issuerDb, deviceA, deviceB, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+  freshWallet(deviceA);
+  freshWallet(deviceB);
+
+  const issuer = new IndyAgent();
+  const holderA = new IndyAgent();
+  const holderB = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holderA, deviceA, pass);
+  await walletCreateOpenIdempotent(holderB, deviceB, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, `SchemaLSBackup_${Date.now()}`, `1.${Math.floor(Date.now() / 1000)}`, ["nome"]
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_LSB_${Math.floor(Date.now() / 1000)}`
+    );
+    const schemaObj = parseJsonSafe(
+      await fn(issuer, "fetchSchemaFromLedger", "fetch_schema_from_ledger")(genesisAbs, schemaId), "schema"
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+
+    console.log("1) Link secret gerado pelo AnonCreds (formato nativo)...");
+    await fn(holderA, "createLinkSecret", "create_link_secret")("main");
+    const lsA = parseJsonSafe(await holderA.listLinkSecrets(), "listLinkSecrets")[0];
+    assert(lsA.id === "main" && lsA.format === "anoncreds" && lsA.weak === false, `link secret inesperado: ${JSON.stringify(lsA)}`);
+    let report = parseJsonSafe(await holderA.linkSecretMigrationReport(), "report");
+    assert(report.weakCount === 0 && report.reissue.length === 0, "wallet nova não deveria ter link secret fraco");
+
+    console.log("2) Credencial emitida no dispositivo A...");
+    const holderDid = JSON.parse(await fn(holderA, "createDidV2", "create_did_v2")("{}")).did;
+    const offerJson = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-lsb-${Date.now()}`);
+    const requestJson = await fn(holderA, "createCredentialRequest", "create_credential_request")(
+      "main", holderDid, credDefJson, offerJson
+    );
+    const credentialJson = await fn(issuer, "createCredential", "create_credential")(
+      credDefId, offerJson, requestJson, JSON.stringify({ nome: "Alice" })
+    );
+    const credentialId = `cred-lsb-${Date.now()}`;
+    await fn(holderA, "storeCredential", "store_credential")(
+      credentialId, credentialJson, extractNonce(offerJson), credDefJson, null
+    );
+
+    console.log("3) Backup cifrado do link secret + pacote da credencial...");
+    const lsPackage = await holderA.exportLinkSecret("main", exportPass);
+    const pkg = parseJsonSafe(lsPackage, "lsPackage");
+    assert(pkg.type === "ssi-link-secret-export" && pkg.linkSecretId === "main", "pacote inesperado");
+    assert(pkg.sealed && pkg.sealed.ct_b64 && pkg.value === undefined, "valor não deveria sair em claro");
+    const credPackage = await fn(holderA, "exportStoredCredential", "export_stored_credential")(credentialId);
+    assert(parseJsonSafe(credPackage, "credPackage").link_secret_id === "main", "pacote da credencial sem link_secret_id");
+
+    console.log("4) Importação no dispositivo B...");
+    let wrongPass = false;
+    try { await holderB.importLinkSecret(lsPackage, "senha-errada"); } catch (e) { wrongPass = errCode(e) === "ExportDecryptFailed"; }
+    assert(wrongPass, "senha errada deveria falhar com ExportDecryptFailed");
+
+    const imported = parseJsonSafe(await holderB.importLinkSecret(lsPackage, exportPass), "import");
+    assert(imported.linkSecretId === "main" && imported.isDefault === true, `import inesperado: ${JSON.stringify(imported)}`);
+
+    let dup = false;
+    try { await holderB.importLinkSecret(lsPackage, exportPass); } catch (e) { dup = errCode(e) === "LinkSecretAlreadyExists"; }
+    assert(dup, "import repetido deveria falhar com LinkSecretAlreadyExists");
+    await holderB.importLinkSecret(lsPackage, exportPass, true);
+
+    await fn(holderB, "importStoredCredential", "import_stored_credential")(credPackage, false, null);
+    let inUse = false;
+    try { await holderB.importLinkSecret(lsPackage, exportPass, true); } catch (e) { inUse = errCode(e) === "LinkSecretInUse"; }
+    assert(inUse, "overwrite com credenciais presas ao link secret deveria falhar com LinkSecretInUse");
+
+    const presReq = {
+      nonce: String(Date.now()),
+      name: "LSBackup",
+      version: "0.1",
+      requested_attributes: { a_nome: { name: "nome" } },
+      requested_predicates: {},
+    };
+    const selection = { selection: [{ cred_id: credentialId, attributes: [{ referent: "a_nome", revealed: true }] }] };
+    const schemasMap = JSON.stringify({ [schemaId]: schemaObj });
+    const credDefsMap = JSON.stringify({ [credDefId]: parseJsonSafe(credDefJson, "credDef") });
+    const presB = await fn(holderB, "createPresentationV2", "create_presentation_v2")(
+      JSON.stringify(presReq), JSON.stringify(selection), schemasMap, credDefsMap
+    );
+    const ok = await fn(issuer, "verifyPresentation", "verify_presentation")(
+      JSON.stringify(presReq), presB, schemasMap, credDefsMap
+    );
+    assert(ok === true, "credencial importada deveria ser apresentável com o link secret restaurado");
+
+    console.log("5) Migração: novo link secret default + relatório de reemissão...");
+    const mig = parseJsonSafe(await holderB.migrateLinkSecret("main"), "migrate");
+    assert(mig.newLinkSecretId === "main_v2" && mig.isDefault === true, `migração inesperada: ${JSON.stringify(mig)}`);
+    assert(mig.reissue.length === 1 && mig.reissue[0].id === credentialId, "credencial a reemitir ausente");
+    assert(mig.reissue[0].issuerDid === issuerDid, "issuerDid ausente no relatório");
+    assert((await holderB.getDefaultLinkSecret()) === "main_v2", "default não migrou");
+
+    report = parseJsonSafe(await holderB.linkSecretMigrationReport(), "report B");
+    const old = report.linkSecrets.find((l) => l.id === "main");
+    assert(old.status === "superseded" && old.supersededBy === "main_v2", "link secret antigo não marcado");
+    assert(old.credentials.length === 1, "credencial deveria continuar presa ao link secret antigo");
+
+    let again = false;
+    try { await holderB.migrateLinkSecret("main"); } catch (e) { again = errCode(e) === "LinkSecretAlreadyMigrated"; }
+    assert(again, "migrar de novo deveria falhar com LinkSecretAlreadyMigrated");
+
+    console.log("✅ OK: TESTE CRED 09 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holderA.walletClose(); } catch { }
+    try { await holderB.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 09:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,138 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_cred_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 10: máquina de estados da troca de emissão (thread = nonce)");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  const state = async (agent, role, threadId) =>
+    parseJsonSafe(await agent.exchangeGet(role, threadId), "exchange").state;
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, `SchemaExchange_${Date.now()}`, `1.${Math.floor(Date.now() / 1000)}`, ["nome"]
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_EXC_${Math.floor(Date.now() / 1000)}`
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    const createOffer = fn(issuer, "createCredentialOffer", "create_credential_offer");
+    const createRequest = fn(holder, "createCredentialRequest", "create_credential_request");
+    const createCredential = fn(issuer, "createCredential", "create_credential");
+
+    console.log("1) Fluxo completo avança os dois lados...");
+    const offerJson = await createOffer(credDefId, `offer-exc-${Date.now()}`);
+    const th = extractNonce(offerJson);
+    assert((await state(issuer, "issuer", th)) === "offer-sent", "issuer deveria estar em offer-sent");
+
+    await fn(holder, "storeReceivedOffer", "store_received_offer")(offerJson);
+    assert((await state(holder, "holder", th)) === "offer-received", "holder deveria estar em offer-received");
+
+    const requestJson = await createRequest("default", holderDid, credDefJson, offerJson);
+    assert((await state(holder, "holder", th)) === "request-sent", "holder deveria estar em request-sent");
+
+    const credentialJson = await createCredential(credDefId, offerJson, requestJson, JSON.stringify({ nome: "Alice" }));
+    const issuerRec = parseJsonSafe(await issuer.exchangeGet("issuer", th), "issuerRec");
+    assert(issuerRec.state === "credential-issued", "issuer deveria estar em credential-issued");
+    assert(
+      issuerRec.history.map((h) => h.to).join(",") === "offer-sent,request-received,credential-issued",
+      `histórico do issuer inesperado: ${JSON.stringify(issuerRec.history)}`
+    );
+    assert(issuerRec.counterpartyDid === holderDid && issuerRec.credDefId === credDefId, "dados da troca ausentes");
+
+    const credentialId = `cred-exc-${Date.now()}`;
+    await fn(holder, "storeCredential", "store_credential")(credentialId, credentialJson, th, credDefJson, null);
+    const holderRec = parseJsonSafe(await holder.exchangeGet("holder", th), "holderRec");
+    assert(holderRec.state === "credential-received" && holderRec.credentialId === credentialId, "holder sem credential-received");
+    assert(holderRec.counterpartyDid === issuerDid, "holder deveria registrar o DID do emissor");
+
+    console.log("2) Transições explícitas + validação...");
+    await holder.exchangeTransition("holder", th, "acked");
+    await issuer.exchangeTransition("issuer", th, "acked");
+    let invalid = false;
+    try { await issuer.exchangeTransition("issuer", th, "abandoned"); } catch (e) { invalid = errCode(e) === "ExchangeTransitionInvalid"; }
+    assert(invalid, "acked é final: abandoned deveria falhar com ExchangeTransitionInvalid");
+
+    let badRole = false;
+    try { await issuer.exchangeGet("verifier", th); } catch (e) { badRole = errCode(e) === "ExchangeRoleInvalid"; }
+    assert(badRole, "role inválido deveria falhar com ExchangeRoleInvalid");
+
+    console.log("3) Troca abandonada bloqueia a emissão...");
+    const offer2 = await createOffer(credDefId, `offer-exc2-${Date.now()}`);
+    const th2 = extractNonce(offer2);
+    let skip = false;
+    try { await issuer.exchangeTransition("issuer", th2, "credential-issued"); } catch (e) { skip = errCode(e) === "ExchangeTransitionInvalid"; }
+    assert(skip, "pular estados deveria falhar com ExchangeTransitionInvalid");
+
+    const request2 = await createRequest("default", holderDid, credDefJson, offer2);
+    await holder.exchangeTransition("holder", th2, "problem-reported", JSON.stringify({
+      reason: "dados incorretos",
+      problem: { code: "issuance-abandoned", description: "nome errado" },
+    }));
+    await issuer.exchangeTransition("issuer", th2, "abandoned", JSON.stringify({ reason: "holder reportou problema" }));
+    let closed = false;
+    try {
+      await createCredential(credDefId, offer2, request2, JSON.stringify({ nome: "Bob" }));
+    } catch (e) { closed = errCode(e) === "ExchangeClosed"; }
+    assert(closed, "emissão em troca abandonada deveria falhar com ExchangeClosed");
+
+    const hRec2 = parseJsonSafe(await holder.exchangeGet("holder", th2), "hRec2");
+    assert(hRec2.problem && hRec2.problem.code === "issuance-abandoned", "problem não registrado");
+    assert(hRec2.history[hRec2.history.length - 1].reason === "dados incorretos", "reason não registrado");
+
+    console.log("4) listExchanges com filtros...");
+    const active = parseJsonSafe(await issuer.listExchanges(JSON.stringify({ role: "issuer", active: true })), "active");
+    assert(!active.some((x) => [th, th2].includes(x.threadId)), "trocas finalizadas não deveriam aparecer como ativas");
+    const finished = parseJsonSafe(await issuer.listExchanges(JSON.stringify({
+      role: "issuer", credDefId, state: ["acked", "abandoned"],
+    })), "finished");
+    assert(finished.map((x) => x.threadId).sort().join(",") === [th, th2].sort().join(","), "filtro por estado/credDef falhou");
+    assert(finished[0].updatedAt >= finished[1].updatedAt, "lista deveria vir por updatedAt desc");
+
+    let badFilter = false;
+    try { await issuer.listExchanges(JSON.stringify({ estado: "x" })); } catch (e) { badFilter = errCode(e) === "ExchangeFilterInvalid"; }
+    assert(badFilter, "filtro desconhecido deveria falhar com ExchangeFilterInvalid");
+
+    console.log("✅ OK: TESTE CRED 10 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 10:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,147 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  errCode,
+} = require("./_helpers");
+
+const PREFIX = "https://didcomm.org/issue-credential/2.0/";
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_cred_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 11: mensagens DIDComm Issue Credential 2.0 (anoncreds)");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  // Simula o transporte: só a string JSON atravessa.
+  const wire = (msg) => JSON.stringify(JSON.parse(msg));
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, `SchemaIssueV2_${Date.now()}`, `1.${Math.floor(Date.now() / 1000)}`, ["nome", "idade"]
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_IV2_${Math.floor(Date.now() / 1000)}`
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    console.log("1) offer-credential com preview...");
+    const offerJson = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-iv2-${Date.now()}`);
+    const offerMsg = await issuer.issueV2BuildOffer(
+      offerJson, JSON.stringify({ nome: "Alice", idade: 30 }), "Sua credencial"
+    );
+    const om = parseJsonSafe(offerMsg, "offerMsg");
+    assert(om["@type"] === PREFIX + "offer-credential", "tipo da oferta inesperado");
+    assert(om.formats[0].format === "anoncreds/credential-offer@v1.0", "formato da oferta inesperado");
+    assert(om.formats[0].attach_id === om["offers~attach"][0]["@id"], "attach_id não bate com o anexo");
+    assert(om.credential_preview.attributes.find((a) => a.name === "idade").value === "30", "preview inesperado");
+    const thid = om["@id"];
+
+    const rOffer = parseJsonSafe(await holder.issueV2Receive(wire(offerMsg)), "rOffer");
+    assert(rOffer.type === "offer-credential" && rOffer.messageThreadId === thid, "offer recebida inesperada");
+    assert(rOffer.credDefId === credDefId && rOffer.comment === "Sua credencial", "dados da oferta ausentes");
+    const nonce = rOffer.threadId;
+    assert(JSON.parse(rOffer.offerJson).nonce === nonce, "offerJson não corresponde ao nonce");
+    await fn(holder, "storeReceivedOffer", "store_received_offer")(rOffer.offerJson);
+    const hRec = parseJsonSafe(await holder.exchangeGet("holder", nonce), "hRec");
+    assert(hRec.state === "offer-received" && hRec.messageThreadId === thid, "troca do holder sem thid");
+
+    console.log("2) request-credential...");
+    const requestJson = await fn(holder, "createCredentialRequest", "create_credential_request")(
+      "default", holderDid, credDefJson, rOffer.offerJson
+    );
+    const requestMsg = holder.issueV2BuildRequest(requestJson, thid);
+    const rReq = parseJsonSafe(await issuer.issueV2Receive(wire(requestMsg)), "rReq");
+    assert(rReq.type === "request-credential" && rReq.threadId === nonce, "request recebido inesperado");
+    assert(rReq.offerJson && rReq.credDefId === credDefId, "issuer deveria recuperar a oferta do thread");
+    assert((parseJsonSafe(await issuer.exchangeGet("issuer", nonce), "iRec")).state === "request-received",
+      "issuer deveria estar em request-received");
+
+    console.log("3) issue-credential + store...");
+    const credentialJson = await fn(issuer, "createCredential", "create_credential")(
+      rReq.credDefId, rReq.offerJson, rReq.requestJson, JSON.stringify({ nome: "Alice", idade: "30" })
+    );
+    const credMsg = issuer.issueV2BuildCredential(credentialJson, thid);
+    const rCred = parseJsonSafe(await holder.issueV2Receive(wire(credMsg)), "rCred");
+    assert(rCred.type === "issue-credential" && rCred.requestMetadataId === nonce, "credencial recebida inesperada");
+    const credentialId = `cred-iv2-${Date.now()}`;
+    await fn(holder, "storeCredential", "store_credential")(
+      credentialId, rCred.credentialJson, rCred.requestMetadataId, credDefJson, null
+    );
+
+    console.log("4) ack fecha os dois lados...");
+    const ackMsg = await holder.issueV2BuildAck(thid);
+    assert(parseJsonSafe(ackMsg, "ack").status === "OK", "ack sem status OK");
+    const rAck = parseJsonSafe(await issuer.issueV2Receive(wire(ackMsg)), "rAck");
+    assert(rAck.type === "ack", "ack recebido inesperado");
+    assert((await holder.exchangeGet("holder", nonce)).includes('"state":"acked"'), "holder deveria estar em acked");
+    assert((await issuer.exchangeGet("issuer", nonce)).includes('"state":"acked"'), "issuer deveria estar em acked");
+
+    console.log("5) problem-report + anexo hlindy em base64url...");
+    const offer2 = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-iv2b-${Date.now()}`);
+    const om2 = parseJsonSafe(await issuer.issueV2BuildOffer(offer2), "om2");
+    om2.formats[0].format = "hlindy/cred-abstract@v2.0";
+    om2["offers~attach"][0].data.base64 = Buffer.from(offer2).toString("base64url");
+    const rOffer2 = parseJsonSafe(await holder.issueV2Receive(JSON.stringify(om2)), "rOffer2");
+    assert(rOffer2.threadId === JSON.parse(offer2).nonce, "oferta hlindy/base64url não interpretada");
+
+    const prMsg = await holder.issueV2BuildProblemReport(om2["@id"], "issuance-abandoned", "não quero");
+    const rPr = parseJsonSafe(await issuer.issueV2Receive(wire(prMsg)), "rPr");
+    assert(rPr.problem.code === "issuance-abandoned", "problem-report inesperado");
+    const iRec2 = parseJsonSafe(await issuer.exchangeGet("issuer", rOffer2.threadId), "iRec2");
+    assert(iRec2.state === "problem-reported" && iRec2.problem.code === "issuance-abandoned", "issuer sem problem-reported");
+    assert((await holder.exchangeGet("holder", rOffer2.threadId)).includes('"state":"problem-reported"'),
+      "holder deveria estar em problem-reported");
+
+    console.log("6) Erros...");
+    const expectCode = async (p, code, msg) => {
+      let got = null;
+      try { await p(); } catch (e) { got = errCode(e); }
+      assert(got === code, `${msg}: esperado ${code}, veio ${got}`);
+    };
+    await expectCode(() => holder.issueV2Receive(JSON.stringify({ "@type": PREFIX + "propose-credential", "@id": "x" })),
+      "IssueV2UnsupportedType", "propose não é suportado");
+    await expectCode(() => holder.issueV2Receive(JSON.stringify({ "@type": "https://didcomm.org/present-proof/2.0/ack" })),
+      "IssueV2UnsupportedType", "outro protocolo");
+    const badFormat = JSON.parse(offerMsg);
+    badFormat.formats[0].format = "aries/ld-proof-vc-detail@v1.0";
+    await expectCode(() => holder.issueV2Receive(JSON.stringify(badFormat)), "IssueV2FormatUnsupported", "formato ld-proof");
+    await expectCode(() => holder.issueV2Receive("{nao-json"), "IssueV2MessageInvalid", "JSON inválido");
+    await expectCode(() => issuer.issueV2Receive(holder.issueV2BuildRequest(requestJson, "thid-desconhecido")),
+      "IssueV2ThreadUnknown", "thid desconhecido");
+
+    console.log("✅ OK: TESTE CRED 11 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 11:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
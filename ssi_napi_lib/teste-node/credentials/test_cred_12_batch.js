@@ -0,0 +1,120 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_cred_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 12: emissão em lote (resultado por item)");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, `SchemaBatch_${Date.now()}`, `1.${Math.floor(Date.now() / 1000)}`, ["matricula", "modelo"]
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_BATCH_${Math.floor(Date.now() / 1000)}`
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    const createOffer = fn(issuer, "createCredentialOffer", "create_credential_offer");
+    const createRequest = fn(holder, "createCredentialRequest", "create_credential_request");
+
+    console.log("1) Prepara ofertas/requests (5 aeronaves)...");
+    const pairs = [];
+    for (let i = 0; i < 5; i++) {
+      const offerJson = await createOffer(credDefId, `offer-batch-${i}-${Date.now()}`);
+      const requestJson = await createRequest("default", holderDid, credDefJson, offerJson);
+      pairs.push({ offerJson, requestJson, matricula: `PR-AB${i}` });
+    }
+    // Troca do item 3 abandonada antes da emissão
+    await issuer.exchangeTransition("issuer", extractNonce(pairs[3].offerJson), "abandoned");
+
+    const items = pairs.map((p, i) => ({
+      ref: `aeronave-${i}`,
+      offer: p.offerJson,
+      request: i === 1 ? JSON.parse(p.requestJson) : p.requestJson, // objeto também vale
+      values: { matricula: p.matricula, modelo: "EMB-190" },
+    }));
+    items.push({ ref: "duplicada", offer: pairs[0].offerJson, request: pairs[0].requestJson, values: { matricula: "X", modelo: "Y" } });
+    const lonelyOffer = await createOffer(credDefId, `offer-batch-x-${Date.now()}`);
+    items.push({ ref: "sem-request", offer: lonelyOffer, values: { matricula: "X", modelo: "Y" } });
+
+    console.log("2) Emissão em lote...");
+    const out = parseJsonSafe(await issuer.createCredentialsBatch(credDefId, JSON.stringify(items)), "batch");
+    const byRef = Object.fromEntries(out.results.map((r) => [r.ref, r]));
+    console.log(JSON.stringify(out.results.map((r) => ({ ref: r.ref, ok: r.ok, code: r.error && r.error.code }))));
+
+    assert(out.total === items.length && out.results.length === items.length, "total inesperado");
+    assert(out.results.every((r, i) => r.index === i), "resultados fora da ordem dos itens");
+    const okRefs = ["aeronave-0", "aeronave-1", "aeronave-2", "aeronave-4"];
+    for (const ref of okRefs) {
+      assert(byRef[ref].ok === true && byRef[ref].credentialJson, `${ref} deveria ter sido emitida`);
+    }
+    assert(byRef["aeronave-3"].ok === false && byRef["aeronave-3"].error.code === "ExchangeClosed",
+      "troca abandonada deveria falhar com ExchangeClosed");
+    assert(byRef["duplicada"].error.code === "BatchDuplicateOffer", "oferta repetida deveria falhar com BatchDuplicateOffer");
+    assert(byRef["sem-request"].error.code === "BatchItemInvalid", "item sem request deveria falhar com BatchItemInvalid");
+    assert(out.issued === 4 && out.failed === items.length - 4, `contagem inesperada: ${out.issued}/${out.failed}`);
+
+    console.log("3) Credenciais do lote são armazenáveis + trocas avançadas...");
+    for (const ref of okRefs) {
+      const r = byRef[ref];
+      await fn(holder, "storeCredential", "store_credential")(
+        `cred-batch-${ref}-${Date.now()}`, r.credentialJson, r.threadId, credDefJson, null
+      );
+      const ex = parseJsonSafe(await issuer.exchangeGet("issuer", r.threadId), "exchange");
+      assert(ex.state === "credential-issued" && ex.counterpartyDid === holderDid, `troca de ${ref} não avançou`);
+    }
+    const ex3 = parseJsonSafe(await issuer.exchangeGet("issuer", extractNonce(pairs[3].offerJson)), "ex3");
+    assert(ex3.state === "abandoned", "item que falhou não deveria avançar a troca");
+
+    console.log("4) Erros do lote inteiro...");
+    let empty = false;
+    try { await issuer.createCredentialsBatch(credDefId, "[]"); } catch (e) { empty = errCode(e) === "BatchItemsInvalid"; }
+    assert(empty, "lote vazio deveria falhar com BatchItemsInvalid");
+    let tooLarge = false;
+    try {
+      await issuer.createCredentialsBatch(credDefId, JSON.stringify(new Array(1001).fill(items[0])));
+    } catch (e) { tooLarge = errCode(e) === "BatchTooLarge"; }
+    assert(tooLarge, "lote acima do limite deveria falhar com BatchTooLarge");
+
+    console.log("✅ OK: TESTE CRED 12 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 12:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
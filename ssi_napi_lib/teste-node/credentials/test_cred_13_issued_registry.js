@@ -0,0 +1,129 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_cred_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 13: registro de emissão do issuer + export + política de valores");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, `SchemaIssued_${Date.now()}`, `1.${Math.floor(Date.now() / 1000)}`, ["nome", "cpf"]
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_ISSUED_${Math.floor(Date.now() / 1000)}`
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    const issue = async (values) => {
+      const offerJson = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-issued-${Date.now()}`);
+      const requestJson = await fn(holder, "createCredentialRequest", "create_credential_request")(
+        "default", holderDid, credDefJson, offerJson
+      );
+      await fn(issuer, "createCredential", "create_credential")(credDefId, offerJson, requestJson, JSON.stringify(values));
+      return extractNonce(offerJson);
+    };
+    const list = async (filter) =>
+      parseJsonSafe(await issuer.listIssuedCredentials(filter ? JSON.stringify(filter) : undefined), "listIssued");
+
+    console.log("1) Política padrão (plain) registra valores em claro...");
+    assert(JSON.parse(await issuer.setIssuedValuesPolicy("{}")).mode === "plain", "política padrão deveria ser plain");
+    const th1 = await issue({ nome: "Alice, \"Ali\"", cpf: "=123.456.789-00" });
+    let recs = await list({ credDefId });
+    assert(recs.length === 1, `esperado 1 registro, veio ${recs.length}`);
+    const r1 = recs[0];
+    assert(r1.id === th1 && r1.holderDid === holderDid && r1.schemaId === schemaId, `registro inesperado: ${JSON.stringify(r1)}`);
+    assert(r1.valuesMode === "plain" && r1.values.cpf === "=123.456.789-00", "valores em claro ausentes");
+    assert(typeof r1.issuedAt === "number" && r1.revIndex == null, "issuedAt/revIndex inesperados");
+
+    console.log("2) Política hashed: valores com sha256 salgado...");
+    await issuer.setIssuedValuesPolicy(JSON.stringify({ mode: "hashed", retentionDays: 3650 }));
+    const th2 = await issue({ nome: "Bob", cpf: "987.654.321-00" });
+    const th3 = await issue({ nome: "Bob", cpf: "987.654.321-00" });
+    recs = await list({ credDefId, holderDid });
+    assert(recs.length === 3, "filtro por holderDid deveria trazer as 3 emissões");
+    const r2 = recs.find((r) => r.id === th2);
+    const r3 = recs.find((r) => r.id === th3);
+    assert(r2.valuesMode === "hashed" && r2.values.cpf.startsWith("sha256:"), "valor deveria estar em hash");
+    assert(!JSON.stringify(r2).includes("987.654.321-00"), "valor em claro vazou no modo hashed");
+    assert(r2.values.cpf === r3.values.cpf && r2.values.cpf !== r2.values.nome, "hash deveria ser estável por atributo/valor");
+    assert(recs[0].issuedAt >= recs[recs.length - 1].issuedAt, "lista deveria vir por issuedAt desc");
+
+    await issuer.setIssuedValuesPolicy(JSON.stringify({ mode: "none" }));
+    const th4 = await issue({ nome: "Carol", cpf: "000" });
+    const r4 = (await list({ threadId: th4 }))[0];
+    assert(r4.valuesMode === "none" && Object.keys(r4.values).length === 0, "modo none não deveria guardar valores");
+
+    console.log("3) Export CSV/JSON para reguladores...");
+    const csv = await issuer.exportIssuedCredentials("csv", JSON.stringify({ credDefId }));
+    const lines = csv.trim().split("\n");
+    assert(lines.length === 5, `CSV deveria ter cabeçalho + 4 linhas, veio ${lines.length}`);
+    assert(lines[0].startsWith("id,issued_at,cred_def_id") && lines[0].includes("attr:cpf"), "cabeçalho CSV inesperado");
+    assert(csv.includes("\"Alice, \"\"Ali\"\"\""), "CSV deveria escapar vírgulas e aspas");
+    assert(csv.includes(",'=123.456.789-00") && !csv.includes(",=123"), "CSV deveria neutralizar fórmulas");
+    const exp = parseJsonSafe(await issuer.exportIssuedCredentials("json", JSON.stringify({ credDefId })), "export");
+    assert(exp.type === "ssi-issued-credentials-export" && exp.count === 4, "export JSON inesperado");
+    assert(exp.policy.mode === "none", "export deveria trazer a política vigente");
+
+    console.log("4) Retenção + validações...");
+    assert((await issuer.purgeIssuedValues()) === 0, "nada deveria expirar sem retenção");
+    const old = await list({ credDefId, issuedUntil: 1 });
+    assert(old.length === 0, "filtro issuedUntil deveria excluir tudo");
+
+    let badMode = false;
+    try { await issuer.setIssuedValuesPolicy(JSON.stringify({ mode: "cifrado" })); } catch (e) { badMode = errCode(e) === "IssuedPolicyInvalid"; }
+    assert(badMode, "modo desconhecido deveria falhar com IssuedPolicyInvalid");
+    let badRetention = false;
+    try { await issuer.setIssuedValuesPolicy(JSON.stringify({ retentionDays: 0 })); } catch (e) { badRetention = errCode(e) === "IssuedPolicyInvalid"; }
+    assert(badRetention, "retentionDays 0 deveria falhar com IssuedPolicyInvalid");
+    let badFilter = false;
+    try { await issuer.listIssuedCredentials(JSON.stringify({ holder: "x" })); } catch (e) { badFilter = errCode(e) === "IssuedFilterInvalid"; }
+    assert(badFilter, "filtro desconhecido deveria falhar com IssuedFilterInvalid");
+    let badFormat = false;
+    try { await issuer.exportIssuedCredentials("xml"); } catch (e) { badFormat = errCode(e) === "IssuedExportFormatInvalid"; }
+    assert(badFormat, "formato desconhecido deveria falhar com IssuedExportFormatInvalid");
+
+    // Volta ao padrão para não afetar as outras suítes
+    await issuer.setIssuedValuesPolicy("{}");
+
+    console.log("✅ OK: TESTE CRED 13 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 13:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
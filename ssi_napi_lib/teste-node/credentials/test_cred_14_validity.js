@@ -0,0 +1,141 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  rmIfExists,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+function freshWallet(p) {
+  rmIfExists(p);
+  rmIfExists(p + ".sidecar");
+  rmIfExists(p + ".kdf.json");
+}
+
+const DAY = 86400;
+const isoDate = (epochS) => new Date(epochS * 1000).toISOString().slice(0, 10);
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  // Holder novo: listExpiringCredentials depende só das credenciais deste teste.
+  const holderDb = path.join(walletDir, "test_wallet_cred_validity.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 14: janela de validade no holder (CONTROL_ATTRS + expiry_date)");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+  freshWallet(holderDb);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaName = `SchemaValidity_${Date.now()}`;
+    const schemaVersion = `1.${Math.floor(Date.now() / 1000)}`;
+    // Layout revogável: attrs do usuário + CONTROL_ATTRS
+    const preview = JSON.parse(issuer.schemaBuildPreview(schemaName, schemaVersion, ["nome", "expiry_date"], true));
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, schemaName, schemaVersion, preview.finalAttrNames
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_VAL_${Math.floor(Date.now() / 1000)}`
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    const issueAndStore = async (id, values) => {
+      const offerJson = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-val-${id}-${Date.now()}`);
+      const requestJson = await fn(holder, "createCredentialRequest", "create_credential_request")(
+        "default", holderDid, credDefJson, offerJson
+      );
+      const all = { seed: "0", root_merkle_L: "0", ...values };
+      const credentialJson = await fn(issuer, "createCredential", "create_credential")(
+        credDefId, offerJson, requestJson, JSON.stringify(all)
+      );
+      await fn(holder, "storeCredential", "store_credential")(id, credentialJson, extractNonce(offerJson), credDefJson, null);
+    };
+
+    const now = Math.floor(Date.now() / 1000);
+    console.log("1) Três credenciais: válida (vence em 4 dias), expirada e futura...");
+    // Janela de controle: começou ontem, 5 dias -> vence em ~4 dias (expiry_date distante não restringe)
+    await issueAndStore("cred-val-a", {
+      nome: "Alice", expiry_date: "2099-12-31", start_time: String(now - DAY), unit_of_time: "day", time_window: "5",
+    });
+    // expiry_date ontem é mais restritivo que a janela de controle (100 dias)
+    await issueAndStore("cred-val-b", {
+      nome: "Bob", expiry_date: isoDate(now - DAY), start_time: String(now - 10 * DAY), unit_of_time: "dias", time_window: "100",
+    });
+    // Começa daqui a 3 dias (ISO com hora)
+    await issueAndStore("cred-val-c", {
+      nome: "Carol", expiry_date: "2099-12-31", start_time: new Date((now + 3 * DAY) * 1000).toISOString(),
+      unit_of_time: "month", time_window: "12",
+    });
+
+    const a = parseJsonSafe(await holder.getCredentialValidity("cred-val-a"), "validity a");
+    assert(a.status === "valid" && a.source === "control_attrs+attrs", `validade A inesperada: ${JSON.stringify(a)}`);
+    assert(a.valid_until === now - DAY + 5 * DAY, "janela de controle deveria prevalecer sobre expiry_date distante");
+    const b = parseJsonSafe(await holder.getCredentialValidity("cred-val-b"), "validity b");
+    assert(b.status === "expired", `B deveria estar expirada: ${JSON.stringify(b)}`);
+    const c = parseJsonSafe(await holder.getCredentialValidity("cred-val-c"), "validity c");
+    assert(c.status === "not_yet_valid" && c.valid_until - c.valid_from === 12 * 30 * DAY, `validade C inesperada: ${JSON.stringify(c)}`);
+
+    console.log("2) list_credentials_view traz validity nos dois modos...");
+    const listView = fn(holder, "listCredentialsView", "list_credentials_view");
+    for (const mode of ["compact", "full"]) {
+      const view = parseJsonSafe(await listView(mode), `view ${mode}`);
+      const st = Object.fromEntries(view.map((v) => [v.id_local, v.validity && v.validity.status]));
+      assert(st["cred-val-a"] === "valid" && st["cred-val-b"] === "expired" && st["cred-val-c"] === "not_yet_valid",
+        `status no modo ${mode} inesperado: ${JSON.stringify(st)}`);
+    }
+
+    console.log("3) listExpiringCredentials para lembretes de renovação...");
+    let expiring = parseJsonSafe(await holder.listExpiringCredentials(7), "expiring");
+    assert(expiring.map((x) => x.id_local).join(",") === "cred-val-a", `expiring(7) inesperado: ${JSON.stringify(expiring)}`);
+    assert([3, 4].includes(expiring[0].days_left), `days_left inesperado: ${expiring[0].days_left}`);
+    assert((parseJsonSafe(await holder.listExpiringCredentials(1), "expiring 1")).length === 0, "nada vence em 1 dia");
+    expiring = parseJsonSafe(await holder.listExpiringCredentials(7, true), "expiring+expired");
+    assert(expiring.map((x) => x.id_local).join(",") === "cred-val-b,cred-val-a", "expiradas deveriam vir primeiro (valid_until asc)");
+
+    console.log("4) Atributos de validade configuráveis...");
+    await holder.setValidityAttributes(JSON.stringify({ validUntil: ["data_que_nao_existe"] }));
+    const b2 = parseJsonSafe(await holder.getCredentialValidity("cred-val-b"), "validity b2");
+    assert(b2.status === "valid" && b2.source === "control_attrs", "sem expiry_date na config, B só tem a janela de controle");
+    assert(JSON.parse(await holder.getValidityAttributes()).validFrom.includes("issue_date"), "validFrom omitido deveria voltar ao padrão");
+    await holder.setValidityAttributes("{}");
+
+    let badCfg = false;
+    try { await holder.setValidityAttributes(JSON.stringify({ expiry: ["x"] })); } catch (e) { badCfg = errCode(e) === "ValidityConfigInvalid"; }
+    assert(badCfg, "config desconhecida deveria falhar com ValidityConfigInvalid");
+    let missing = false;
+    try { await holder.getCredentialValidity("nao-existe"); } catch (e) { missing = errCode(e) === "CredentialNotFound"; }
+    assert(missing, "credencial inexistente deveria falhar com CredentialNotFound");
+
+    console.log("✅ OK: TESTE CRED 14 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 14:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,176 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+const DAY = 86400;
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_cred_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 15: revogação por janela de tempo (Merkle) controlada pelo holder");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaName = `SchemaWindow_${Date.now()}`;
+    const schemaVersion = `1.${Math.floor(Date.now() / 1000)}`;
+    const preview = JSON.parse(issuer.schemaBuildPreview(schemaName, schemaVersion, ["nome"], true));
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, schemaName, schemaVersion, preview.finalAttrNames
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_TW_${Math.floor(Date.now() / 1000)}`
+    );
+    const schemaObj = parseJsonSafe(
+      await fn(issuer, "fetchSchemaFromLedger", "fetch_schema_from_ledger")(genesisAbs, schemaId), "schema"
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    console.log("1) Holder gera seed + root no request; issuer embute os CONTROL_ATTRS...");
+    const now = Math.floor(Date.now() / 1000);
+    const start = now - 2 * DAY - 60; // slot 2 de 10 (dias)
+    const offerJson = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-tw-${Date.now()}`);
+    const requestJson = await fn(holder, "createCredentialRequest", "create_credential_request")(
+      "default", holderDid, credDefJson, offerJson
+    );
+    const control = parseJsonSafe(
+      await holder.timeWindowPrepare(offerJson, JSON.stringify({ startTime: start, unitOfTime: "day", timeWindow: 10 })),
+      "prepare"
+    );
+    assert(/^[0-9a-f]{64}$/.test(control.root_merkle_L) && /^[0-9a-f]{64}$/.test(control.seed), "root/seed inesperados");
+    assert(control.start_time === String(start) && control.time_window === "10", `controle inesperado: ${JSON.stringify(control)}`);
+
+    const values = await issuer.timeWindowEmbed(JSON.stringify({ nome: "Alice" }), JSON.stringify(control));
+    assert(JSON.parse(values).root_merkle_L === control.root_merkle_L, "root não embutido");
+    const credentialJson = await fn(issuer, "createCredential", "create_credential")(credDefId, offerJson, requestJson, values);
+    const credentialId = `cred-tw-${Date.now()}`;
+    await fn(holder, "storeCredential", "store_credential")(
+      credentialId, credentialJson, extractNonce(offerJson), credDefJson, null
+    );
+    assert(!credentialJson.includes(JSON.parse(await holder.timeWindowProof(credentialId)).leaf),
+      "folha não deveria aparecer na credencial");
+
+    console.log("2) Apresentação revela a janela + prova do slot atual...");
+    const presReq = {
+      nonce: String(Date.now()),
+      name: "TW",
+      version: "0.1",
+      requested_attributes: {
+        a_nome: { name: "nome" },
+        a_start: { name: "start_time" },
+        a_unit: { name: "unit_of_time" },
+        a_window: { name: "time_window" },
+        a_root: { name: "root_merkle_L" },
+      },
+      requested_predicates: {},
+    };
+    const selection = {
+      selection: [{
+        cred_id: credentialId,
+        attributes: ["a_nome", "a_start", "a_unit", "a_window", "a_root"].map((referent) => ({ referent, revealed: true })),
+      }],
+    };
+    const schemasMap = JSON.stringify({ [schemaId]: schemaObj });
+    const credDefsMap = JSON.stringify({ [credDefId]: parseJsonSafe(credDefJson, "credDef") });
+    const presentation = await fn(holder, "createPresentationV2", "create_presentation_v2")(
+      JSON.stringify(presReq), JSON.stringify(selection), schemasMap, credDefsMap
+    );
+    assert((await fn(issuer, "verifyPresentation", "verify_presentation")(
+      JSON.stringify(presReq), presentation, schemasMap, credDefsMap)) === true, "prova AnonCreds deveria verificar");
+
+    const proof = parseJsonSafe(await holder.timeWindowProof(credentialId), "proof");
+    assert(proof.slot === 2 && proof.rootMerkleL === control.root_merkle_L, `prova inesperada: ${JSON.stringify(proof)}`);
+    assert(proof.validFrom === start + 2 * DAY && proof.validUntil === start + 3 * DAY, "limites do slot inesperados");
+    const verify = (p, opts) => parseJsonSafe(
+      issuer.timeWindowVerify(JSON.stringify(presReq), presentation, JSON.stringify(p), opts ? JSON.stringify(opts) : undefined),
+      "verify"
+    );
+    let r = verify(proof);
+    assert(r.valid === true && r.expectedSlot === 2, `prova de janela deveria verificar: ${JSON.stringify(r)}`);
+
+    console.log("3) Folha adulterada, slot fora do relógio, outro root...");
+    const flipped = proof.leaf.slice(0, -1) + (proof.leaf.endsWith("0") ? "1" : "0");
+    r = verify({ ...proof, leaf: flipped });
+    assert(r.valid === false && r.reason === "MerklePathInvalid", `folha adulterada deveria falhar: ${JSON.stringify(r)}`);
+    r = verify(proof, { atTime: now + DAY });
+    assert(r.valid === false && r.reason === "SlotMismatch" && r.expectedSlot === 3, "slot 2 não vale no dia seguinte");
+    assert(verify(proof, { atTime: now + DAY, toleranceSlots: 1 }).valid === true, "tolerância de 1 slot deveria aceitar");
+    r = verify({ ...proof, rootMerkleL: "0".repeat(64) });
+    assert(r.valid === false && r.reason === "RootNotRevealed", "root não revelado deveria falhar");
+    r = verify({ ...proof, slot: 3 }, { atTime: now + DAY });
+    assert(r.valid === false && r.reason === "MerklePathInvalid", "folha do slot 2 não prova o slot 3");
+
+    const future = parseJsonSafe(await holder.timeWindowProof(credentialId, JSON.stringify({ atTime: now + 5 * DAY })), "future");
+    assert(future.slot === 7 && verify(future, { atTime: now + 5 * DAY }).valid === true, "holder deveria provar slots futuros");
+    let expired = false;
+    try { await holder.timeWindowProof(credentialId, JSON.stringify({ atTime: now + 30 * DAY })); } catch (e) { expired = errCode(e) === "TimeWindowExpired"; }
+    assert(expired, "fora da janela deveria falhar com TimeWindowExpired");
+    let minTime = null;
+    try { await holder.timeWindowProof(credentialId, '{"atTime": -9223372036854775808}'); } catch (e) { minTime = errCode(e); }
+    assert(minTime === "TimeWindowParamsInvalid", `atTime = i64::MIN deveria falhar com TimeWindowParamsInvalid, veio ${minTime}`);
+
+    console.log("4) Holder revoga: sem seed não há mais prova...");
+    assert((await holder.timeWindowRevoke(credentialId)) === true, "revogação deveria retornar true");
+    assert((await holder.timeWindowRevoke(credentialId)) === false, "segunda revogação deveria retornar false");
+    let revoked = false;
+    try { await holder.timeWindowProof(credentialId); } catch (e) { revoked = errCode(e) === "TimeWindowRevoked"; }
+    assert(revoked, "prova após revogação deveria falhar com TimeWindowRevoked");
+
+    console.log("5) Validações de prepare/embed...");
+    let badParams = false;
+    try {
+      await holder.timeWindowPrepare(offerJson, JSON.stringify({ unitOfTime: "quinzena", timeWindow: 3 }));
+    } catch (e) { badParams = errCode(e) === "TimeWindowParamsInvalid"; }
+    assert(badParams, "unidade desconhecida deveria falhar com TimeWindowParamsInvalid");
+    let badControl = false;
+    try {
+      issuer.timeWindowEmbed("{}", JSON.stringify({ ...control, root_merkle_L: "abc" }));
+    } catch (e) { badControl = errCode(e) === "TimeWindowControlInvalid"; }
+    assert(badControl, "root inválido deveria falhar com TimeWindowControlInvalid");
+    let conflict = false;
+    try {
+      issuer.timeWindowEmbed(JSON.stringify({ nome: "Bob", time_window: "99" }), JSON.stringify(control));
+    } catch (e) { conflict = errCode(e) === "TimeWindowConflict"; }
+    assert(conflict, "time_window divergente deveria falhar com TimeWindowConflict");
+    // Atributo de controle vazio é preenchido
+    assert(JSON.parse(issuer.timeWindowEmbed(JSON.stringify({ nome: "Bob", seed: "" }), JSON.stringify(control))).seed === control.seed,
+      "seed vazio deveria ser preenchido");
+
+    console.log("✅ OK: TESTE CRED 15 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 15:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
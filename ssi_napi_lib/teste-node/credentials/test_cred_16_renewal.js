@@ -0,0 +1,154 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_cred_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 16: renovação de credencial (createRenewalOffer + replaces)");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, `SchemaRenewal_${Date.now()}`, `1.${Math.floor(Date.now() / 1000)}`, ["nome", "licenca", "validade"]
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_REN_${Math.floor(Date.now() / 1000)}`
+    );
+    const schemaObj = parseJsonSafe(
+      await fn(issuer, "fetchSchemaFromLedger", "fetch_schema_from_ledger")(genesisAbs, schemaId), "schema"
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    const createRequest = fn(holder, "createCredentialRequest", "create_credential_request");
+    const createCredential = fn(issuer, "createCredential", "create_credential");
+    const storeCredential = fn(holder, "storeCredential", "store_credential");
+    await issuer.setIssuedValuesPolicy("{}");
+
+    console.log("1) Emissão original (licença de piloto)...");
+    const offer1 = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-ren-${Date.now()}`);
+    const request1 = await createRequest("default", holderDid, credDefJson, offer1);
+    const cred1 = await createCredential(credDefId, offer1, request1,
+      JSON.stringify({ nome: "Alice", licenca: "PPL-123", validade: "2026-12-31" }));
+    const oldId = `cred-ren-old-${Date.now()}`;
+    await storeCredential(oldId, cred1, extractNonce(offer1), credDefJson, null);
+    const thread1 = extractNonce(offer1);
+
+    console.log("2) Issuer cria oferta de renovação com valores pré-preenchidos...");
+    const renewal = parseJsonSafe(await issuer.createRenewalOffer(thread1), "renewal");
+    assert(renewal.prefilled === true && renewal.values.licenca === "PPL-123", `valores não pré-preenchidos: ${JSON.stringify(renewal)}`);
+    assert(renewal.holderDid === holderDid && renewal.credDefId === credDefId, "renovação deveria manter holder e cred def");
+    assert(renewal.threadId === extractNonce(renewal.offerJson), "threadId deveria ser o nonce da nova oferta");
+    const ex = parseJsonSafe(await issuer.exchangeGet("issuer", renewal.threadId), "exchange");
+    assert(ex.state === "offer-sent" && ex.renews === thread1 && ex.counterpartyDid === holderDid, `troca inesperada: ${JSON.stringify(ex)}`);
+
+    const request2 = await createRequest("default", holderDid, credDefJson, renewal.offerJson);
+    const cred2 = await createCredential(credDefId, renewal.offerJson, request2,
+      JSON.stringify({ ...renewal.values, validade: "2028-12-31" }));
+    const newId = `cred-ren-new-${Date.now()}`;
+    await storeCredential(newId, cred2, renewal.threadId, credDefJson, null, JSON.stringify({ replaces: oldId }));
+
+    console.log("3) Registro de emissão liga as duas emissões...");
+    const issued = parseJsonSafe(await issuer.listIssuedCredentials(JSON.stringify({ credDefId })), "issued");
+    const r1 = issued.find((r) => r.id === thread1);
+    const r2 = issued.find((r) => r.id === renewal.threadId);
+    assert(r1.supersededBy === renewal.threadId && r2.renews === thread1, `elos de renovação ausentes: ${JSON.stringify(issued)}`);
+    let again = false;
+    try { await issuer.createRenewalOffer(thread1); } catch (e) { again = errCode(e) === "IssuedAlreadyRenewed"; }
+    assert(again, "renovar de novo a mesma emissão deveria falhar com IssuedAlreadyRenewed");
+    let unknown = false;
+    try { await issuer.createRenewalOffer("nao-existe"); } catch (e) { unknown = errCode(e) === "IssuedCredentialNotFound"; }
+    assert(unknown, "emissão inexistente deveria falhar com IssuedCredentialNotFound");
+
+    console.log("4) Holder: antiga some das listagens padrão, histórico preservado...");
+    for (const mode of ["compact", "full"]) {
+      const ids = parseJsonSafe(await fn(holder, "listCredentialsView", "list_credentials_view")(mode), mode).map((c) => c.id_local);
+      assert(ids.includes(newId) && !ids.includes(oldId), `modo ${mode} deveria esconder a substituída`);
+    }
+    const listed = parseJsonSafe(await fn(holder, "listCredentialsBy", "list_credentials_by")(schemaId, null), "by");
+    assert(listed.length === 1 && listed[0].id_local === newId, "listCredentialsBy deveria trazer só a nova");
+    const stored = parseJsonSafe(await fn(holder, "getStoredCredential", "get_stored_credential")(oldId), "old");
+    assert(stored, "substituída deveria continuar na wallet");
+
+    let history = parseJsonSafe(await holder.getCredentialHistory(oldId), "history");
+    assert(history.map((h) => `${h.id_local}:${h.status}`).join(",") === `${newId}:active,${oldId}:superseded`,
+      `histórico inesperado: ${JSON.stringify(history)}`);
+    assert(history[0].replaces === oldId && history[1].superseded_by === newId, "elos do histórico ausentes");
+
+    console.log("5) Apresentação não aceita a substituída...");
+    const presReq = {
+      nonce: String(Date.now()), name: "REN", version: "0.1",
+      requested_attributes: { a_lic: { name: "licenca" } }, requested_predicates: {},
+    };
+    const schemasMap = JSON.stringify({ [schemaId]: schemaObj });
+    const credDefsMap = JSON.stringify({ [credDefId]: parseJsonSafe(credDefJson, "credDef") });
+    const present = (credId) => fn(holder, "createPresentationV2", "create_presentation_v2")(
+      JSON.stringify(presReq),
+      JSON.stringify({ selection: [{ cred_id: credId, attributes: [{ referent: "a_lic", revealed: true }] }] }),
+      schemasMap, credDefsMap
+    );
+    let superseded = false;
+    try { await present(oldId); } catch (e) { superseded = errCode(e) === "CredentialSuperseded"; }
+    assert(superseded, "apresentar a substituída deveria falhar com CredentialSuperseded");
+    const pres = await present(newId);
+    assert((await fn(issuer, "verifyPresentation", "verify_presentation")(
+      JSON.stringify(presReq), pres, schemasMap, credDefsMap)) === true, "apresentação da nova deveria verificar");
+
+    console.log("6) Validações de replaces + rename mantém os elos...");
+    let twice = false;
+    try { await storeCredential(`x-${Date.now()}`, cred2, "x", credDefJson, null, JSON.stringify({ replaces: oldId })); }
+    catch (e) { twice = errCode(e) === "CredentialSuperseded"; }
+    assert(twice, "substituir de novo deveria falhar com CredentialSuperseded");
+    let missing = false;
+    try { await storeCredential(`x-${Date.now()}`, cred2, "x", credDefJson, null, JSON.stringify({ replaces: "nao-existe" })); }
+    catch (e) { missing = errCode(e) === "CredentialNotFound"; }
+    assert(missing, "replaces inexistente deveria falhar com CredentialNotFound");
+    let badOpts = false;
+    try { await storeCredential(`x-${Date.now()}`, cred2, "x", credDefJson, null, JSON.stringify({ replace: oldId })); }
+    catch (e) { badOpts = errCode(e) === "StoreOptionsInvalid"; }
+    assert(badOpts, "opção desconhecida deveria falhar com StoreOptionsInvalid");
+
+    const renamed = `${newId}-renomeada`;
+    await fn(holder, "renameStoredCredentialId", "rename_stored_credential_id")(newId, renamed, false);
+    history = parseJsonSafe(await holder.getCredentialHistory(oldId), "history renamed");
+    assert(history[0].id_local === renamed && history[1].superseded_by === renamed, "rename deveria atualizar os elos");
+
+    console.log("✅ OK: TESTE CRED 16 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 16:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,158 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  rmIfExists,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  parseJsonSafe,
+  walletCreateOpenIdempotent,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+function freshWallet(p) {
+  rmIfExists(p);
+  rmIfExists(p + ".sidecar");
+  rmIfExists(p + ".kdf.json");
+}
+
+const sleep = (ms) => new Promise((r) => setTimeout(r, ms));
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_cred_issuer.db");
+  // Holder novo: as contagens do purge dependem só das ofertas deste teste.
+  const holderDb = path.join(walletDir, "test_wallet_cred_offer_expiry.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE CRED 17: expiração de ofertas (OfferExpired / OfferAlreadyUsed / purgeExpiredOffers)");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+  freshWallet(holderDb);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, `SchemaOfferExp_${Date.now()}`, `1.${Math.floor(Date.now() / 1000)}`, ["nome"]
+    );
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_OEXP_${Math.floor(Date.now() / 1000)}`
+    );
+    const credDefJson = await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId);
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+
+    const createOffer = fn(issuer, "createCredentialOffer", "create_credential_offer");
+    const createRequest = fn(holder, "createCredentialRequest", "create_credential_request");
+    const createCredential = fn(issuer, "createCredential", "create_credential");
+    const storeReceivedOffer = fn(holder, "storeReceivedOffer", "store_received_offer");
+    const listOffers = async () => parseJsonSafe(await fn(issuer, "listCredentialOffers", "list_credential_offers")(), "offers");
+    const values = JSON.stringify({ nome: "Alice" });
+
+    console.log("1) Prazo padrão e oferta com ttl explícito...");
+    assert(JSON.parse(await holder.getOfferExpiryConfig()).ttlSecs === 7 * 86400, "prazo padrão deveria ser 7 dias");
+    const idA = `offer-exp-a-${Date.now()}`;
+    const offerA = await createOffer(credDefId, idA, 2);
+    const thA = extractNonce(offerA);
+    const exA = parseJsonSafe(await issuer.exchangeGet("issuer", thA), "exA");
+    const listedA = (await listOffers()).find((o) => o.id_local === idA);
+    assert(exA.expiresAt && String(exA.expiresAt) === listedA.expires_at, `prazo da oferta A inesperado: ${JSON.stringify(exA)}`);
+    const requestA = await createRequest("default", holderDid, credDefJson, offerA);
+
+    console.log("2) Oferta vencida não é emitida...");
+    await sleep(3000);
+    let expired = false;
+    try { await createCredential(credDefId, offerA, requestA, values); } catch (e) { expired = errCode(e) === "OfferExpired"; }
+    assert(expired, "emitir oferta vencida deveria falhar com OfferExpired");
+
+    console.log("3) Oferta consumida não é reutilizada...");
+    const offerB = await createOffer(credDefId, `offer-exp-b-${Date.now()}`);
+    const requestB = await createRequest("default", holderDid, credDefJson, offerB);
+    await createCredential(credDefId, offerB, requestB, values);
+    let issuedTwice = false;
+    try { await createCredential(credDefId, offerB, requestB, values); } catch (e) { issuedTwice = errCode(e) === "OfferAlreadyUsed"; }
+    assert(issuedTwice, "emitir duas vezes a mesma oferta deveria falhar com OfferAlreadyUsed");
+    let requestedTwice = false;
+    try { await createRequest("default", holderDid, credDefJson, offerB); } catch (e) { requestedTwice = errCode(e) === "OfferAlreadyUsed"; }
+    assert(requestedTwice, "segundo request para a mesma oferta deveria falhar com OfferAlreadyUsed");
+
+    console.log("4) Holder: prazo informado pelo emissor...");
+    const offerC = await createOffer(credDefId, `offer-exp-c-${Date.now()}`);
+    let badExpiry = false;
+    try { await storeReceivedOffer(offerC, "amanhã"); } catch (e) { badExpiry = errCode(e) === "OfferExpiryInvalid"; }
+    assert(badExpiry, "prazo ilegível deveria falhar com OfferExpiryInvalid");
+    await storeReceivedOffer(offerC, "2020-01-01T00:00:00Z");
+    const exC = parseJsonSafe(await holder.exchangeGet("holder", extractNonce(offerC)), "exC");
+    assert(exC.expiresAt === 1577836800, `prazo do holder inesperado: ${exC.expiresAt}`);
+    let holderExpired = false;
+    try { await createRequest("default", holderDid, credDefJson, offerC); } catch (e) { holderExpired = errCode(e) === "OfferExpired"; }
+    assert(holderExpired, "request de oferta vencida deveria falhar com OfferExpired");
+
+    console.log("5) DIDComm: ~timing.expires_time leva o prazo ao holder...");
+    const offerD = await createOffer(credDefId, `offer-exp-d-${Date.now()}`, 3600);
+    const thD = extractNonce(offerD);
+    const offerMsg = parseJsonSafe(await issuer.issueV2BuildOffer(offerD, null, null), "offerMsg");
+    const exD = parseJsonSafe(await issuer.exchangeGet("issuer", thD), "exD");
+    assert(offerMsg["~timing"].expires_time === new Date(exD.expiresAt * 1000).toISOString().replace(".000", ""),
+      `~timing inesperado: ${JSON.stringify(offerMsg["~timing"])}`);
+    const rOffer = parseJsonSafe(await holder.issueV2Receive(JSON.stringify(offerMsg)), "rOffer");
+    assert(rOffer.expiresAt === exD.expiresAt, "issueV2Receive deveria devolver o prazo da oferta");
+    await storeReceivedOffer(rOffer.offerJson);
+    const exDh = parseJsonSafe(await holder.exchangeGet("holder", thD), "exDh");
+    assert(exDh.expiresAt === exD.expiresAt, "storeReceivedOffer sem prazo deveria manter o do ~timing");
+
+    console.log("6) purgeExpiredOffers dos dois lados...");
+    const pi = parseJsonSafe(await issuer.purgeExpiredOffers(), "purge issuer");
+    assert(pi.issuerOffers >= 1 && pi.exchangesAbandoned >= 1, `purge do issuer inesperado: ${JSON.stringify(pi)}`);
+    const ids = (await listOffers()).map((o) => o.id_local);
+    assert(!ids.includes(idA), "oferta A vencida deveria ter sido apagada");
+    assert(ids.some((id) => id.startsWith("offer-exp-d-")), "oferta D no prazo deveria continuar");
+    const exA2 = parseJsonSafe(await issuer.exchangeGet("issuer", thA), "exA2");
+    const last = exA2.history[exA2.history.length - 1];
+    assert(exA2.state === "abandoned" && last.reason === "offer-expired", `troca A deveria ser abandonada: ${JSON.stringify(exA2)}`);
+    assert(parseJsonSafe(await issuer.exchangeGet("issuer", extractNonce(offerB)), "exB").state === "credential-issued",
+      "troca já emitida não deveria mudar");
+
+    const ph = parseJsonSafe(await holder.purgeExpiredOffers(), "purge holder");
+    assert(ph.issuerOffers === 0 && ph.receivedOffers === 1 && ph.exchangesAbandoned === 1,
+      `purge do holder inesperado: ${JSON.stringify(ph)}`);
+    const again = parseJsonSafe(await holder.purgeExpiredOffers(), "purge again");
+    assert(again.receivedOffers === 0, "segundo purge não deveria apagar nada");
+
+    console.log("7) Configuração do prazo padrão...");
+    await holder.setOfferExpiryConfig(JSON.stringify({ ttlSecs: 0 }));
+    const offerE = await createOffer(credDefId, `offer-exp-e-${Date.now()}`);
+    await storeReceivedOffer(offerE);
+    const exE = parseJsonSafe(await holder.exchangeGet("holder", extractNonce(offerE)), "exE");
+    assert(exE.expiresAt == null, "ttlSecs 0 deveria gravar oferta sem expiração");
+    await holder.setOfferExpiryConfig("{}");
+    assert(JSON.parse(await holder.getOfferExpiryConfig()).ttlSecs === 7 * 86400, "config vazia deveria voltar ao padrão");
+    let badCfg = false;
+    try { await holder.setOfferExpiryConfig(JSON.stringify({ ttl: 10 })); } catch (e) { badCfg = errCode(e) === "OfferExpiryConfigInvalid"; }
+    assert(badCfg, "config desconhecida deveria falhar com OfferExpiryConfigInvalid");
+
+    console.log("✅ OK: TESTE CRED 17 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch { }
+    try { await holder.walletClose(); } catch { }
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE CRED 17:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
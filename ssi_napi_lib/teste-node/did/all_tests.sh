@@ -9,3 +9,8 @@ RESET_WALLET=1 node ./teste-node/did/teste_did_05_list_dids_legacy.js
 RESET_WALLET=1 node ./teste-node/did/teste_did_06_resolve_v2_retry.js
 RESET_WALLET=1 node ./teste-node/did/teste_did_07_register_updates_local_record.js
 RESET_WALLET=1 node ./teste-node/did/teste_did_08_primary_did.js
+RESET_WALLET=1 node ./teste-node/did/teste_did_09_verify_contacts_ledger.js
+RESET_WALLET=1 node ./teste-node/did/teste_did_10_signed_batch.js
+RESET_WALLET=1 node ./teste-node/did/teste_did_11_retire_did.js
+RESET_WALLET=1 node ./teste-node/did/teste_did_12_set_ledger_role.js
+RESET_WALLET=1 node ./teste-node/did/teste_did_13_ledger_history.js
@@ -0,0 +1,170 @@
+// RESET_WALLET=1 node ./teste-node/did/teste_did_09_verify_contacts_ledger.js
+
+// teste-node/did/teste_did_09_verify_contacts_ledger.js
+const fs = require("fs");
+const http = require("http");
+
+const {
+  loadIndyAgent,
+  resetWalletArtifacts,
+  openOrCreateWallet,
+  assert,
+} = require("./_did_common");
+
+const IndyAgent = loadIndyAgent();
+
+// CONFIG (Von-Network local)
+const NETWORK_CONFIG = {
+  genesisUrl: "http://localhost:9000/genesis",
+  genesisFile: "/tmp/von_genesis.txn",
+};
+
+// Trustee / Steward padrão da Von-Network
+const TRUSTEE_SEED = "000000000000000000000000Trustee1";
+const TRUSTEE_DID = "V4SGRU86Z58d6TV7PBUe6f";
+const STEWARD_DID = "Th7MpTaRZVRYnPiabds81Y";
+
+function downloadGenesisHttp(url, dest) {
+  return new Promise((resolve, reject) => {
+    try {
+      if (fs.existsSync(dest)) fs.unlinkSync(dest);
+    } catch (_) {}
+
+    const file = fs.createWriteStream(dest);
+    console.log(`⏳ Baixando Genesis de: ${url}...`);
+
+    http
+      .get(url, (res) => {
+        if (res.statusCode !== 200) {
+          reject(new Error(`Erro HTTP: ${res.statusCode}`));
+          return;
+        }
+        res.pipe(file);
+        file.on("finish", () => {
+          file.close(() => {
+            console.log("✅ Genesis baixado.");
+            resolve(true);
+          });
+        });
+      })
+      .on("error", (err) => {
+        try {
+          fs.unlinkSync(dest);
+        } catch (_) {}
+        reject(err);
+      });
+  });
+}
+
+function safeJsonParse(s, label = "json") {
+  try {
+    return JSON.parse(s);
+  } catch (e) {
+    throw new Error(`${label}: JSON inválido: ${String(e)} | raw=${String(s).slice(0, 300)}`);
+  }
+}
+
+async function main() {
+  console.log("🚀 TESTE DID 09: verifyContactAgainstLedger / refreshExternalDids");
+
+  const dbA = "./wallet_did_09_a.db";
+  const dbB = "./wallet_did_09_b.db";
+  const pass = "pass_did_09";
+  const agentA = new IndyAgent(); // fornece verkeys reais
+  const agentB = new IndyAgent(); // wallet com os contatos
+
+  try {
+    if (process.env.RESET_WALLET === "1") {
+      console.log("🧹 RESET_WALLET=1: removendo wallet artifacts...");
+      resetWalletArtifacts(dbA);
+      resetWalletArtifacts(dbB);
+    }
+
+    await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, NETWORK_CONFIG.genesisFile);
+
+    // 1) Wallet A: verkey do Trustee + DIDs locais (fora do ledger)
+    console.log("1) Wallet A: importando Trustee e criando DIDs locais...");
+    await openOrCreateWallet(agentA, dbA, pass);
+    const [trusteeDid, trusteeVerkey] = await agentA.importDidFromSeed(TRUSTEE_SEED);
+    assert(trusteeDid === TRUSTEE_DID, "Seed gerou DID Trustee incorreto!");
+    const x = safeJsonParse(await agentA.createDidV2(JSON.stringify({ alias: "X", public: false })), "createDidV2(X)");
+    const y = safeJsonParse(await agentA.createDidV2(JSON.stringify({ alias: "Y", public: false })), "createDidV2(Y)");
+
+    // 2) Wallet B: contatos
+    console.log("2) Wallet B: salvando contatos...");
+    await openOrCreateWallet(agentB, dbB, pass);
+    await agentB.connectNetwork(NETWORK_CONFIG.genesisFile);
+    await agentB.storeTheirDid(TRUSTEE_DID, trusteeVerkey, "Trustee OK");
+    await agentB.storeTheirDid(STEWARD_DID, x.verkey, "Steward verkey errada");
+    await agentB.storeTheirDid(y.did, y.verkey, "Fora do ledger");
+
+    // 3) Verificação individual
+    console.log("3) verifyContactAgainstLedger...");
+    const ok = safeJsonParse(await agentB.verifyContactAgainstLedger(TRUSTEE_DID), "verify(trustee)");
+    assert(ok.ok === true, "verify(trustee): ok !== true");
+    assert(ok.ledgerCheck.status === "verified", `trustee: esperado verified, veio ${ok.ledgerCheck.status}`);
+    assert(typeof ok.ledgerCheck.checkedAt === "number", "trustee: checkedAt inválido");
+
+    const bad = safeJsonParse(await agentB.verifyContactAgainstLedger(STEWARD_DID), "verify(steward)");
+    assert(bad.ledgerCheck.status === "mismatch", `steward: esperado mismatch, veio ${bad.ledgerCheck.status}`);
+    assert(bad.ledgerCheck.mismatch.includes("verkey"), "steward: motivo verkey ausente");
+
+    const none = safeJsonParse(await agentB.verifyContactAgainstLedger(y.did), "verify(y)");
+    assert(none.ledgerCheck.status === "not_on_ledger", `y: esperado not_on_ledger, veio ${none.ledgerCheck.status}`);
+
+    // 4) Estado persistido no DidRecord
+    const rec = safeJsonParse(await agentB.getDid(STEWARD_DID), "getDid(steward)");
+    assert(rec.ledgerCheck && rec.ledgerCheck.status === "mismatch", "getDid: ledgerCheck não persistido");
+    assert(rec.alias === "Steward verkey errada", "getDid: alias não preservado");
+
+    // 5) Lote
+    console.log("4) refreshExternalDids...");
+    const batch = safeJsonParse(await agentB.refreshExternalDids(), "refreshExternalDids");
+    assert(batch.ok === true, "refresh: ok !== true");
+    assert(batch.total === 3, `refresh: total esperado 3, veio ${batch.total}`);
+    assert(batch.verified === 1 && batch.mismatch === 1 && batch.notOnLedger === 1, "refresh: contadores inesperados");
+
+    // 6) Envelope: contato em mismatch é recusado (default) e só avisado com policy=warn
+    console.log("5) envelopePackAuthcrypt para contato em mismatch...");
+    const me = safeJsonParse(await agentB.createDidV2(JSON.stringify({ alias: "Eu", public: false })), "createDidV2(me)");
+    let failed = false;
+    try {
+      await agentB.envelopePackAuthcrypt(me.did, x.verkey, "test", null, "oi", null, null);
+    } catch (e) {
+      failed = true;
+      const msg = String(e.message || e);
+      assert(msg.includes("ContactLedgerMismatch"), `código inesperado: ${msg}`);
+      console.log("   ✅ Recusado como esperado:", msg);
+    }
+    assert(failed === true, "pack deveria falhar para contato em mismatch");
+
+    let badPolicy = false;
+    try {
+      await agentB.setContactMismatchConfig(JSON.stringify({ policy: "ignore" }));
+    } catch (e) {
+      badPolicy = String(e.message || e).includes("ContactMismatchConfigInvalid");
+    }
+    assert(badPolicy, "policy desconhecida deveria falhar com ContactMismatchConfigInvalid");
+
+    await agentB.setContactMismatchConfig(JSON.stringify({ policy: "warn" }));
+    assert(safeJsonParse(await agentB.getContactMismatchConfig(), "config").policy === "warn", "config não salva");
+    const envStr = await agentB.envelopePackAuthcrypt(me.did, x.verkey, "test", null, "oi", null, null);
+    await agentB.setContactMismatchConfig("{}");
+    assert(safeJsonParse(await agentB.getContactMismatchConfig(), "config").policy === "refuse", "default deveria ser refuse");
+    const packed = safeJsonParse(envStr, "envelope");
+    assert(packed.crypto.mode === "authcrypt", "pack (warn) deveria funcionar");
+    assert(Array.isArray(packed.warnings) && packed.warnings.some((w) => w.includes("ledgerStatus=mismatch")),
+      "pack (warn) deveria devolver o aviso em warnings");
+
+    console.log("✅ OK: TESTE DID 09 passou.");
+  } catch (e) {
+    console.error("❌ ERRO:", e);
+  } finally {
+    console.log("🔒 Fechando wallets...");
+    try { await agentA.walletClose(); } catch (_) {}
+    try { await agentB.walletClose(); } catch (_) {}
+    console.log("👋 Fim.");
+  }
+}
+
+main();
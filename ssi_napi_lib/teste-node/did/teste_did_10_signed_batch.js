@@ -0,0 +1,138 @@
+// teste-node/did/teste_did_10_signed_batch.js
+const { loadIndyAgent, resetWalletArtifacts, openOrCreateWallet, assert } = require("./_did_common");
+
+function expectCode(err, code) {
+  const msg = String((err && err.message) || err);
+  let got = "";
+  try { got = JSON.parse(msg).code || ""; } catch (_) {}
+  assert(got === code || msg.includes(code), `esperado código ${code}, veio: ${msg}`);
+}
+
+async function main() {
+  console.log("🚀 TESTE DID 10: export/import batch assinado (A -> B)");
+
+  const IndyAgent = loadIndyAgent();
+  const agentA = new IndyAgent();
+  const agentB = new IndyAgent();
+  const agentC = new IndyAgent();
+
+  const dbA = "./wallet_did_10_A.db";
+  const dbB = "./wallet_did_10_B.db";
+  const dbC = "./wallet_did_10_C.db";
+  const passA = "pass_did_10_A";
+  const passB = "pass_did_10_B";
+  const passC = "pass_did_10_C";
+
+  try {
+    if (process.env.RESET_WALLET === "1") {
+      console.log("🧹 RESET_WALLET=1: removendo wallet artifacts...");
+      resetWalletArtifacts(dbA);
+      resetWalletArtifacts(dbB);
+      resetWalletArtifacts(dbC);
+    }
+
+    console.log("\n1) Wallet A + DID assinante...");
+    await openOrCreateWallet(agentA, dbA, passA);
+    const signer = JSON.parse(await agentA.createDidV2(JSON.stringify({ alias: "Vertiport A", public: false })));
+    assert(signer.ok === true, "createDidV2 signer ok");
+
+    await agentA.storeTheirDid("Th7MpTaRZVRYnPiabds81Y", "CnEDk9HrMnmiHXEV1WFgbVCRteYnPqsJwrTdcZaNhFVW", "A Ext 1");
+    await agentA.storeTheirDid("EbP4aYNeTHL6q385GuVpRV", "9wBBr1J6vZsV3pNQyQ6YwG4cB9hVtYVwQeGg1g2h3i4j", "A Ext 2");
+
+    console.log("2) Export assinado dos externos...");
+    const batch = JSON.parse(await agentA.exportDidsBatch(
+      JSON.stringify({ type: "external", limit: 1000, offset: 0 }),
+      signer.did
+    ));
+    assert(batch.signedBy && batch.signedBy.did === signer.did, "signedBy.did deve ser o signer");
+    assert(batch.signedBy.verkey === signer.verkey, "signedBy.verkey deve ser a verkey do signer");
+    assert(batch.signature && batch.signature.alg === "Ed25519", "signature.alg deve ser Ed25519");
+    assert(typeof batch.signature.value === "string" && batch.signature.value.length > 40, "signature.value inválida");
+
+    console.log("\n3) Wallet B...");
+    await openOrCreateWallet(agentB, dbB, passB);
+
+    console.log("4) Batch adulterado deve ser rejeitado...");
+    const tampered = JSON.parse(JSON.stringify(batch));
+    tampered.items[0].verkey = "9wBBr1J6vZsV3pNQyQ6YwG4cB9hVtYVwQeGg1g2h3i4j";
+    let failed = false;
+    try {
+      await agentB.importDidsBatch(JSON.stringify(tampered), "external");
+    } catch (e) {
+      failed = true;
+      expectCode(e, "DidBatchSignatureInvalid");
+      console.log("   ✅ Rejeitado como esperado");
+    }
+    assert(failed === true, "import de batch adulterado deveria falhar");
+
+    console.log("5) Batch sem assinatura + requireSignature=true deve falhar...");
+    const unsigned = JSON.parse(JSON.stringify(batch));
+    delete unsigned.signature;
+    delete unsigned.signedBy;
+    failed = false;
+    try {
+      await agentB.importDidsBatch(JSON.stringify(unsigned), "external", true);
+    } catch (e) {
+      failed = true;
+      expectCode(e, "DidBatchUnsigned");
+    }
+    assert(failed === true, "import sem assinatura deveria falhar com requireSignature");
+
+    console.log("6) Signer desconhecido (fora da wallet e do ledger)...");
+    failed = false;
+    try {
+      await agentB.importDidsBatch(JSON.stringify(batch), "external", true);
+    } catch (e) {
+      failed = true;
+      expectCode(e, "DidBatchSignerUnverified");
+    }
+    assert(failed === true, "signer não confirmado + requireSignature deveria falhar");
+
+    await openOrCreateWallet(agentC, dbC, passC);
+    const impC = JSON.parse(await agentC.importDidsBatch(JSON.stringify(batch), "external"));
+    assert(impC.signatureVerified === true && impC.signerVerified === false, "signer deveria vir unverified");
+    const extC = JSON.parse(await agentC.searchDids(JSON.stringify({ type: "external", limit: 1000, offset: 0 })));
+    assert(extC.every(x => x.origin === `imported_batch_unverified:${signer.did}`), "origin deveria marcar unverified");
+
+    // Signer conhecido com outra verkey: a verkey de signedBy não é aceita
+    const other = JSON.parse(await agentC.createDidV2(JSON.stringify({ alias: "Outra chave", public: false })));
+    await agentC.storeTheirDid(signer.did, other.verkey, "Vertiport A (outra chave)");
+    failed = false;
+    try {
+      await agentC.importDidsBatch(JSON.stringify(batch), "external");
+    } catch (e) {
+      failed = true;
+      expectCode(e, "DidBatchSignerMismatch");
+    }
+    assert(failed === true, "verkey divergente da wallet deveria falhar");
+
+    await agentB.storeTheirDid(signer.did, signer.verkey, "Vertiport A");
+
+    console.log("6.1) Import do batch íntegro (signer conhecido em B)...");
+    const imp = JSON.parse(await agentB.importDidsBatch(JSON.stringify(batch), "external", true));
+    assert(imp.ok === true, "import ok");
+    assert(imp.signatureVerified === true, "signatureVerified deve ser true");
+    assert(imp.signedBy === signer.did, "signedBy deve ser o signer");
+    assert(imp.signerVerified === true && imp.signerSource === "wallet", "signer deveria ser confirmado pela wallet");
+    assert(imp.imported === 2, `imported esperado 2, veio ${imp.imported}`);
+
+    console.log("7) origin = imported_batch:<did>...");
+    const extB = JSON.parse(await agentB.searchDids(JSON.stringify({ type: "external", limit: 1000, offset: 0 })));
+    assert(extB.length === 3, "B deve ter 2 externos + o signer");
+    assert(extB.filter(x => x.did !== signer.did).every(x => x.origin === `imported_batch:${signer.did}`),
+      "origin deve registrar o DID assinante");
+
+    console.log("✅ OK: TESTE DID 10 passou.");
+  } catch (e) {
+    console.error("❌ ERRO:", e);
+    process.exitCode = 1;
+  } finally {
+    console.log("🔒 Fechando wallets...");
+    try { await agentA.walletClose(); } catch (_) {}
+    try { await agentB.walletClose(); } catch (_) {}
+    try { await agentC.walletClose(); } catch (_) {}
+    console.log("👋 Fim.");
+  }
+}
+
+main();
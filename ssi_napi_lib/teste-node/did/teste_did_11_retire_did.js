@@ -0,0 +1,101 @@
+// RESET_WALLET=1 node ./teste-node/did/teste_did_11_retire_did.js
+// teste-node/did/teste_did_11_retire_did.js
+const {
+  loadIndyAgent,
+  resetWalletArtifacts,
+  openOrCreateWallet,
+  assert,
+} = require("./_did_common");
+
+const IndyAgent = loadIndyAgent();
+
+function safeJsonParse(s, label = "json") {
+  try { return JSON.parse(s); }
+  catch (e) {
+    throw new Error(`${label}: JSON inválido: ${String(e)} | raw=${String(s).slice(0, 300)}`);
+  }
+}
+
+async function expectCode(p, code, label) {
+  let failed = false;
+  try {
+    await p;
+  } catch (e) {
+    failed = true;
+    const msg = String(e.message || e);
+    assert(msg.includes(code), `${label}: esperado ${code}, veio ${msg}`);
+    console.log(`   ✅ ${label}: falhou como esperado (${code})`);
+  }
+  assert(failed === true, `${label}: deveria falhar com ${code}`);
+}
+
+async function main() {
+  console.log("🚀 TESTE DID 11: deactivateDid / archiveDid / deleteDid (local)");
+
+  const dbPath = "./wallet_did_11.db";
+  const pass = "pass_did_11";
+  const agent = new IndyAgent();
+
+  try {
+    if (process.env.RESET_WALLET === "1") {
+      console.log("🧹 RESET_WALLET=1: removendo wallet artifacts...");
+      resetWalletArtifacts(dbPath);
+    }
+
+    await openOrCreateWallet(agent, dbPath, pass);
+
+    console.log("1) Criando 3 DIDs locais...");
+    const mk = async (alias) =>
+      safeJsonParse(await agent.createDidV2(JSON.stringify({ alias, public: false })), `createDidV2(${alias})`);
+    const a = await mk("Retire A");
+    const b = await mk("Retire B");
+    const c = await mk("Retire C");
+
+    console.log("2) Guard do primary...");
+    await agent.setPrimaryDid(a.did);
+    await expectCode(agent.archiveDid(a.did), "DidIsPrimary", "archiveDid(primary)");
+    await expectCode(agent.deleteDid(a.did), "DidIsPrimary", "deleteDid(primary)");
+    await expectCode(agent.deactivateDid(a.did), "DidIsPrimary", "deactivateDid(primary)");
+
+    console.log("3) archiveDid(B)...");
+    const arch = safeJsonParse(await agent.archiveDid(b.did), "archiveDid");
+    assert(arch.ok === true && arch.status === "archived", "archiveDid: status inválido");
+
+    const def = safeJsonParse(await agent.searchDids(JSON.stringify({ type: "own" })), "searchDids(default)");
+    assert(!def.some(x => x.did === b.did), "archived não deve aparecer na busca default");
+    const onlyArch = safeJsonParse(await agent.searchDids(JSON.stringify({ type: "own", status: "archived" })), "searchDids(archived)");
+    assert(onlyArch.length === 1 && onlyArch[0].did === b.did, "status=archived deve retornar B");
+    await expectCode(agent.setPrimaryDid(b.did), "DidNotActive", "setPrimaryDid(archived)");
+
+    console.log("4) deactivateDid(C) local (sem ledger)...");
+    const deact = safeJsonParse(await agent.deactivateDid(c.did), "deactivateDid");
+    assert(deact.ok === true && deact.status === "deactivated", "deactivateDid: status inválido");
+    assert(deact.onLedger === false, "deactivateDid: DID local não deveria ir ao ledger");
+    const cRec = safeJsonParse(await agent.getDid(c.did), "getDid(C)");
+    assert(cRec.status === "deactivated" && typeof cRec.deactivatedAt === "number", "getDid(C): status não persistido");
+    assert(cRec.verkey === c.verkey, "getDid(C): verkey deve ser mantida");
+    await expectCode(agent.deactivateDid(c.did), "DidAlreadyDeactivated", "deactivateDid(de novo)");
+
+    console.log("5) deleteDid(B)...");
+    const d = await mk("Retire D");
+    await expectCode(agent.deleteDid(d.did), "DidNotRetired", "deleteDid(ativo)");
+    const del = safeJsonParse(await agent.deleteDid(b.did, true), "deleteDid");
+    assert(del.ok === true && del.deleted === true && del.keyRemoved === true, "deleteDid: retorno inválido");
+    const delC = safeJsonParse(await agent.deleteDid(c.did), "deleteDid(C)");
+    assert(delC.deleted === true && delC.keyRemoved === false, "deleteDid sem deleteKey não deveria apagar a chave");
+    let failed = false;
+    try { await agent.getDid(b.did); } catch (_) { failed = true; }
+    assert(failed === true, "getDid(B) deveria falhar após delete");
+
+    console.log("✅ OK: TESTE DID 11 passou.");
+  } catch (e) {
+    console.error("❌ ERRO:", e);
+    process.exitCode = 1;
+  } finally {
+    console.log("🔒 Fechando wallet...");
+    try { await agent.walletClose(); } catch (_) {}
+    console.log("👋 Fim.");
+  }
+}
+
+main();
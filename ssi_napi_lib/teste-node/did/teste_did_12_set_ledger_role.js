@@ -0,0 +1,138 @@
+// RESET_WALLET=1 node ./teste-node/did/teste_did_12_set_ledger_role.js
+
+// teste-node/did/teste_did_12_set_ledger_role.js
+const fs = require("fs");
+const http = require("http");
+
+const {
+  loadIndyAgent,
+  resetWalletArtifacts,
+  openOrCreateWallet,
+  assert,
+} = require("./_did_common");
+
+const IndyAgent = loadIndyAgent();
+
+// CONFIG (Von-Network local)
+const NETWORK_CONFIG = {
+  genesisUrl: "http://localhost:9000/genesis",
+  genesisFile: "/tmp/von_genesis.txn",
+};
+
+// Trustee padrão da Von-Network
+const TRUSTEE_SEED = "000000000000000000000000Trustee1";
+const TRUSTEE_DID = "V4SGRU86Z58d6TV7PBUe6f";
+
+function downloadGenesisHttp(url, dest) {
+  return new Promise((resolve, reject) => {
+    try {
+      if (fs.existsSync(dest)) fs.unlinkSync(dest);
+    } catch (_) {}
+
+    const file = fs.createWriteStream(dest);
+    console.log(`⏳ Baixando Genesis de: ${url}...`);
+
+    http
+      .get(url, (res) => {
+        if (res.statusCode !== 200) {
+          reject(new Error(`Erro HTTP: ${res.statusCode}`));
+          return;
+        }
+        res.pipe(file);
+        file.on("finish", () => {
+          file.close(() => {
+            console.log("✅ Genesis baixado.");
+            resolve(true);
+          });
+        });
+      })
+      .on("error", (err) => {
+        try {
+          fs.unlinkSync(dest);
+        } catch (_) {}
+        reject(err);
+      });
+  });
+}
+
+function safeJsonParse(s, label = "json") {
+  try {
+    return JSON.parse(s);
+  } catch (e) {
+    throw new Error(`${label}: JSON inválido: ${String(e)} | raw=${String(s).slice(0, 300)}`);
+  }
+}
+
+async function main() {
+  console.log("🚀 TESTE DID 12: setLedgerRole (atribuir/remover role)");
+
+  const dbPath = "./wallet_did_12.db";
+  const pass = "pass_did_12";
+  const agent = new IndyAgent();
+
+  try {
+    if (process.env.RESET_WALLET === "1") {
+      console.log("🧹 RESET_WALLET=1: removendo wallet artifacts...");
+      resetWalletArtifacts(dbPath);
+    }
+
+    await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, NETWORK_CONFIG.genesisFile);
+    await openOrCreateWallet(agent, dbPath, pass);
+
+    console.log("1) Importando Trustee + conectando...");
+    const [trusteeDid] = await agent.importDidFromSeed(TRUSTEE_SEED);
+    assert(trusteeDid === TRUSTEE_DID, "Seed gerou DID Trustee incorreto!");
+    await agent.connectNetwork(NETWORK_CONFIG.genesisFile);
+
+    console.log("2) Criando e registrando target como ENDORSER...");
+    const t = safeJsonParse(await agent.createDidV2(JSON.stringify({ alias: "DID-12 Target", public: false })), "createDidV2");
+    const reg = safeJsonParse(
+      await agent.registerDidOnLedger(NETWORK_CONFIG.genesisFile, TRUSTEE_DID, t.did, t.verkey, "ENDORSER"),
+      "registerDidOnLedger"
+    );
+    assert(reg.op === "REPLY", "registro deveria retornar REPLY");
+
+    console.log("3) Submitter sem role não pode alterar roles...");
+    let failed = false;
+    try {
+      await agent.setLedgerRole(t.did, TRUSTEE_DID, "none");
+    } catch (e) {
+      failed = true;
+      assert(String(e.message || e).includes("SubmitterRoleInsufficient"), `código inesperado: ${e.message}`);
+    }
+    assert(failed === true, "ENDORSER não deveria poder remover role de TRUSTEE");
+
+    console.log("4) Trustee remove o role (none)...");
+    const rm = safeJsonParse(await agent.setLedgerRole(TRUSTEE_DID, t.did, "none"), "setLedgerRole(none)");
+    assert(rm.ok === true, "setLedgerRole(none): ok !== true");
+    assert(rm.role === null, "setLedgerRole(none): role deveria ser null");
+    assert(rm.previousRole === "ENDORSER", `previousRole esperado ENDORSER, veio ${rm.previousRole}`);
+    assert(rm.localUpdated === true, "record local deveria ser atualizado");
+
+    const afterRm = safeJsonParse(await agent.getDid(t.did), "getDid(after none)");
+    assert(afterRm.role === null, "getDid: role deveria ser null");
+    const filt = safeJsonParse(await agent.searchDids(JSON.stringify({ type: "own", role: "none" })), "searchDids(role none)");
+    assert(filt.some(x => x.did === t.did), "tag role deveria ser none");
+
+    const res = safeJsonParse(await agent.resolveDidOnLedgerV2(t.did), "resolveDidOnLedgerV2");
+    assert(res.found === true && (res.roleName === null || res.roleName === undefined), "ledger ainda mostra role");
+
+    console.log("5) Trustee devolve ENDORSER...");
+    const set = safeJsonParse(await agent.setLedgerRole(TRUSTEE_DID, t.did, "ENDORSER"), "setLedgerRole(ENDORSER)");
+    assert(set.role === "ENDORSER", "setLedgerRole(ENDORSER): role inválido");
+    const afterSet = safeJsonParse(await agent.getDid(t.did), "getDid(after ENDORSER)");
+    assert(afterSet.role === "ENDORSER", "getDid: role deveria ser ENDORSER");
+    assert(afterSet.roleUpdatedBy === TRUSTEE_DID, "getDid: roleUpdatedBy inválido");
+
+    console.log("✅ OK: TESTE DID 12 passou.");
+  } catch (e) {
+    console.error("❌ ERRO:", e);
+    process.exitCode = 1;
+  } finally {
+    console.log("🔒 Fechando wallet...");
+    try { await agent.walletClose(); } catch (_) {}
+    console.log("👋 Fim.");
+  }
+}
+
+main();
@@ -0,0 +1,136 @@
+// RESET_WALLET=1 node ./teste-node/did/teste_did_13_ledger_history.js
+
+// teste-node/did/teste_did_13_ledger_history.js
+const fs = require("fs");
+const http = require("http");
+
+const {
+  loadIndyAgent,
+  resetWalletArtifacts,
+  openOrCreateWallet,
+  assert,
+} = require("./_did_common");
+
+const IndyAgent = loadIndyAgent();
+
+// CONFIG (Von-Network local)
+const NETWORK_CONFIG = {
+  genesisUrl: "http://localhost:9000/genesis",
+  genesisFile: "/tmp/von_genesis.txn",
+};
+
+// Trustee padrão da Von-Network
+const TRUSTEE_SEED = "000000000000000000000000Trustee1";
+const TRUSTEE_DID = "V4SGRU86Z58d6TV7PBUe6f";
+
+function downloadGenesisHttp(url, dest) {
+  return new Promise((resolve, reject) => {
+    try {
+      if (fs.existsSync(dest)) fs.unlinkSync(dest);
+    } catch (_) {}
+
+    const file = fs.createWriteStream(dest);
+    console.log(`⏳ Baixando Genesis de: ${url}...`);
+
+    http
+      .get(url, (res) => {
+        if (res.statusCode !== 200) {
+          reject(new Error(`Erro HTTP: ${res.statusCode}`));
+          return;
+        }
+        res.pipe(file);
+        file.on("finish", () => {
+          file.close(() => {
+            console.log("✅ Genesis baixado.");
+            resolve(true);
+          });
+        });
+      })
+      .on("error", (err) => {
+        try {
+          fs.unlinkSync(dest);
+        } catch (_) {}
+        reject(err);
+      });
+  });
+}
+
+function safeJsonParse(s, label = "json") {
+  try {
+    return JSON.parse(s);
+  } catch (e) {
+    throw new Error(`${label}: JSON inválido: ${String(e)} | raw=${String(s).slice(0, 300)}`);
+  }
+}
+
+async function main() {
+  console.log("🚀 TESTE DID 13: getDidLedgerHistory (seqNos locais + intervalo)");
+
+  const dbPath = "./wallet_did_13.db";
+  const pass = "pass_did_13";
+  const agent = new IndyAgent();
+
+  try {
+    if (process.env.RESET_WALLET === "1") {
+      console.log("🧹 RESET_WALLET=1: removendo wallet artifacts...");
+      resetWalletArtifacts(dbPath);
+    }
+
+    await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, NETWORK_CONFIG.genesisFile);
+    await openOrCreateWallet(agent, dbPath, pass);
+
+    console.log("1) Trustee + pool...");
+    const [trusteeDid] = await agent.importDidFromSeed(TRUSTEE_SEED);
+    assert(trusteeDid === TRUSTEE_DID, "Seed gerou DID Trustee incorreto!");
+    await agent.connectNetwork(NETWORK_CONFIG.genesisFile);
+
+    console.log("2) Registrando target (NYM) + ATTRIB...");
+    const t = safeJsonParse(await agent.createDidV2(JSON.stringify({ alias: "DID-13", public: false })), "createDidV2");
+    const reg = safeJsonParse(
+      await agent.registerDidOnLedger(NETWORK_CONFIG.genesisFile, TRUSTEE_DID, t.did, t.verkey, "ENDORSER"),
+      "registerDidOnLedger"
+    );
+    const nymSeq = reg.result.txnMetadata.seqNo;
+    const attr = safeJsonParse(await agent.writeAttribOnLedger(t.did, "endpoint", "http://vertiport.local"), "writeAttrib");
+    assert(attr.op === "REPLY", "ATTRIB deveria retornar REPLY");
+
+    console.log("3) Histórico só com seqNos locais...");
+    const h = safeJsonParse(await agent.getDidLedgerHistory(t.did), "getDidLedgerHistory");
+    assert(h.ok === true, "history: ok !== true");
+    assert(h.localSeqNos >= 2, `history: esperado >=2 seqNos locais, veio ${h.localSeqNos}`);
+    const nym = h.items.find(x => x.type === "NYM");
+    const att = h.items.find(x => x.type === "ATTRIB");
+    assert(nym && nym.seqNo === nymSeq && nym.relation === "subject", "history: NYM ausente/relação errada");
+    assert(nym.details.roleName === "ENDORSER", "history: NYM sem roleName ENDORSER");
+    assert(typeof nym.txnTime === "number", "history: txnTime inválido");
+    assert(att && att.relation === "self" && att.details.rawKeys.includes("endpoint"), "history: ATTRIB inválido");
+
+    console.log("4) Intervalo explícito + filtro de tipo...");
+    const r = safeJsonParse(
+      await agent.getDidLedgerHistory(t.did, JSON.stringify({ from: nymSeq, to: nymSeq + 5, types: ["NYM"] })),
+      "getDidLedgerHistory(range)"
+    );
+    assert(r.items.length >= 1 && r.items.every(x => x.type === "NYM"), "range: filtro de tipo falhou");
+
+    console.log("5) Intervalo grande demais...");
+    let failed = false;
+    try {
+      await agent.getDidLedgerHistory(t.did, JSON.stringify({ from: 1, to: 100000 }));
+    } catch (e) {
+      failed = true;
+      assert(String(e.message || e).includes("HistoryRangeTooLarge"), `código inesperado: ${e.message}`);
+    }
+    assert(failed === true, "intervalo grande deveria falhar");
+
+    console.log("✅ OK: TESTE DID 13 passou.");
+  } catch (e) {
+    console.error("❌ ERRO:", e);
+    process.exitCode = 1;
+  } finally {
+    console.log("🔒 Fechando wallet...");
+    try { await agent.walletClose(); } catch (_) {}
+    console.log("👋 Fim.");
+  }
+}
+
+main();
@@ -9,6 +9,7 @@ node teste-node/presentations/test_pres_03_negative_tamper_presentation.js
 node teste-node/presentations/test_pres_04_negative_mismatch_request.js
 node teste-node/presentations/test_pres_05_negative_missing_attribute.js
 node teste-node/presentations/test_pres_08_negative_create_presentation_v2_bad_referent.js
+node teste-node/presentations/test_pres_09_predicate_encoding.js
 
 # NOVO
 node teste-node/presentations/test_pres_06_build_requested_credentials_v1.js
@@ -0,0 +1,189 @@
+const path = require("path");
+const fs = require("fs");
+const {
+  NETWORK_CONFIG,
+  assert,
+  downloadGenesisHttp,
+  loadIndyAgent,
+  fn,
+  walletCreateOpenIdempotent,
+  parseJsonSafe,
+  extractNonce,
+  errCode,
+} = require("./_helpers");
+
+function nowSec() {
+  return Math.floor(Date.now() / 1000);
+}
+
+(async () => {
+  const IndyAgent = loadIndyAgent();
+
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const walletDir = path.join(__dirname, "..", "wallets");
+  fs.mkdirSync(walletDir, { recursive: true });
+
+  const issuerDb = process.env.WALLET_ISSUER || path.join(walletDir, "test_wallet_pres_issuer.db");
+  const holderDb = process.env.WALLET_HOLDER || path.join(walletDir, "test_wallet_pres_holder.db");
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE PRES 09: registro de codificação + predicados só sobre inteiros");
+  console.log("Config:", { issuerDb, holderDb, genesisAbs });
+
+  await downloadGenesisHttp(NETWORK_CONFIG.genesisUrl, genesisAbs);
+
+  const issuer = new IndyAgent();
+  const holder = new IndyAgent();
+  await walletCreateOpenIdempotent(issuer, issuerDb, pass);
+  await walletCreateOpenIdempotent(holder, holderDb, pass);
+
+  try {
+    await issuer.connectNetwork(genesisAbs);
+    await holder.connectNetwork(genesisAbs);
+
+    const [issuerDid] = await fn(issuer, "importDidFromSeed", "import_did_from_seed")(NETWORK_CONFIG.trusteeSeed);
+
+    const schemaName = `SchemaEncoding_${Date.now()}`;
+    const schemaVersion = `1.${nowSec()}`;
+    const attrs = ["nome", "cpf", "temperatura", "horas_voo", "validade", "emitido_em"];
+
+    console.log("1) Schema + tipos (int negativo, decimal, date, timestamp)...");
+    const schemaId = await fn(issuer, "createAndRegisterSchema", "create_and_register_schema")(
+      genesisAbs, issuerDid, schemaName, schemaVersion, attrs
+    );
+    const local = parseJsonSafe(await issuer.schemaSaveLocal(schemaName, schemaVersion, attrs, false, "test"), "local");
+    await issuer.schemaRegisterFromLocal(genesisAbs, local.id_local, issuerDid);
+    await issuer.schemaSetAttrTypes(local.id_local, JSON.stringify({
+      temperatura: { type: "int", min: -60, max: 60 },
+      horas_voo: { type: "decimal", scale: 1, min: "0" },
+      validade: { type: "date" },
+      emitido_em: { type: "timestamp" },
+    }));
+
+    let badScale = false;
+    try {
+      await issuer.schemaSetAttrTypes(local.id_local, JSON.stringify({ horas_voo: { type: "decimal", scale: 12 } }));
+    } catch (e) { badScale = errCode(e) === "AttrTypeInvalid"; }
+    assert(badScale, "scale > 6 deveria falhar com AttrTypeInvalid");
+
+    const credDefId = await fn(issuer, "createAndRegisterCredDef", "create_and_register_cred_def")(
+      genesisAbs, issuerDid, schemaId, `TAG_ENC_${nowSec()}`
+    );
+    const schemaLedgerObj = parseJsonSafe(
+      await fn(issuer, "fetchSchemaFromLedger", "fetch_schema_from_ledger")(genesisAbs, schemaId), "schema"
+    );
+    const credDefLedgerObj = parseJsonSafe(
+      await fn(issuer, "fetchCredDefFromLedger", "fetch_cred_def_from_ledger")(genesisAbs, credDefId), "credDef"
+    );
+
+    console.log("2) Emissão + armazenamento...");
+    await fn(holder, "createLinkSecret", "create_link_secret")("default");
+    const holderDid = JSON.parse(await fn(holder, "createDidV2", "create_did_v2")("{}")).did;
+    const offerJson = await fn(issuer, "createCredentialOffer", "create_credential_offer")(credDefId, `offer-enc-${Date.now()}`);
+    const requestJson = await fn(holder, "createCredentialRequest", "create_credential_request")(
+      "default", holderDid, JSON.stringify(credDefLedgerObj), offerJson
+    );
+    const credentialJson = await fn(issuer, "createCredential", "create_credential")(
+      credDefId, offerJson, requestJson, JSON.stringify({
+        nome: "Alice",
+        cpf: "12345678900",
+        temperatura: "-12",
+        horas_voo: "1520.5",
+        validade: "2030-06-15",
+        emitido_em: "2026-03-01T12:30:45Z",
+      })
+    );
+    const v = parseJsonSafe(credentialJson, "credential").values;
+    assert(v.temperatura.encoded === "48", `int negativo: esperado 48 (=-12+60), veio ${v.temperatura.encoded}`);
+    assert(v.horas_voo.raw === "1520.5" && v.horas_voo.encoded === "15205", "decimal escala 1 inesperado");
+    assert(v.emitido_em.encoded === String(Math.floor(Date.parse("2026-03-01T12:30:45Z") / 60000)), "timestamp não em minutos");
+
+    const credentialId = `cred-enc-${Date.now()}`;
+    await fn(holder, "storeCredential", "store_credential")(
+      credentialId, credentialJson, extractNonce(offerJson), JSON.stringify(credDefLedgerObj), null
+    );
+
+    console.log("3) credentialEncodingReport...");
+    const report = parseJsonSafe(await holder.credentialEncodingReport(credentialId), "report");
+    const byName = Object.fromEntries(report.attributes.map((a) => [a.name, a]));
+    assert(byName.nome.encoding === "hash" && !byName.nome.predicateReady, "nome deveria ser hash");
+    assert(byName.cpf.encoding === "integer_out_of_range" && !byName.cpf.predicateReady, "cpf excede i32");
+    assert(byName.temperatura.predicateReady && byName.temperatura.offset === -60, "offset de temperatura ausente");
+    assert(byName.horas_voo.encoding === "decimal" && byName.horas_voo.scale === 1, "decimal não detectado");
+    assert(byName.validade.encoding === "date", "date não detectado");
+    assert(byName.emitido_em.encoding === "timestamp", "timestamp não detectado");
+    assert(report.notPredicateReady.sort().join(",") === "cpf,nome", `notPredicateReady inesperado: ${report.notPredicateReady}`);
+
+    let notFound = false;
+    try { await holder.credentialEncodingReport("nao-existe"); } catch (e) { notFound = errCode(e) === "CredentialNotFound"; }
+    assert(notFound, "credencial inexistente deveria falhar com CredentialNotFound");
+
+    console.log("4) Predicado sobre atributo hash é recusado antes do AnonCreds...");
+    const schemasMap = { [schemaId]: schemaLedgerObj };
+    const credDefsMap = { [credDefId]: credDefLedgerObj };
+    const createPresentation = fn(holder, "createPresentation", "create_presentation");
+
+    const badReq = {
+      nonce: String(nowSec() * 1000000 + 1),
+      name: "PredHash",
+      version: "0.1",
+      requested_attributes: {},
+      requested_predicates: { p1: { name: "nome", p_type: ">=", p_value: 1 } },
+    };
+    let refused = false;
+    try {
+      await createPresentation(
+        JSON.stringify(badReq),
+        JSON.stringify({ requested_attributes: {}, requested_predicates: { p1: { cred_id: credentialId } } }),
+        JSON.stringify(schemasMap),
+        JSON.stringify(credDefsMap)
+      );
+    } catch (e) { refused = errCode(e) === "PredicateAttrNotInteger"; }
+    assert(refused, "predicado sobre hash deveria falhar com PredicateAttrNotInteger");
+
+    console.log("5) Predicados de data e de inteiro negativo com limiar codificado...");
+    const minValidade = await issuer.encodingPredicateValue(JSON.stringify({ type: "date" }), "2030-01-01");
+    const minTemp = await issuer.encodingPredicateValue(JSON.stringify({ type: "int", min: -60, max: 60 }), "-20");
+    assert(minTemp === 40, `limiar de temperatura codificado inesperado: ${minTemp}`);
+    let overflow = null;
+    try { await issuer.encodingPredicateValue(JSON.stringify({ type: "int", min: -1 }), "9223372036854775807"); }
+    catch (e) { overflow = errCode(e); }
+    assert(overflow === "AttrValueInvalid", `limiar fora de i64 deveria falhar com AttrValueInvalid, veio ${overflow}`);
+    let badType = null;
+    try { await issuer.encodingPredicateValue(JSON.stringify({ type: "decimal", scale: 99 }), "1"); }
+    catch (e) { badType = errCode(e); }
+    assert(badType === "AttrTypeInvalid", `tipo fora dos limites deveria falhar com AttrTypeInvalid, veio ${badType}`);
+
+    const okReq = {
+      nonce: String(nowSec() * 1000000 + 2),
+      name: "PredOk",
+      version: "0.1",
+      requested_attributes: {},
+      requested_predicates: {
+        p_val: { name: "validade", p_type: ">=", p_value: minValidade },
+        p_temp: { name: "temperatura", p_type: ">=", p_value: minTemp },
+      },
+    };
+    const presentationJson = await createPresentation(
+      JSON.stringify(okReq),
+      JSON.stringify({
+        requested_attributes: {},
+        requested_predicates: { p_val: { cred_id: credentialId }, p_temp: { cred_id: credentialId } },
+      }),
+      JSON.stringify(schemasMap),
+      JSON.stringify(credDefsMap)
+    );
+    const ok = await fn(issuer, "verifyPresentation", "verify_presentation")(
+      JSON.stringify(okReq), presentationJson, JSON.stringify(schemasMap), JSON.stringify(credDefsMap)
+    );
+    assert(ok === true, "apresentação com predicados codificados deveria verificar");
+
+    console.log("✅ OK: TESTE PRES 09 passou.");
+  } finally {
+    try { await issuer.walletClose(); } catch {}
+    try { await holder.walletClose(); } catch {}
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE PRES 09:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -40,10 +40,16 @@ function rmIfExists(p) {
   try { fs.rmSync(p, { recursive: true, force: true }); } catch {}
 }
 
+// Código de erro estruturado (napi_err: { ok:false, code, message }); null se ausente.
+function errCode(e) {
+  try { return JSON.parse(e.message).code; } catch { return null; }
+}
+
 module.exports = {
   loadIndyAgent,
   assert,
   parseJsonSafe,
   ensureFileExists,
   rmIfExists,
+  errCode,
 };
@@ -20,6 +20,16 @@ echo "🧪 SCHEMA 04 (local filters)"
 WALLET_PATH="$WALLET_PATH_LOCAL" node teste-node/schemas/test_schema_04_local_filters.js
 echo "✅ PASSOU SCHEMA 04"
 
+# Teste 05 (versionamento local)
+echo "🧪 SCHEMA 05 (versioning)"
+WALLET_PATH="$WALLET_PATH_LOCAL" node teste-node/schemas/test_schema_05_versioning.js
+echo "✅ PASSOU SCHEMA 05"
+
+# Teste 06 (catálogo de templates UAM)
+echo "🧪 SCHEMA 06 (templates)"
+WALLET_PATH="$WALLET_PATH_LOCAL" node teste-node/schemas/test_schema_06_templates.js
+echo "✅ PASSOU SCHEMA 06"
+
 # Teste 02 (ledger)
 echo "🧪 SCHEMA 02 (ledger smoke)"
 WALLET_PATH="$WALLET_PATH_LEDGER" node teste-node/schemas/test_schema_02_ledger_smoke.js
@@ -30,5 +40,10 @@ echo "🧪 SCHEMA 03 (ledger fetch negative)"
 WALLET_PATH="$WALLET_PATH_LEDGER" node teste-node/schemas/test_schema_03_ledger_fetch_negative.js
 echo "✅ PASSOU SCHEMA 03"
 
+# Teste 07 (import do ledger)
+echo "🧪 SCHEMA 07 (import from ledger)"
+WALLET_PATH="$WALLET_PATH_LEDGER" node teste-node/schemas/test_schema_07_import_from_ledger.js
+echo "✅ PASSOU SCHEMA 07"
+
 echo "✅ SUÍTE SCHEMAS completa."
 
@@ -0,0 +1,105 @@
+const path = require("path");
+const { errCode } = require("./_helpers");
+
+let IndyAgent;
+try {
+  IndyAgent = require(path.join(process.cwd(), "index.js")).IndyAgent;
+} catch {
+  IndyAgent = require(path.join(process.cwd(), "index.node")).IndyAgent;
+}
+
+function assert(cond, msg) {
+  if (!cond) throw new Error(msg || "assertion failed");
+}
+
+function parseJsonSafe(s, label = "json") {
+  try { return JSON.parse(s); } catch (e) {
+    throw new Error(`Falha ao parsear ${label}: ${e.message}`);
+  }
+}
+
+async function expectCode(p, code, label) {
+  try {
+    await p;
+  } catch (e) {
+    assert(errCode(e) === code, `${label}: esperado ${code}, veio ${e.message}`);
+    return;
+  }
+  throw new Error(`${label}: deveria falhar com ${code}`);
+}
+
+(async () => {
+  const agent = new IndyAgent();
+
+  const dbPath = process.env.WALLET_PATH || path.join(__dirname, "..", "wallets", "test_wallet_schema_01.db");
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const name = `SchemaVer_${Date.now()}`;
+
+  console.log("🚀 TESTE SCHEMA 05: versionamento (nextVersion + lineage)");
+  console.log("Config:", { dbPath, name });
+
+  await agent.walletOpen(dbPath, pass);
+
+  const created = [];
+  try {
+    const v1 = parseJsonSafe(await agent.schemaSaveLocal(name, "1.0", ["nome", "cpf"], true, "env1"), "v1");
+    created.push(v1.id_local);
+
+    // 1) minor: adiciona atributo
+    const v11 = parseJsonSafe(
+      await agent.schemaCreateNextVersion(v1.id_local, JSON.stringify({ addAttrs: ["email"] })),
+      "v1.1"
+    );
+    created.push(v11.id_local);
+    assert(v11.version === "1.1", `versão esperada 1.1, veio ${v11.version}`);
+    assert(v11.supersedes === v1.id_local, "supersedes não aponta para v1");
+    assert(v11.attr_names.join(",") === "nome,cpf,email", `attrs inesperados: ${v11.attr_names}`);
+    assert(v11.final_attr_names.includes("root_merkle_L"), "revocable não preservado");
+
+    // 2) major: remove atributo
+    const v2 = parseJsonSafe(
+      await agent.schemaCreateNextVersion(v11.id_local, JSON.stringify({ removeAttrs: ["cpf"], bump: "major" })),
+      "v2.0"
+    );
+    created.push(v2.id_local);
+    assert(v2.version === "2.0", `versão esperada 2.0, veio ${v2.version}`);
+    assert(!v2.attr_names.includes("cpf"), "cpf deveria ter sido removido");
+
+    // 3) negativos
+    await expectCode(agent.schemaCreateNextVersion(v1.id_local, null), "SchemaAlreadySuperseded", "v1 já sucedido");
+    await expectCode(
+      agent.schemaCreateNextVersion(v2.id_local, JSON.stringify({ addAttrs: ["seed"] })),
+      "SchemaAttrsInvalid",
+      "atributo reservado"
+    );
+    await expectCode(
+      agent.schemaCreateNextVersion(v2.id_local, JSON.stringify({ removeAttrs: ["inexistente"] })),
+      "SchemaAttrNotFound",
+      "remover inexistente"
+    );
+    await expectCode(
+      agent.schemaCreateNextVersion(v2.id_local, JSON.stringify({ bump: "patch" })),
+      "SchemaBumpInvalid",
+      "bump inválido"
+    );
+
+    // 4) lineage
+    const lin = parseJsonSafe(await agent.schemaLineage(name), "lineage");
+    assert(lin.count === 3, `lineage esperado 3 versões, veio ${lin.count}`);
+    assert(lin.versions.map((v) => v.version).join(",") === "1.0,1.1,2.0", "ordem de versões inesperada");
+    assert(lin.latest === v2.id_local, "latest deveria ser v2");
+    assert(lin.versions[0].supersededBy === v11.id_local, "supersededBy de v1 inesperado");
+    assert(lin.versions[1].addedAttrs.join(",") === "email", "addedAttrs de v1.1 inesperado");
+    assert(lin.versions[2].removedAttrs.join(",") === "cpf", "removedAttrs de v2.0 inesperado");
+
+    console.log("✅ OK: TESTE SCHEMA 05 passou.");
+  } finally {
+    for (const id of created) {
+      try { await agent.schemaDeleteLocal(id); } catch (_) {}
+    }
+    await agent.walletClose();
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE SCHEMA 05:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,92 @@
+const path = require("path");
+const { errCode } = require("./_helpers");
+
+let IndyAgent;
+try {
+  IndyAgent = require(path.join(process.cwd(), "index.js")).IndyAgent;
+} catch {
+  IndyAgent = require(path.join(process.cwd(), "index.node")).IndyAgent;
+}
+
+function assert(cond, msg) {
+  if (!cond) throw new Error(msg || "assertion failed");
+}
+
+function parseJsonSafe(s, label = "json") {
+  try { return JSON.parse(s); } catch (e) {
+    throw new Error(`Falha ao parsear ${label}: ${e.message}`);
+  }
+}
+
+async function expectCode(p, code, label) {
+  try {
+    await p;
+  } catch (e) {
+    assert(errCode(e) === code, `${label}: esperado ${code}, veio ${e.message}`);
+    return;
+  }
+  throw new Error(`${label}: deveria falhar com ${code}`);
+}
+
+(async () => {
+  const agent = new IndyAgent();
+
+  const dbPath = process.env.WALLET_PATH || path.join(__dirname, "..", "wallets", "test_wallet_schema_01.db");
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const envLabel = `tpl_${Date.now()}`;
+
+  console.log("🚀 TESTE SCHEMA 06: catálogo de templates UAM");
+  console.log("Config:", { dbPath, envLabel });
+
+  await agent.walletOpen(dbPath, pass);
+
+  const created = [];
+  try {
+    // 1) catálogo
+    const cat = parseJsonSafe(agent.schemaTemplatesList(), "catálogo");
+    const ids = cat.templates.map((t) => t.templateId);
+    for (const id of [
+      "uam.pilot_license.v1",
+      "uam.evtol_airworthiness.v1",
+      "uam.operator_certificate.v1",
+      "uam.vertiport_slot.v1",
+      "uam.maintenance_release.v1",
+    ]) {
+      assert(ids.includes(id), `template ausente no catálogo: ${id}`);
+    }
+    assert(typeof cat.catalogVersion === "string" && cat.catalogVersion.length > 0, "catalogVersion ausente");
+
+    // 2) instancia todos
+    for (const t of cat.templates) {
+      const r = parseJsonSafe(await agent.schemaInstantiateTemplate(t.templateId, envLabel), "instantiate");
+      assert(r.created === true, `template ${t.templateId} deveria ser criado`);
+      const rec = parseJsonSafe(r.json, "record");
+      created.push(rec.id_local);
+      assert(rec.name === t.name && rec.version === t.version, "nome/versão divergentes do template");
+      assert(rec.attr_names.join(",") === t.attrNames.join(","), "atributos divergentes do template");
+      assert(rec.template_id === t.templateId, "template_id não gravado");
+      assert(rec.env === envLabel, "env não aplicado");
+    }
+
+    // 3) idempotência
+    const again = parseJsonSafe(await agent.schemaInstantiateTemplate("uam.pilot_license.v1", envLabel), "again");
+    assert(again.created === false, "segunda instância deveria reaproveitar o schema existente");
+    assert(created.includes(parseJsonSafe(again.json, "again.json").id_local), "id_local divergente na reinstância");
+
+    const listed = await agent.schemaListLocal(false, envLabel, null);
+    assert(listed.length === cat.templates.length, `esperado ${cat.templates.length} schemas em ${envLabel}`);
+
+    // 4) template inexistente
+    await expectCode(agent.schemaInstantiateTemplate("uam.nao_existe.v1", envLabel), "SchemaTemplateNotFound", "template inválido");
+
+    console.log("✅ OK: TESTE SCHEMA 06 passou.");
+  } finally {
+    for (const id of created) {
+      try { await agent.schemaDeleteLocal(id); } catch (_) {}
+    }
+    await agent.walletClose();
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE SCHEMA 06:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});
@@ -0,0 +1,106 @@
+const fs = require("fs");
+const path = require("path");
+const { errCode } = require("./_helpers");
+
+const NETWORK_CONFIG = {
+  genesisFile: "./von_genesis.txn",
+  trusteeSeed: "000000000000000000000000Trustee1",
+  trusteeDid: "V4SGRU86Z58d6TV7PBUe6f",
+};
+
+let IndyAgent;
+try {
+  IndyAgent = require(path.join(process.cwd(), "index.js")).IndyAgent;
+} catch {
+  IndyAgent = require(path.join(process.cwd(), "index.node")).IndyAgent;
+}
+
+function assert(cond, msg) {
+  if (!cond) throw new Error(msg || "assertion failed");
+}
+
+function parseJsonSafe(s, label = "json") {
+  try { return JSON.parse(s); } catch (e) {
+    throw new Error(`Falha ao parsear ${label}: ${e.message}`);
+  }
+}
+
+const CONTROL_ATTRS = ["seed", "start_time", "unit_of_time", "time_window", "root_merkle_L"];
+
+(async () => {
+  const issuer = new IndyAgent();
+  const verifier = new IndyAgent();
+
+  const dbPath = process.env.WALLET_PATH || path.join(__dirname, "..", "wallets", "test_wallet_schema_ledger.db");
+  const verifierDb = path.join(__dirname, "..", "wallets", "test_wallet_schema_import.db");
+  const pass = process.env.WALLET_PASS || "minha_senha_teste";
+  const genesisAbs = path.join(process.cwd(), NETWORK_CONFIG.genesisFile);
+
+  console.log("🚀 TESTE SCHEMA 07: importar schema estrangeiro do ledger");
+  console.log("Config:", { dbPath, verifierDb, genesisAbs });
+
+  assert(fs.existsSync(genesisAbs), "Genesis ausente.");
+
+  // Emissor publica dois schemas: simples e com layout revogável
+  await issuer.walletOpen(dbPath, pass);
+  await issuer.connectNetwork(genesisAbs);
+  let plainId;
+  let revId;
+  try {
+    const [did] = await issuer.importDidFromSeed(NETWORK_CONFIG.trusteeSeed);
+    const ver = `1.${Math.floor(Date.now() / 1000)}`;
+    plainId = await issuer.createAndRegisterSchema(genesisAbs, did, `SchemaForeign_${Date.now()}`, ver, ["nome", "cpf"]);
+    revId = await issuer.createAndRegisterSchema(
+      genesisAbs, did, `SchemaForeignRev_${Date.now()}`, ver, ["nome", "licenca", ...CONTROL_ATTRS]
+    );
+  } finally {
+    await issuer.walletClose();
+  }
+
+  // Verificador (wallet separada) adota os schemas
+  for (const f of [verifierDb, `${verifierDb}.kdf.json`]) {
+    try { fs.rmSync(f, { force: true }); } catch {}
+  }
+  await verifier.walletCreate(verifierDb, pass);
+  await verifier.walletOpen(verifierDb, pass);
+  await verifier.connectNetwork(genesisAbs);
+
+  try {
+    console.log("1) import simples...");
+    const r1 = parseJsonSafe(await verifier.schemaImportFromLedger(plainId, null), "r1");
+    assert(r1.imported === true && r1.seqNo > 0, "import simples falhou");
+    const rec1 = parseJsonSafe(r1.json, "rec1");
+    assert(rec1.on_ledger === true && rec1.schema_id === plainId, "registro sem on_ledger/schema_id");
+    assert(rec1.issuer_did === NETWORK_CONFIG.trusteeDid, "issuer_did estrangeiro não gravado");
+    assert(rec1.revocable === false, "schema simples não deveria ser revogável");
+    assert(rec1.attr_names.slice().sort().join(",") === "cpf,nome", `attrs inesperados: ${rec1.attr_names}`);
+
+    console.log("2) import com layout revogável...");
+    const r2 = parseJsonSafe(await verifier.schemaImportFromLedger(revId, "partner"), "r2");
+    const rec2 = parseJsonSafe(r2.json, "rec2");
+    assert(rec2.revocable === true, "layout CONTROL_ATTRS não detectado");
+    assert(rec2.attr_names.slice().sort().join(",") === "licenca,nome", `attrs de usuário inesperados: ${rec2.attr_names}`);
+    assert(CONTROL_ATTRS.every((c) => rec2.final_attr_names.includes(c)), "final_attr_names sem CONTROL_ATTRS");
+    assert(rec2.env === "partner", "env não aplicado");
+
+    console.log("3) idempotência + listagem...");
+    const again = parseJsonSafe(await verifier.schemaImportFromLedger(plainId, null), "again");
+    assert(again.imported === false, "reimport deveria reaproveitar o registro");
+    const listed = (await verifier.schemaListLocal(true, null, null)).map((s) => parseJsonSafe(s, "item"));
+    assert(listed.some((x) => x.schema_id === plainId) && listed.some((x) => x.schema_id === revId), "importados não listados");
+
+    console.log("4) schema inexistente...");
+    let missing = false;
+    try {
+      await verifier.schemaImportFromLedger(`${NETWORK_CONFIG.trusteeDid}:2:NaoExiste_${Date.now()}:1.0`, null);
+    } catch (e) { missing = errCode(e) === "SchemaNotOnLedger"; }
+    assert(missing, "schema inexistente deveria falhar com SchemaNotOnLedger");
+
+    console.log("✅ OK: TESTE SCHEMA 07 passou.");
+  } finally {
+    await verifier.walletClose();
+  }
+})().catch((e) => {
+  console.error("❌ FALHA TESTE SCHEMA 07:", e && e.stack ? e.stack : e);
+  process.exit(1);
+});